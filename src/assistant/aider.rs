@@ -0,0 +1,84 @@
+use super::{AssistantBackend, AssistantCommand, AssistantPrompt};
+use crate::litellm_config::LiteLLMConfig;
+
+/// Model used when none is configured, matching the team's `v aider` helper
+pub const DEFAULT_AIDER_MODEL: &str = "openai/gpt-4o";
+
+/// Aider, talking to the LiteLLM proxy's OpenAI-compatible `/v1` endpoint
+#[derive(Debug, Clone, Default)]
+pub struct AiderBackend {
+    pub model: Option<String>,
+}
+
+impl AssistantBackend for AiderBackend {
+    fn name(&self) -> &str {
+        "aider"
+    }
+
+    fn build_command(&self, prompt: &AssistantPrompt, litellm: &LiteLLMConfig) -> AssistantCommand {
+        let model = self.model.as_deref().unwrap_or(DEFAULT_AIDER_MODEL);
+        let command = AssistantCommand::new("aider")
+            .env(
+                "OPENAI_API_BASE",
+                format!("{}/v1", litellm.url.trim_end_matches('/')),
+            )
+            .secret("OPENAI_API_KEY")
+            .arg("--model")
+            .arg(model);
+
+        match prompt {
+            AssistantPrompt::Text(text) => command.arg("--message").arg(text.as_str()),
+            AssistantPrompt::File(path) => command
+                .arg("--message-file")
+                .arg(path.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::ShellDialect;
+
+    fn test_config() -> LiteLLMConfig {
+        LiteLLMConfig {
+            url: "https://litellm.test/".to_string(),
+            ..LiteLLMConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_build_command_uses_default_model() {
+        let backend = AiderBackend::default();
+        let command =
+            backend.build_command(&AssistantPrompt::Text("Fix it".to_string()), &test_config());
+
+        assert_eq!(
+            command.render(ShellDialect::Posix),
+            "OPENAI_API_BASE=https://litellm.test/v1 aider --model openai/gpt-4o --message 'Fix it'"
+        );
+        assert_eq!(command.secrets, vec!["OPENAI_API_KEY"]);
+        assert_eq!(backend.name(), "aider");
+    }
+
+    #[test]
+    fn test_build_command_with_prompt_file() {
+        let backend = AiderBackend {
+            model: Some("openai/o4-mini".to_string()),
+        };
+        let command = backend.build_command(
+            &AssistantPrompt::File(".zzz/task-1/prompt-commander-1.md".into()),
+            &test_config(),
+        );
+
+        assert_eq!(
+            command.args,
+            vec![
+                "--model",
+                "openai/o4-mini",
+                "--message-file",
+                ".zzz/task-1/prompt-commander-1.md"
+            ]
+        );
+    }
+}
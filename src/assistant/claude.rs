@@ -0,0 +1,71 @@
+use super::{AssistantBackend, AssistantCommand, AssistantPrompt};
+use crate::litellm_config::LiteLLMConfig;
+
+/// Claude Code CLI, pointed at the LiteLLM proxy through the Anthropic env vars
+#[derive(Debug, Clone, Default)]
+pub struct ClaudeBackend {
+    pub model: Option<String>,
+}
+
+impl AssistantBackend for ClaudeBackend {
+    fn name(&self) -> &str {
+        "claude"
+    }
+
+    fn build_command(&self, prompt: &AssistantPrompt, litellm: &LiteLLMConfig) -> AssistantCommand {
+        let mut command = AssistantCommand::new("claude")
+            .env("ANTHROPIC_BASE_URL", litellm.url.as_str())
+            .secret("ANTHROPIC_AUTH_TOKEN");
+
+        if let Some(model) = &self.model {
+            command = command.arg("--model").arg(model.as_str());
+        }
+
+        command.arg(prompt.as_inline_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::ShellDialect;
+
+    fn test_config() -> LiteLLMConfig {
+        LiteLLMConfig {
+            url: "https://litellm.test".to_string(),
+            ..LiteLLMConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_build_command() {
+        let backend = ClaudeBackend {
+            model: Some("sonnet".to_string()),
+        };
+        let command = backend.build_command(
+            &AssistantPrompt::Text("Review it".to_string()),
+            &test_config(),
+        );
+
+        assert_eq!(
+            command.render(ShellDialect::Posix),
+            "ANTHROPIC_BASE_URL=https://litellm.test claude --model sonnet 'Review it'"
+        );
+        assert_eq!(command.secrets, vec!["ANTHROPIC_AUTH_TOKEN"]);
+        assert_eq!(backend.name(), "claude");
+    }
+
+    #[test]
+    fn test_prompt_file_becomes_instruction() {
+        let backend = ClaudeBackend::default();
+        let command = backend.build_command(
+            &AssistantPrompt::File(".zzz/task-1/prompt-overseer-1.md".into()),
+            &test_config(),
+        );
+
+        assert_eq!(
+            command.args,
+            vec!["Follow the instructions in .zzz/task-1/prompt-overseer-1.md"]
+        );
+    }
+}
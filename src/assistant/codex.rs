@@ -0,0 +1,72 @@
+use super::{AssistantBackend, AssistantCommand, AssistantPrompt};
+use crate::litellm_config::LiteLLMConfig;
+
+/// OpenAI Codex CLI, pointed at the LiteLLM proxy through the OpenAI env vars
+#[derive(Debug, Clone, Default)]
+pub struct CodexBackend {
+    pub model: Option<String>,
+}
+
+impl AssistantBackend for CodexBackend {
+    fn name(&self) -> &str {
+        "codex"
+    }
+
+    fn build_command(&self, prompt: &AssistantPrompt, litellm: &LiteLLMConfig) -> AssistantCommand {
+        let mut command = AssistantCommand::new("codex")
+            .env("OPENAI_BASE_URL", litellm.url.as_str())
+            .secret("OPENAI_API_KEY")
+            .arg("--quiet");
+
+        if let Some(model) = &self.model {
+            command = command.arg("--model").arg(model.as_str());
+        }
+
+        command.arg(prompt.as_inline_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::ShellDialect;
+
+    fn test_config() -> LiteLLMConfig {
+        LiteLLMConfig {
+            url: "https://litellm.test".to_string(),
+            ..LiteLLMConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_build_command() {
+        let backend = CodexBackend::default();
+        let command = backend.build_command(
+            &AssistantPrompt::Text("Plan it".to_string()),
+            &test_config(),
+        );
+
+        assert_eq!(
+            command.render(ShellDialect::Posix),
+            "OPENAI_BASE_URL=https://litellm.test codex --quiet 'Plan it'"
+        );
+        assert_eq!(command.secrets, vec!["OPENAI_API_KEY"]);
+    }
+
+    #[test]
+    fn test_build_command_with_model() {
+        let backend = CodexBackend {
+            model: Some("o4-mini".to_string()),
+        };
+        let command = backend.build_command(
+            &AssistantPrompt::Text("Plan it".to_string()),
+            &test_config(),
+        );
+
+        assert_eq!(
+            command.args,
+            vec!["--quiet", "--model", "o4-mini", "Plan it"]
+        );
+        assert_eq!(backend.name(), "codex");
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use super::{AiderBackend, AssistantBackend, ClaudeBackend, CodexBackend, TemplateBackend};
+use crate::pane_role::PaneRole;
+use crate::shell::ShellDialect;
+
+/// Assistant CLIs the coordinator knows how to drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    Codex,
+    Claude,
+    Aider,
+    Template,
+}
+
+impl BackendKind {
+    /// Parse a backend name from the plugin configuration
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "codex" => Some(BackendKind::Codex),
+            "claude" => Some(BackendKind::Claude),
+            "aider" => Some(BackendKind::Aider),
+            "template" | "custom" => Some(BackendKind::Template),
+            _ => None,
+        }
+    }
+}
+
+/// Errors in the per-role assistant configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssistantConfigError {
+    UnknownBackend { role: PaneRole, name: String },
+    MissingTemplate(PaneRole),
+    ApiKeyOutsideAssignment(PaneRole),
+    UnknownShell { role: PaneRole, name: String },
+    InvalidCaptureOutput { role: PaneRole, value: String },
+}
+
+impl fmt::Display for AssistantConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssistantConfigError::UnknownBackend { role, name } => write!(
+                f,
+                "Unknown assistant backend '{}' for {:?} (expected codex, claude, aider or template)",
+                name, role
+            ),
+            AssistantConfigError::MissingTemplate(role) => write!(
+                f,
+                "Template backend for {:?} needs {}_command_template",
+                role,
+                role.config_key()
+            ),
+            AssistantConfigError::ApiKeyOutsideAssignment(role) => write!(
+                f,
+                "{}_command_template may only use {{api_key}} as NAME={{api_key}} before the command",
+                role.config_key()
+            ),
+            AssistantConfigError::UnknownShell { role, name } => write!(
+                f,
+                "Unknown shell '{}' for {:?} (expected sh, bash, zsh or fish)",
+                name, role
+            ),
+            AssistantConfigError::InvalidCaptureOutput { role, value } => write!(
+                f,
+                "Invalid {}_capture_output '{}' (expected true or false)",
+                role.config_key(),
+                value
+            ),
+        }
+    }
+}
+
+/// Assistant backend and model chosen for one pane role
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleAssistant {
+    pub backend: BackendKind,
+    pub model: Option<String>,
+    pub template: Option<String>,
+    /// Shell running in the role's pane, used to quote the command
+    pub shell: ShellDialect,
+    /// Copy the assistant's output into the role's log; the assistant then
+    /// writes to a pipe rather than a terminal
+    pub capture_output: bool,
+}
+
+impl Default for RoleAssistant {
+    fn default() -> Self {
+        Self {
+            backend: BackendKind::Codex,
+            model: None,
+            template: None,
+            shell: ShellDialect::default(),
+            capture_output: false,
+        }
+    }
+}
+
+impl RoleAssistant {
+    /// Instantiate the configured backend
+    pub fn backend(&self) -> Box<dyn AssistantBackend> {
+        let model = self.model.clone();
+        match self.backend {
+            BackendKind::Codex => Box::new(CodexBackend { model }),
+            BackendKind::Claude => Box::new(ClaudeBackend { model }),
+            BackendKind::Aider => Box::new(AiderBackend { model }),
+            BackendKind::Template => Box::new(TemplateBackend::new(
+                self.template.clone().unwrap_or_default(),
+                model,
+            )),
+        }
+    }
+}
+
+/// Per-role assistant configuration
+///
+/// Read from `<role>_backend`, `<role>_model`, `<role>_command_template`,
+/// `<role>_shell` and `<role>_capture_output` keys in the plugin
+/// configuration. Roles without keys use codex in a POSIX shell, with its
+/// output left uncaptured.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssistantConfig {
+    roles: HashMap<PaneRole, RoleAssistant>,
+}
+
+impl AssistantConfig {
+    /// Parse the assistant keys out of the plugin configuration
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+    ) -> Result<Self, AssistantConfigError> {
+        let mut roles = HashMap::new();
+
+        for role in PaneRole::ALL.iter() {
+            let key = |suffix: &str| {
+                configuration
+                    .get(&format!("{}_{}", role.config_key(), suffix))
+                    .map(|value| value.trim())
+                    .filter(|value| !value.is_empty())
+            };
+
+            let backend_name = key("backend");
+            let model = key("model");
+            let template = key("command_template");
+            let shell_name = key("shell");
+            let capture_output = key("capture_output");
+            if backend_name.is_none()
+                && model.is_none()
+                && template.is_none()
+                && shell_name.is_none()
+                && capture_output.is_none()
+            {
+                continue;
+            }
+
+            let capture_output = match capture_output {
+                Some("true") => true,
+                None | Some("false") => false,
+                Some(value) => {
+                    return Err(AssistantConfigError::InvalidCaptureOutput {
+                        role: *role,
+                        value: value.to_string(),
+                    })
+                }
+            };
+
+            let shell = match shell_name {
+                Some(name) => ShellDialect::from_name(name).ok_or_else(|| {
+                    AssistantConfigError::UnknownShell {
+                        role: *role,
+                        name: name.to_string(),
+                    }
+                })?,
+                None => ShellDialect::default(),
+            };
+
+            let backend = match backend_name {
+                Some(name) => BackendKind::from_name(name).ok_or_else(|| {
+                    AssistantConfigError::UnknownBackend {
+                        role: *role,
+                        name: name.to_string(),
+                    }
+                })?,
+                // A template on its own is enough to pick the template backend
+                None if template.is_some() => BackendKind::Template,
+                None => BackendKind::Codex,
+            };
+
+            if backend == BackendKind::Template {
+                match template {
+                    None => return Err(AssistantConfigError::MissingTemplate(*role)),
+                    Some(template)
+                        if !TemplateBackend::new(template, None).api_key_only_in_assignments() =>
+                    {
+                        return Err(AssistantConfigError::ApiKeyOutsideAssignment(*role));
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            roles.insert(
+                *role,
+                RoleAssistant {
+                    backend,
+                    model: model.map(str::to_string),
+                    template: template.map(str::to_string),
+                    shell,
+                    capture_output,
+                },
+            );
+        }
+
+        Ok(Self { roles })
+    }
+
+    /// Assistant configured for `role`, falling back to codex
+    pub fn for_role(&self, role: PaneRole) -> RoleAssistant {
+        self.roles.get(&role).cloned().unwrap_or_default()
+    }
+
+    /// Set the assistant for a role
+    pub fn set_role(&mut self, role: PaneRole, assistant: RoleAssistant) {
+        self.roles.insert(role, assistant);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_is_codex_for_every_role() {
+        let config = AssistantConfig::from_configuration(&BTreeMap::new()).unwrap();
+        for role in PaneRole::ALL.iter() {
+            assert_eq!(config.for_role(*role), RoleAssistant::default());
+        }
+        assert_eq!(
+            config.for_role(PaneRole::Overseer).backend().name(),
+            "codex"
+        );
+    }
+
+    #[test]
+    fn test_backend_and_model_per_role() {
+        let config = AssistantConfig::from_configuration(&configuration(&[
+            ("overseer_backend", "codex"),
+            ("overseer_model", "o4-mini"),
+            ("commander_backend", "Claude"),
+            ("commander_model", "sonnet"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            config.for_role(PaneRole::Overseer),
+            RoleAssistant {
+                backend: BackendKind::Codex,
+                model: Some("o4-mini".to_string()),
+                template: None,
+                shell: ShellDialect::Posix,
+                capture_output: false,
+            }
+        );
+        let commander = config.for_role(PaneRole::Commander);
+        assert_eq!(commander.backend, BackendKind::Claude);
+        assert_eq!(commander.backend().name(), "claude");
+    }
+
+    #[test]
+    fn test_shell_per_role() {
+        let config =
+            AssistantConfig::from_configuration(&configuration(&[("commander_shell", "fish")]))
+                .unwrap();
+
+        let commander = config.for_role(PaneRole::Commander);
+        assert_eq!(commander.backend, BackendKind::Codex);
+        assert_eq!(commander.shell, ShellDialect::Fish);
+
+        let result =
+            AssistantConfig::from_configuration(&configuration(&[("overseer_shell", "pwsh")]));
+        assert_eq!(
+            result.unwrap_err(),
+            AssistantConfigError::UnknownShell {
+                role: PaneRole::Overseer,
+                name: "pwsh".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_template_api_key_must_be_an_assignment() {
+        let result = AssistantConfig::from_configuration(&configuration(&[(
+            "commander_command_template",
+            "llm --key {api_key} {prompt}",
+        )]));
+        assert_eq!(
+            result.unwrap_err(),
+            AssistantConfigError::ApiKeyOutsideAssignment(PaneRole::Commander)
+        );
+    }
+
+    #[test]
+    fn test_template_implies_template_backend() {
+        let config = AssistantConfig::from_configuration(&configuration(&[(
+            "commander_command_template",
+            "llm {prompt}",
+        )]))
+        .unwrap();
+
+        let commander = config.for_role(PaneRole::Commander);
+        assert_eq!(commander.backend, BackendKind::Template);
+        assert_eq!(commander.template.as_deref(), Some("llm {prompt}"));
+    }
+
+    #[test]
+    fn test_unknown_backend_is_rejected() {
+        let result =
+            AssistantConfig::from_configuration(&configuration(&[("overseer_backend", "gpt-cli")]));
+
+        assert_eq!(
+            result.unwrap_err(),
+            AssistantConfigError::UnknownBackend {
+                role: PaneRole::Overseer,
+                name: "gpt-cli".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_template_backend_requires_template() {
+        let result = AssistantConfig::from_configuration(&configuration(&[(
+            "commander_backend",
+            "template",
+        )]));
+
+        let error = result.unwrap_err();
+        assert_eq!(
+            error,
+            AssistantConfigError::MissingTemplate(PaneRole::Commander)
+        );
+        assert_eq!(
+            format!("{}", error),
+            "Template backend for Commander needs commander_command_template"
+        );
+    }
+
+    #[test]
+    fn test_capture_output_per_role() {
+        let config = AssistantConfig::from_configuration(&configuration(&[(
+            "overseer_capture_output",
+            "true",
+        )]))
+        .unwrap();
+
+        assert!(config.for_role(PaneRole::Overseer).capture_output);
+        assert!(!config.for_role(PaneRole::Commander).capture_output);
+
+        let error = AssistantConfig::from_configuration(&configuration(&[(
+            "overseer_capture_output",
+            "no",
+        )]))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid overseer_capture_output 'no' (expected true or false)"
+        );
+    }
+}
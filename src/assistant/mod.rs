@@ -0,0 +1,220 @@
+pub mod aider;
+pub mod claude;
+pub mod codex;
+pub mod config;
+pub mod template;
+
+use std::path::PathBuf;
+
+use crate::litellm_config::LiteLLMConfig;
+use crate::shell::ShellDialect;
+
+pub use aider::AiderBackend;
+pub use claude::ClaudeBackend;
+pub use codex::CodexBackend;
+pub use config::{AssistantConfig, AssistantConfigError, BackendKind, RoleAssistant};
+pub use template::TemplateBackend;
+
+/// The prompt handed to an assistant, either inline or as a file on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssistantPrompt {
+    Text(String),
+    File(PathBuf),
+}
+
+impl AssistantPrompt {
+    /// Prompt text for backends that only take an inline prompt
+    ///
+    /// Agentic assistants can read files themselves, so a prompt file is passed
+    /// as an instruction to follow it.
+    pub fn as_inline_text(&self) -> String {
+        match self {
+            AssistantPrompt::Text(text) => text.clone(),
+            AssistantPrompt::File(path) => {
+                format!("Follow the instructions in {}", path.display())
+            }
+        }
+    }
+}
+
+/// A command line to launch an assistant: environment, program and arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssistantCommand {
+    pub env: Vec<(String, String)>,
+    /// Variables that must hold the API key; never rendered on the command line
+    pub secrets: Vec<String>,
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl AssistantCommand {
+    pub fn new<S: Into<String>>(program: S) -> Self {
+        Self {
+            env: Vec::new(),
+            secrets: Vec::new(),
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add an environment variable assignment
+    pub fn env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Require the API key in the named environment variable
+    pub fn secret<S: Into<String>>(mut self, name: S) -> Self {
+        let name = name.into();
+        if !self.secrets.contains(&name) {
+            self.secrets.push(name);
+        }
+        self
+    }
+
+    /// Add a single argument
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Render the command as a line to type into a pane running `shell`
+    ///
+    /// Every word is quoted for that shell, so prompts reach the assistant
+    /// exactly as written.
+    pub fn render(&self, shell: ShellDialect) -> String {
+        let mut words: Vec<String> = self
+            .env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, shell.quote(value)))
+            .collect();
+        words.push(shell.quote(&self.program));
+        words.extend(self.args.iter().map(|arg| shell.quote(arg)));
+        words.join(" ")
+    }
+
+    /// Render the command so it first sources the env file holding its secrets
+    ///
+    /// Runs in a subshell so the key does not leak into the pane's shell, and
+    /// tightens the file's permissions in case the plugin could not. The env
+    /// file is sh syntax, so fish hands the whole line to `sh -c`.
+    pub fn render_with_env_file(&self, env_file: &str, shell: ShellDialect) -> String {
+        if self.secrets.is_empty() {
+            return self.render(shell);
+        }
+
+        shell.subshell(&self.source_env_file(env_file, &self.render(ShellDialect::Posix)))
+    }
+
+    /// Prefix a POSIX sh `script` with sourcing the env file holding the secrets
+    ///
+    /// The script is returned unchanged if the command needs no secrets.
+    pub fn source_env_file(&self, env_file: &str, script: &str) -> String {
+        if self.secrets.is_empty() {
+            return script.to_string();
+        }
+
+        let env_file = ShellDialect::Posix.quote(env_file);
+        format!("chmod 600 {} && . {} && {}", env_file, env_file, script)
+    }
+}
+
+/// Builds the command that runs a prompt with a particular assistant CLI
+pub trait AssistantBackend {
+    /// Backend name as used in the plugin configuration
+    fn name(&self) -> &str;
+
+    /// Build the command line, environment and prompt arguments for a prompt
+    fn build_command(&self, prompt: &AssistantPrompt, litellm: &LiteLLMConfig) -> AssistantCommand;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_quotes_env_and_unsafe_args() {
+        let command = AssistantCommand::new("codex")
+            .env("OPENAI_BASE_URL", "https://litellm.test")
+            .arg("--quiet")
+            .arg("Say \"hi\" to $USER");
+
+        assert_eq!(
+            command.render(ShellDialect::Bash),
+            "OPENAI_BASE_URL=https://litellm.test codex --quiet 'Say \"hi\" to $USER'"
+        );
+    }
+
+    #[test]
+    fn test_render_round_trips_through_sh() {
+        let prompt = "It's `$(rm -rf /)` \\ !! \n done";
+        let command = AssistantCommand::new("printf")
+            .env("ZZZ_UNUSED", "a b")
+            .arg("%s")
+            .arg(prompt);
+
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(command.render(ShellDialect::Posix))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), prompt);
+    }
+
+    #[test]
+    fn test_render_leaves_out_secrets() {
+        let command = AssistantCommand::new("codex")
+            .secret("OPENAI_API_KEY")
+            .secret("OPENAI_API_KEY")
+            .arg("go");
+
+        assert_eq!(command.secrets, vec!["OPENAI_API_KEY"]);
+        assert_eq!(command.render(ShellDialect::Posix), "codex go");
+        assert_eq!(
+            command.render_with_env_file(".zzz/task-1/overseer.env", ShellDialect::Zsh),
+            "(chmod 600 .zzz/task-1/overseer.env && . .zzz/task-1/overseer.env && codex go)"
+        );
+    }
+
+    #[test]
+    fn test_render_with_env_file_in_fish_uses_sh() {
+        let command = AssistantCommand::new("codex")
+            .secret("OPENAI_API_KEY")
+            .arg("it's");
+
+        let inner =
+            "chmod 600 .zzz/task-1/overseer.env && . .zzz/task-1/overseer.env && codex 'it'\\''s'";
+        assert_eq!(
+            command.render_with_env_file(".zzz/task-1/overseer.env", ShellDialect::Fish),
+            format!("sh -c {}", ShellDialect::Fish.quote(inner))
+        );
+    }
+
+    #[test]
+    fn test_render_without_secrets_skips_env_file() {
+        let command = AssistantCommand::new("tool").arg("go");
+        assert_eq!(
+            command.render_with_env_file(".zzz/task-1/editor.env", ShellDialect::Bash),
+            "tool go"
+        );
+    }
+
+    #[test]
+    fn test_render_quotes_empty_argument() {
+        let command = AssistantCommand::new("tool").arg("");
+        assert_eq!(command.render(ShellDialect::Posix), "tool ''");
+        assert_eq!(command.render(ShellDialect::Fish), "tool ''");
+    }
+
+    #[test]
+    fn test_prompt_inline_text() {
+        assert_eq!(
+            AssistantPrompt::Text("Do it".to_string()).as_inline_text(),
+            "Do it"
+        );
+        assert_eq!(
+            AssistantPrompt::File(PathBuf::from(".zzz/task-1/prompt.md")).as_inline_text(),
+            "Follow the instructions in .zzz/task-1/prompt.md"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use super::{AssistantBackend, AssistantCommand, AssistantPrompt};
+use crate::litellm_config::LiteLLMConfig;
+
+/// Generic backend driven by a command template from the plugin configuration
+///
+/// The template is split into words on whitespace before placeholders are
+/// substituted, so a prompt always stays a single argument. Supported
+/// placeholders are `{prompt}`, `{prompt_file}`, `{model}`, `{api_key}` and
+/// `{base_url}`. Leading `NAME=value` words become environment variables.
+/// `{api_key}` may only appear as `NAME={api_key}`; the key is then exported
+/// as `NAME` from the role's env file and never appears in the command.
+#[derive(Debug, Clone)]
+pub struct TemplateBackend {
+    pub template: String,
+    pub model: Option<String>,
+}
+
+impl TemplateBackend {
+    pub fn new<S: Into<String>>(template: S, model: Option<String>) -> Self {
+        Self {
+            template: template.into(),
+            model,
+        }
+    }
+
+    fn substitute(&self, word: &str, prompt: &AssistantPrompt, litellm: &LiteLLMConfig) -> String {
+        let prompt_file = match prompt {
+            AssistantPrompt::File(path) => path.to_string_lossy().into_owned(),
+            AssistantPrompt::Text(_) => String::new(),
+        };
+
+        word.replace("{prompt_file}", &prompt_file)
+            .replace("{prompt}", &prompt.as_inline_text())
+            .replace("{model}", self.model.as_deref().unwrap_or(""))
+            .replace("{base_url}", &litellm.url)
+    }
+
+    /// Check that `{api_key}` is only used as the value of a leading assignment
+    pub fn api_key_only_in_assignments(&self) -> bool {
+        let mut words = self.template.split_whitespace().peekable();
+        while let Some((_, value)) = words.peek().and_then(|w| split_assignment(w)) {
+            if value != API_KEY_PLACEHOLDER && value.contains(API_KEY_PLACEHOLDER) {
+                return false;
+            }
+            words.next();
+        }
+        words.all(|word| !word.contains(API_KEY_PLACEHOLDER))
+    }
+}
+
+const API_KEY_PLACEHOLDER: &str = "{api_key}";
+
+/// Split `NAME=value` into its parts if `NAME` is a valid variable name
+fn split_assignment(word: &str) -> Option<(&str, &str)> {
+    let (name, value) = word.split_at(word.find('=')?);
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Some((name, &value[1..]))
+    } else {
+        None
+    }
+}
+
+impl AssistantBackend for TemplateBackend {
+    fn name(&self) -> &str {
+        "template"
+    }
+
+    fn build_command(&self, prompt: &AssistantPrompt, litellm: &LiteLLMConfig) -> AssistantCommand {
+        let mut words = self.template.split_whitespace().peekable();
+        let mut env = Vec::new();
+        let mut secrets = Vec::new();
+
+        while let Some((name, value)) = words.peek().and_then(|w| split_assignment(w)) {
+            if value == API_KEY_PLACEHOLDER {
+                secrets.push(name);
+            } else {
+                env.push((name.to_string(), self.substitute(value, prompt, litellm)));
+            }
+            words.next();
+        }
+
+        let mut command = AssistantCommand::new(
+            words
+                .next()
+                .map(|word| self.substitute(word, prompt, litellm))
+                .unwrap_or_default(),
+        );
+        command.env = env;
+        for word in words {
+            command = command.arg(self.substitute(word, prompt, litellm));
+        }
+        for name in secrets {
+            command = command.secret(name);
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> LiteLLMConfig {
+        LiteLLMConfig {
+            url: "https://litellm.test".to_string(),
+            ..LiteLLMConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_build_command_substitutes_placeholders() {
+        let backend = TemplateBackend::new(
+            "LLM_KEY={api_key} LLM_URL={base_url}/v1 llm -m {model} {prompt}",
+            Some("gpt-4o".to_string()),
+        );
+        let command = backend.build_command(
+            &AssistantPrompt::Text("Plan the work now".to_string()),
+            &test_config(),
+        );
+
+        assert_eq!(
+            command.env,
+            vec![("LLM_URL".to_string(), "https://litellm.test/v1".to_string()),]
+        );
+        assert_eq!(command.program, "llm");
+        assert_eq!(command.args, vec!["-m", "gpt-4o", "Plan the work now"]);
+        assert_eq!(command.secrets, vec!["LLM_KEY"]);
+        assert!(backend.api_key_only_in_assignments());
+        assert_eq!(backend.name(), "template");
+    }
+
+    #[test]
+    fn test_build_command_with_prompt_file() {
+        let backend = TemplateBackend::new("goose run --instructions {prompt_file}", None);
+        let command = backend.build_command(
+            &AssistantPrompt::File(".zzz/task-1/prompt-overseer-1.md".into()),
+            &test_config(),
+        );
+
+        assert!(command.env.is_empty());
+        assert!(command.secrets.is_empty());
+        assert_eq!(command.program, "goose");
+        assert_eq!(
+            command.args,
+            vec!["run", "--instructions", ".zzz/task-1/prompt-overseer-1.md"]
+        );
+    }
+
+    #[test]
+    fn test_assignments_only_recognised_before_program() {
+        let backend = TemplateBackend::new("tool --flag=x {prompt}", None);
+        let command =
+            backend.build_command(&AssistantPrompt::Text("go".to_string()), &test_config());
+
+        assert!(command.env.is_empty());
+        assert_eq!(command.args, vec!["--flag=x", "go"]);
+    }
+
+    #[test]
+    fn test_api_key_outside_assignment_is_detected() {
+        let in_arg = TemplateBackend::new("llm --key {api_key} {prompt}", None);
+        assert!(!in_arg.api_key_only_in_assignments());
+
+        let in_url = TemplateBackend::new("URL=https://x/?k={api_key} llm {prompt}", None);
+        assert!(!in_url.api_key_only_in_assignments());
+    }
+
+    #[test]
+    fn test_split_assignment() {
+        assert_eq!(split_assignment("A_B=1"), Some(("A_B", "1")));
+        assert_eq!(split_assignment("--flag=1"), None);
+        assert_eq!(split_assignment("1A=1"), None);
+        assert_eq!(split_assignment("plain"), None);
+    }
+}
@@ -1,51 +1,172 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Instant;
 use zellij_tile::prelude::*;
 
-use super::envelope::MessageEnvelope;
+use super::envelope::{MessageEnvelope, MessageTarget};
 use super::error::CommunicationError;
+use super::protocol::{self, PROTOCOL_VERSION};
+use super::queue::{DeliveryTick, OutboundQueue, RetryPolicy};
+use super::router::Transport;
 use crate::coordination_message::CoordinationMessage;
 use crate::zellij_service::ZellijService;
 
+/// Name of the pipe envelopes are sent on
+pub const COORDINATION_PIPE: &str = "coordination";
+
 /// Communication utilities for the ZZZ plugin
 pub struct Communication<T: ZellijService> {
     zellij_service: T,
+    /// Envelopes awaiting acknowledgement
+    outbound: RefCell<OutboundQueue>,
+    /// Protocol version last seen from each sender, used when replying to it
+    peer_versions: RefCell<HashMap<String, u32>>,
 }
 
 impl<T: ZellijService> Communication<T> {
     /// Create a new Communication instance with the given ZellijService
     pub fn new(zellij_service: T) -> Self {
-        Self { zellij_service }
+        Self {
+            zellij_service,
+            outbound: RefCell::new(OutboundQueue::default()),
+            peer_versions: RefCell::new(HashMap::new()),
+        }
     }
 
-    /// Send a coordination message using Zellij's pipe system
+    /// Replace the retry policy; messages already awaiting acknowledgement are dropped
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.outbound = RefCell::new(OutboundQueue::new(policy));
+    }
+
+    /// Send a coordination message to every plugin on the coordination pipe
     ///
     /// This is a low-level function that handles the actual pipe message sending.
     /// Use the State wrapper methods for most use cases.
     pub fn send_pipe_message(&self, envelope: &MessageEnvelope) -> Result<(), CommunicationError> {
-        // Serialize the envelope to JSON
-        let payload = serde_json::to_string(envelope)?;
+        self.send(envelope, Transport::Broadcast)
+    }
 
-        // Send via Zellij's pipe system using the injected service
-        // Note: This sends to all plugins listening on the "coordination" pipe
-        self.zellij_service
-            .pipe_message_to_plugin(&payload, "coordination");
+    /// Send an envelope over a transport resolved by `MessageRouter::resolve_target`
+    ///
+    /// Terminals get the envelope as one line of input; plugins get it on the
+    /// coordination pipe.
+    pub fn send(
+        &self,
+        envelope: &MessageEnvelope,
+        transport: Transport,
+    ) -> Result<(), CommunicationError> {
+        // Serialize the envelope in the newest protocol version the target speaks
+        let version = match &envelope.target {
+            Some(MessageTarget::Plugin(name)) | Some(MessageTarget::Title(name)) => {
+                self.peer_version(name)
+            }
+            _ => PROTOCOL_VERSION,
+        };
+        let payload = protocol::encode_envelope(envelope, version)?;
+
+        match transport {
+            Transport::Keystrokes(terminal_id) => self
+                .zellij_service
+                .write_chars_to_pane_id(&format!("{}\n", payload), PaneId::Terminal(terminal_id)),
+            Transport::PluginPipe(plugin_id) => self.zellij_service.pipe_message_to_plugin_id(
+                &payload,
+                COORDINATION_PIPE,
+                plugin_id,
+            ),
+            Transport::Broadcast => self
+                .zellij_service
+                .pipe_message_to_plugin(&payload, COORDINATION_PIPE),
+        }
 
         Ok(())
     }
 
-    /// Parse an incoming payload as either MessageEnvelope or legacy CoordinationMessage
+    /// Send an envelope that the receiver must acknowledge
+    ///
+    /// The envelope is given a message ID and resent over the same transport
+    /// by `process_timeouts` until an `Ack` for that ID arrives or its
+    /// retries run out.
+    pub fn send_with_ack(
+        &self,
+        envelope: MessageEnvelope,
+        transport: Transport,
+        now: Instant,
+    ) -> Result<u64, CommunicationError> {
+        let envelope = self.outbound.borrow_mut().enqueue(envelope, transport, now);
+        let message_id = envelope.message_id.unwrap_or_default();
+
+        if let Err(e) = self.send(&envelope, transport) {
+            self.outbound.borrow_mut().acknowledge(message_id);
+            return Err(e);
+        }
+        Ok(message_id)
+    }
+
+    /// Settle an outstanding message; returns false if it was not awaiting an acknowledgement
+    pub fn acknowledge(&self, message_id: u64) -> bool {
+        self.outbound.borrow_mut().acknowledge(message_id)
+    }
+
+    /// Resend envelopes whose acknowledgement timed out and give up on the exhausted ones
+    ///
+    /// Dead letters are returned for logging; `DeadLetter::error` gives the
+    /// `MessageDeliveryFailed` error for each.
+    pub fn process_timeouts(&self, now: Instant) -> DeliveryTick {
+        let tick = self.outbound.borrow_mut().tick(now);
+        for (envelope, transport) in &tick.retries {
+            // A retry that cannot be serialized now could not be the first time either
+            let _ = self.send(envelope, *transport);
+        }
+        tick
+    }
+
+    /// Number of envelopes awaiting acknowledgement
+    pub fn pending_acks(&self) -> usize {
+        self.outbound.borrow().len()
+    }
+
+    /// Remember the protocol version a sender used, so replies to it can be understood
+    pub fn record_peer_version(&self, sender: &str, version: u32) {
+        self.peer_versions
+            .borrow_mut()
+            .insert(sender.to_string(), version.min(PROTOCOL_VERSION));
+    }
+
+    /// Protocol version to use for messages to `peer`; the current one unless
+    /// the peer has been seen speaking an older version
+    pub fn peer_version(&self, peer: &str) -> u32 {
+        self.peer_versions
+            .borrow()
+            .get(peer)
+            .copied()
+            .unwrap_or(PROTOCOL_VERSION)
+    }
+
+    /// Parse an incoming payload as either MessageEnvelope or bare CoordinationMessage
+    ///
+    /// Objects with a `coordination_message` field are envelopes; their
+    /// `protocol_version` (1 when missing) must not be newer than this build's.
+    /// Messages are accepted in both the tagged and the version 1 externally
+    /// tagged encoding.
     pub fn parse_incoming_message(payload: &str) -> Result<ParsedMessage, serde_json::Error> {
-        // Try parsing as MessageEnvelope first
-        if let Ok(envelope) = serde_json::from_str::<MessageEnvelope>(payload) {
+        let value: serde_json::Value = serde_json::from_str(payload)?;
+
+        if value.get("coordination_message").is_some() {
+            let version = protocol::envelope_version(&value)?;
+            let mut envelope: MessageEnvelope =
+                serde_json::from_value(protocol::upgrade_envelope(value))?;
+            envelope.protocol_version = version;
             return Ok(ParsedMessage::Envelope(envelope));
         }
 
-        // Fall back to legacy CoordinationMessage format
-        if let Ok(message) = serde_json::from_str::<CoordinationMessage>(payload) {
-            return Ok(ParsedMessage::Legacy(message));
-        }
+        let message = serde_json::from_value(protocol::upgrade_message(value))?;
+        Ok(ParsedMessage::Legacy(message))
+    }
 
-        // If neither works, return the JSON error from the envelope parsing
-        Err(serde_json::from_str::<MessageEnvelope>(payload).unwrap_err())
+    /// Get access to the zellij service (for testing)
+    #[cfg(test)]
+    pub fn get_zellij_service(&self) -> &T {
+        &self.zellij_service
     }
 }
 
@@ -69,6 +190,8 @@ use crate::zellij_service::MockZellijService;
 mod tests {
     use super::*;
     use crate::coordination_message::CoordinationMessage;
+    use crate::pane_role::PaneRole;
+    use crate::task_id::TaskId;
     use crate::workflow_phase::WorkflowPhase;
     use crate::zellij_service::MockZellijService;
 
@@ -78,10 +201,14 @@ mod tests {
 
     fn create_test_envelope() -> MessageEnvelope {
         let message = CoordinationMessage::StartPlanning {
-            task_id: 123,
+            task_id: TaskId::from(123),
             task_description: "Test task".to_string(),
         };
-        MessageEnvelope::new_targeted(message, "test-pane", "test-sender")
+        MessageEnvelope::new_targeted(
+            message,
+            MessageTarget::Title("test-pane".to_string()),
+            "test-sender",
+        )
     }
 
     fn create_test_coordination_message() -> CoordinationMessage {
@@ -119,10 +246,101 @@ mod tests {
         // Verify the payload is valid JSON that can be deserialized back to MessageEnvelope
         let deserialized: MessageEnvelope =
             serde_json::from_str(payload).expect("Should deserialize");
-        assert_eq!(deserialized.target_pane, envelope.target_pane);
+        assert_eq!(deserialized.target, envelope.target);
         assert_eq!(deserialized.sender, envelope.sender);
     }
 
+    #[test]
+    fn test_send_with_ack_tags_envelope() {
+        let communication = create_test_communication();
+        let message_id = communication
+            .send_with_ack(create_test_envelope(), Transport::Broadcast, Instant::now())
+            .unwrap();
+
+        let piped = communication.zellij_service.get_piped_messages();
+        assert_eq!(piped.len(), 1);
+        let sent: MessageEnvelope = serde_json::from_str(&piped[0].0).unwrap();
+        assert_eq!(sent.message_id, Some(message_id));
+        assert!(sent.ack_requested);
+        assert_eq!(communication.pending_acks(), 1);
+    }
+
+    #[test]
+    fn test_acknowledged_message_is_not_resent() {
+        let communication = create_test_communication();
+        let now = Instant::now();
+        let message_id = communication
+            .send_with_ack(create_test_envelope(), Transport::Broadcast, now)
+            .unwrap();
+
+        assert!(communication.acknowledge(message_id));
+        let tick = communication.process_timeouts(now + std::time::Duration::from_secs(600));
+
+        assert!(tick.retries.is_empty());
+        assert!(tick.dead_letters.is_empty());
+        assert_eq!(communication.zellij_service.get_piped_messages().len(), 1);
+    }
+
+    #[test]
+    fn test_unacknowledged_message_is_resent_then_dead_lettered() {
+        let mut communication = create_test_communication();
+        communication.set_retry_policy(RetryPolicy {
+            ack_timeout: std::time::Duration::from_secs(1),
+            max_retries: 1,
+        });
+        let start = Instant::now();
+        let message_id = communication
+            .send_with_ack(create_test_envelope(), Transport::PluginPipe(4), start)
+            .unwrap();
+
+        let tick = communication.process_timeouts(start + std::time::Duration::from_secs(1));
+        assert_eq!(tick.retries.len(), 1);
+        // The retry goes to the same plugin as the first attempt
+        let piped = communication.zellij_service.get_plugin_piped_messages();
+        assert_eq!(piped.len(), 2);
+        assert_eq!(piped[0], piped[1]);
+        assert_eq!(piped[0].1, COORDINATION_PIPE);
+        assert_eq!(piped[0].2, 4);
+
+        let tick = communication.process_timeouts(start + std::time::Duration::from_secs(10));
+        assert!(tick.retries.is_empty());
+        assert_eq!(tick.dead_letters.len(), 1);
+        assert_eq!(tick.dead_letters[0].envelope.message_id, Some(message_id));
+        assert!(matches!(
+            tick.dead_letters[0].error(),
+            CommunicationError::MessageDeliveryFailed(_)
+        ));
+        assert_eq!(communication.pending_acks(), 0);
+        assert_eq!(
+            communication
+                .zellij_service
+                .get_plugin_piped_messages()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_send_to_terminal_writes_one_line() {
+        let communication = create_test_communication();
+        let envelope = create_test_envelope();
+
+        communication
+            .send(&envelope, Transport::Keystrokes(5))
+            .unwrap();
+
+        let sent = communication.zellij_service.get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(5));
+        assert!(sent[0].0.ends_with('\n'));
+        assert_eq!(sent[0].0.matches('\n').count(), 1);
+        match Communication::<MockZellijService>::parse_incoming_message(sent[0].0.trim_end()) {
+            Ok(ParsedMessage::Envelope(parsed)) => assert_eq!(parsed.target, envelope.target),
+            other => panic!("Expected an envelope, got {:?}", other),
+        }
+        assert!(communication.zellij_service.get_piped_messages().is_empty());
+    }
+
     #[test]
     fn test_send_pipe_message_with_different_envelope_types() {
         let communication = create_test_communication();
@@ -130,7 +348,7 @@ mod tests {
         // Test targeted envelope
         let targeted_envelope = MessageEnvelope::new_targeted(
             create_test_coordination_message(),
-            "specific-pane",
+            MessageTarget::Role(PaneRole::Commander),
             "sender1",
         );
 
@@ -163,7 +381,7 @@ mod tests {
         assert!(result.is_ok());
 
         if let Ok(ParsedMessage::Envelope(parsed_envelope)) = result {
-            assert_eq!(parsed_envelope.target_pane, original_envelope.target_pane);
+            assert_eq!(parsed_envelope.target, original_envelope.target);
             assert_eq!(parsed_envelope.sender, original_envelope.sender);
             assert_eq!(parsed_envelope.timestamp, original_envelope.timestamp);
         } else {
@@ -200,14 +418,101 @@ mod tests {
         let error = result.unwrap_err();
         let error_message = format!("{}", error);
         // Just verify it's a JSON parsing error, don't be too specific about the message
-        assert!(error_message.len() > 0);
+        assert!(!error_message.is_empty());
+    }
+
+    #[test]
+    fn test_parse_incoming_message_version_1_envelope() {
+        let payload = r#"{"target_pane":"Overseer","coordination_message":{"PlanReady":{"todo_file_path":"todo.md"}},"sender":"old-plugin","timestamp":1,"message_id":5,"ack_requested":true}"#;
+
+        match Communication::<MockZellijService>::parse_incoming_message(payload) {
+            Ok(ParsedMessage::Envelope(envelope)) => {
+                assert_eq!(envelope.protocol_version, 1);
+                assert_eq!(
+                    envelope.target,
+                    Some(MessageTarget::Title("Overseer".to_string()))
+                );
+                assert_eq!(envelope.ack_id(), Some(5));
+                assert_eq!(
+                    envelope.coordination_message,
+                    CoordinationMessage::PlanReady {
+                        todo_file_path: "todo.md".to_string()
+                    }
+                );
+            }
+            other => panic!("Expected a version 1 envelope, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_incoming_message_legacy_unit_variants() {
+        for payload in [r#""StartReview""#, r#"{"type":"StartReview"}"#].iter() {
+            match Communication::<MockZellijService>::parse_incoming_message(payload) {
+                Ok(ParsedMessage::Legacy(CoordinationMessage::StartReview)) => {}
+                other => panic!("Expected StartReview for {}, got {:?}", payload, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_incoming_message_rejects_newer_protocol() {
+        let mut envelope = serde_json::to_value(create_test_envelope()).unwrap();
+        envelope["protocol_version"] = serde_json::json!(PROTOCOL_VERSION + 1);
+
+        let error =
+            Communication::<MockZellijService>::parse_incoming_message(&envelope.to_string())
+                .unwrap_err();
+        assert!(error.to_string().starts_with(&format!(
+            "unsupported protocol version {}",
+            PROTOCOL_VERSION + 1
+        )));
+    }
+
+    #[test]
+    fn test_parse_incoming_message_unknown_type_is_error() {
+        let payload = r#"{"coordination_message":{"type":"Teleport"},"sender":"x","timestamp":1,"protocol_version":2,"target_pane":null}"#;
+        assert!(Communication::<MockZellijService>::parse_incoming_message(payload).is_err());
+    }
+
+    #[test]
+    fn test_replies_use_peer_protocol_version() {
+        let communication = create_test_communication();
+        communication.record_peer_version("old-plugin", 1);
+        assert_eq!(communication.peer_version("old-plugin"), 1);
+        assert_eq!(communication.peer_version("new-plugin"), PROTOCOL_VERSION);
+
+        communication
+            .send_pipe_message(&MessageEnvelope::new_ack(
+                5,
+                "old-plugin",
+                "zzz-coordinator",
+            ))
+            .unwrap();
+        communication
+            .send_pipe_message(&MessageEnvelope::new_ack(
+                6,
+                "new-plugin",
+                "zzz-coordinator",
+            ))
+            .unwrap();
+
+        let piped = communication.zellij_service.get_piped_messages();
+        let old: serde_json::Value = serde_json::from_str(&piped[0].0).unwrap();
+        let new: serde_json::Value = serde_json::from_str(&piped[1].0).unwrap();
+        assert!(old.get("protocol_version").is_none());
+        assert_eq!(
+            old["coordination_message"],
+            serde_json::json!({"Ack": {"message_id": 5}})
+        );
+        assert_eq!(new["protocol_version"], PROTOCOL_VERSION);
+        assert_eq!(new["coordination_message"]["type"], "Ack");
     }
 
     #[test]
     fn test_parse_incoming_message_all_coordination_variants() {
         let coordination_messages = vec![
             CoordinationMessage::StartPlanning {
-                task_id: 1,
+                task_id: TaskId::from(1),
                 task_description: "Plan task".to_string(),
             },
             CoordinationMessage::PlanReady {
@@ -296,7 +601,7 @@ mod tests {
 
         // Verify we get back the same envelope
         if let Ok(ParsedMessage::Envelope(parsed_envelope)) = parse_result {
-            assert_eq!(parsed_envelope.target_pane, original_envelope.target_pane);
+            assert_eq!(parsed_envelope.target, original_envelope.target);
             assert_eq!(parsed_envelope.sender, original_envelope.sender);
             assert_eq!(parsed_envelope.timestamp, original_envelope.timestamp);
         } else {
@@ -310,13 +615,13 @@ mod tests {
 
         // Create envelope with special characters
         let special_message = CoordinationMessage::StartPlanning {
-            task_id: 999,
+            task_id: TaskId::from(999),
             task_description: "Task with special chars: !@#$%^&*()_+-=[]{}|;':\",./<>?`~"
                 .to_string(),
         };
         let special_envelope = MessageEnvelope::new_targeted(
             special_message,
-            "pane-with-special_chars!@#",
+            MessageTarget::Plugin("pane-with-special_chars!@#".to_string()),
             "sender/with\\slashes",
         );
 
@@ -1,54 +1,141 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::time::{SystemTime, UNIX_EPOCH};
+use zellij_tile::prelude::PaneId;
 
+use super::protocol::{legacy_protocol_version, PROTOCOL_VERSION};
 use crate::coordination_message::CoordinationMessage;
+use crate::pane_role::PaneRole;
+
+/// Sender identifier used for every envelope the coordinator sends
+pub const COORDINATOR_SENDER: &str = "zzz-coordinator";
+
+/// Who an envelope is addressed to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageTarget {
+    /// Whichever pane is registered for the role
+    Role(PaneRole),
+    /// A specific terminal or plugin pane
+    Pane(PaneId),
+    /// A plugin by name: its plugin URL, the URL's file stem or its pane title
+    Plugin(String),
+    /// A pane by title, as addressed by protocol versions before 3
+    Title(String),
+}
+
+impl fmt::Display for MessageTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageTarget::Role(role) => write!(f, "{:?}", role),
+            MessageTarget::Pane(PaneId::Terminal(id)) => write!(f, "terminal_{}", id),
+            MessageTarget::Pane(PaneId::Plugin(id)) => write!(f, "plugin_{}", id),
+            MessageTarget::Plugin(name) | MessageTarget::Title(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Identity of a receiver, used to decide whether an envelope is meant for it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recipient {
+    /// Plugin name, matched by `Plugin` targets and, for older senders, `Title` targets
+    pub name: String,
+    /// The receiver's own pane
+    pub pane_id: Option<PaneId>,
+    /// Role the receiver's pane is registered for
+    pub role: Option<PaneRole>,
+    /// The receiver's pane title
+    pub title: Option<String>,
+}
 
 /// Message envelope for inter-pane communication
 /// Wraps CoordinationMessage with metadata for routing and debugging
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MessageEnvelope {
-    /// Target pane title (None = broadcast to all panes)
-    pub target_pane: Option<String>,
+    /// Wire protocol version the envelope was encoded with
+    #[serde(default = "legacy_protocol_version")]
+    pub protocol_version: u32,
+    /// Receiver of the message (None = broadcast to all panes)
+    #[serde(default)]
+    pub target: Option<MessageTarget>,
     /// The actual coordination message
     pub coordination_message: CoordinationMessage,
     /// Sender identifier for debugging and routing
     pub sender: String,
     /// Unix timestamp when message was created
     pub timestamp: u64,
+    /// Identifier acknowledgements refer to (None for fire-and-forget messages)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<u64>,
+    /// Whether the receiver should reply with an `Ack` envelope
+    #[serde(default)]
+    pub ack_requested: bool,
 }
 
 impl MessageEnvelope {
-    /// Create a new message envelope for a specific target pane
-    pub fn new_targeted(message: CoordinationMessage, target_pane: &str, sender: &str) -> Self {
+    /// Create a new message envelope for a specific target
+    pub fn new_targeted(message: CoordinationMessage, target: MessageTarget, sender: &str) -> Self {
         Self {
-            target_pane: Some(target_pane.to_string()),
+            protocol_version: PROTOCOL_VERSION,
+            target: Some(target),
             coordination_message: message,
             sender: sender.to_string(),
             timestamp: Self::current_timestamp(),
+            message_id: None,
+            ack_requested: false,
         }
     }
 
     /// Create a new message envelope for broadcasting to all panes
     pub fn new_broadcast(message: CoordinationMessage, sender: &str) -> Self {
         Self {
-            target_pane: None,
+            protocol_version: PROTOCOL_VERSION,
+            target: None,
             coordination_message: message,
             sender: sender.to_string(),
             timestamp: Self::current_timestamp(),
+            message_id: None,
+            ack_requested: false,
         }
     }
 
-    /// Check if this message is targeted to a specific pane
-    pub fn is_targeted_to(&self, pane_title: &str) -> bool {
-        match &self.target_pane {
-            Some(target) => target == pane_title,
+    /// Create an acknowledgement of `message_id`, addressed to the original sender
+    pub fn new_ack(message_id: u64, original_sender: &str, sender: &str) -> Self {
+        Self::new_targeted(
+            CoordinationMessage::Ack { message_id },
+            MessageTarget::Plugin(original_sender.to_string()),
+            sender,
+        )
+    }
+
+    /// Tag the envelope with a message ID and ask the receiver to acknowledge it
+    pub fn with_ack_request(mut self, message_id: u64) -> Self {
+        self.message_id = Some(message_id);
+        self.ack_requested = true;
+        self
+    }
+
+    /// Message ID the receiver should acknowledge, if one was requested
+    pub fn ack_id(&self) -> Option<u64> {
+        self.message_id.filter(|_| self.ack_requested)
+    }
+
+    /// Check if this message is meant for `recipient`
+    pub fn is_targeted_to(&self, recipient: &Recipient) -> bool {
+        match &self.target {
             None => true, // Broadcast messages are for everyone
+            Some(MessageTarget::Role(role)) => recipient.role == Some(*role),
+            Some(MessageTarget::Pane(pane_id)) => recipient.pane_id == Some(*pane_id),
+            Some(MessageTarget::Plugin(name)) => recipient.name == *name,
+            Some(MessageTarget::Title(title)) => {
+                recipient.title.as_deref() == Some(title.as_str()) || recipient.name == *title
+            }
         }
     }
 
     /// Check if this is a broadcast message
     pub fn is_broadcast(&self) -> bool {
-        self.target_pane.is_none()
+        self.target.is_none()
     }
 
     /// Get current Unix timestamp
@@ -64,12 +151,24 @@ impl MessageEnvelope {
 mod tests {
     use super::*;
     use crate::coordination_message::CoordinationMessage;
+    use crate::task_id::TaskId;
     use crate::workflow_phase::WorkflowPhase;
     use std::time::{SystemTime, UNIX_EPOCH};
 
+    fn title(title: &str) -> MessageTarget {
+        MessageTarget::Title(title.to_string())
+    }
+
+    fn named(name: &str) -> Recipient {
+        Recipient {
+            name: name.to_string(),
+            ..Recipient::default()
+        }
+    }
+
     fn create_test_message() -> CoordinationMessage {
         CoordinationMessage::StartPlanning {
-            task_id: 123,
+            task_id: TaskId::from(123),
             task_description: "Test task".to_string(),
         }
     }
@@ -80,9 +179,9 @@ mod tests {
         let target_pane = "test-pane";
         let sender = "test-sender";
 
-        let envelope = MessageEnvelope::new_targeted(message.clone(), target_pane, sender);
+        let envelope = MessageEnvelope::new_targeted(message.clone(), title(target_pane), sender);
 
-        assert_eq!(envelope.target_pane, Some(target_pane.to_string()));
+        assert_eq!(envelope.target, Some(title(target_pane)));
         assert_eq!(envelope.sender, sender);
         assert!(matches!(
             envelope.coordination_message,
@@ -106,7 +205,7 @@ mod tests {
 
         let envelope = MessageEnvelope::new_broadcast(message.clone(), sender);
 
-        assert_eq!(envelope.target_pane, None);
+        assert_eq!(envelope.target, None);
         assert_eq!(envelope.sender, sender);
         assert!(matches!(
             envelope.coordination_message,
@@ -127,11 +226,77 @@ mod tests {
     fn test_is_targeted_to_with_targeted_message() {
         let message = create_test_message();
         let target_pane = "specific-pane";
-        let envelope = MessageEnvelope::new_targeted(message, target_pane, "sender");
+        let envelope = MessageEnvelope::new_targeted(message, title(target_pane), "sender");
+
+        assert!(envelope.is_targeted_to(&named(target_pane)));
+        assert!(envelope.is_targeted_to(&Recipient {
+            title: Some(target_pane.to_string()),
+            ..Recipient::default()
+        }));
+        assert!(!envelope.is_targeted_to(&named("different-pane")));
+        assert!(!envelope.is_targeted_to(&named("")));
+    }
+
+    #[test]
+    fn test_is_targeted_to_role_pane_and_plugin() {
+        let commander = Recipient {
+            name: "commander-plugin".to_string(),
+            pane_id: Some(PaneId::Plugin(4)),
+            role: Some(PaneRole::Commander),
+            title: Some("Commander".to_string()),
+        };
+        let message = create_test_message;
+
+        let by_role =
+            MessageEnvelope::new_targeted(message(), MessageTarget::Role(PaneRole::Commander), "s");
+        let by_pane =
+            MessageEnvelope::new_targeted(message(), MessageTarget::Pane(PaneId::Plugin(4)), "s");
+        let by_name = MessageEnvelope::new_targeted(
+            message(),
+            MessageTarget::Plugin("commander-plugin".to_string()),
+            "s",
+        );
+        for envelope in [&by_role, &by_pane, &by_name].iter() {
+            assert!(envelope.is_targeted_to(&commander));
+            assert!(!envelope.is_targeted_to(&named("overseer-plugin")));
+        }
+
+        let other_role =
+            MessageEnvelope::new_targeted(message(), MessageTarget::Role(PaneRole::Overseer), "s");
+        let other_pane =
+            MessageEnvelope::new_targeted(message(), MessageTarget::Pane(PaneId::Terminal(4)), "s");
+        assert!(!other_role.is_targeted_to(&commander));
+        assert!(!other_pane.is_targeted_to(&commander));
+    }
+
+    #[test]
+    fn test_target_serialization_and_display() {
+        let targets = [
+            (
+                MessageTarget::Role(PaneRole::TaskList),
+                r#"{"role":"TaskList"}"#,
+                "TaskList",
+            ),
+            (
+                MessageTarget::Pane(PaneId::Terminal(3)),
+                r#"{"pane":{"Terminal":3}}"#,
+                "terminal_3",
+            ),
+            (
+                MessageTarget::Plugin("zzz".to_string()),
+                r#"{"plugin":"zzz"}"#,
+                "zzz",
+            ),
+        ];
 
-        assert!(envelope.is_targeted_to(target_pane));
-        assert!(!envelope.is_targeted_to("different-pane"));
-        assert!(!envelope.is_targeted_to(""));
+        for (target, json, display) in targets.iter() {
+            assert_eq!(serde_json::to_string(target).unwrap(), *json);
+            assert_eq!(
+                serde_json::from_str::<MessageTarget>(json).unwrap(),
+                *target
+            );
+            assert_eq!(target.to_string(), *display);
+        }
     }
 
     #[test]
@@ -140,29 +305,74 @@ mod tests {
         let envelope = MessageEnvelope::new_broadcast(message, "sender");
 
         // Broadcast messages should be targeted to everyone
-        assert!(envelope.is_targeted_to("any-pane"));
-        assert!(envelope.is_targeted_to("another-pane"));
-        assert!(envelope.is_targeted_to(""));
+        assert!(envelope.is_targeted_to(&named("any-pane")));
+        assert!(envelope.is_targeted_to(&named("another-pane")));
+        assert!(envelope.is_targeted_to(&Recipient::default()));
     }
 
     #[test]
     fn test_is_broadcast_identification() {
         let message = create_test_message();
 
-        let targeted_envelope = MessageEnvelope::new_targeted(message.clone(), "pane", "sender");
+        let targeted_envelope =
+            MessageEnvelope::new_targeted(message.clone(), title("pane"), "sender");
         let broadcast_envelope = MessageEnvelope::new_broadcast(message, "sender");
 
         assert!(!targeted_envelope.is_broadcast());
         assert!(broadcast_envelope.is_broadcast());
     }
 
+    #[test]
+    fn test_with_ack_request() {
+        let envelope =
+            MessageEnvelope::new_targeted(create_test_message(), title("pane"), "sender");
+        assert_eq!(envelope.ack_id(), None);
+
+        let envelope = envelope.with_ack_request(42);
+        assert_eq!(envelope.message_id, Some(42));
+        assert!(envelope.ack_requested);
+        assert_eq!(envelope.ack_id(), Some(42));
+    }
+
+    #[test]
+    fn test_new_ack_targets_original_sender() {
+        let ack = MessageEnvelope::new_ack(42, "commander-plugin", COORDINATOR_SENDER);
+
+        assert_eq!(
+            ack.target,
+            Some(MessageTarget::Plugin("commander-plugin".to_string()))
+        );
+        assert_eq!(ack.sender, COORDINATOR_SENDER);
+        assert_eq!(
+            ack.coordination_message,
+            CoordinationMessage::Ack { message_id: 42 }
+        );
+        // Acknowledgements are never acknowledged themselves
+        assert_eq!(ack.ack_id(), None);
+    }
+
+    #[test]
+    fn test_envelope_without_ack_fields_still_parses() {
+        let json =
+            r#"{"coordination_message":{"type":"StartReview"},"sender":"cli","timestamp":1}"#;
+        let envelope: MessageEnvelope = serde_json::from_str(json).unwrap();
+
+        assert_eq!(envelope.protocol_version, 1);
+        assert_eq!(envelope.message_id, None);
+        assert!(!envelope.ack_requested);
+
+        // Fire-and-forget envelopes keep their original shape
+        let serialized = serde_json::to_value(&envelope).unwrap();
+        assert!(serialized.get("message_id").is_none());
+    }
+
     #[test]
     fn test_serialization_roundtrip() {
         let message = CoordinationMessage::PhaseTransition {
             from: WorkflowPhase::PlanningInProgress,
             to: WorkflowPhase::ImplementationInProgress,
         };
-        let envelope = MessageEnvelope::new_targeted(message, "test-pane", "test-sender");
+        let envelope = MessageEnvelope::new_targeted(message, title("test-pane"), "test-sender");
 
         // Serialize to JSON
         let serialized = serde_json::to_string(&envelope).expect("Serialization failed");
@@ -172,7 +382,7 @@ mod tests {
             serde_json::from_str(&serialized).expect("Deserialization failed");
 
         // Verify all fields match
-        assert_eq!(envelope.target_pane, deserialized.target_pane);
+        assert_eq!(envelope.target, deserialized.target);
         assert_eq!(envelope.sender, deserialized.sender);
         assert_eq!(envelope.timestamp, deserialized.timestamp);
 
@@ -215,47 +425,43 @@ mod tests {
         let message = create_test_message();
 
         // Test with empty strings
-        let envelope_empty_target = MessageEnvelope::new_targeted(message.clone(), "", "sender");
-        let envelope_empty_sender = MessageEnvelope::new_targeted(message.clone(), "pane", "");
+        let envelope_empty_target =
+            MessageEnvelope::new_targeted(message.clone(), title(""), "sender");
+        let envelope_empty_sender =
+            MessageEnvelope::new_targeted(message.clone(), title("pane"), "");
 
-        assert_eq!(envelope_empty_target.target_pane, Some("".to_string()));
+        assert_eq!(envelope_empty_target.target, Some(title("")));
         assert_eq!(envelope_empty_target.sender, "sender");
-        assert_eq!(envelope_empty_sender.target_pane, Some("pane".to_string()));
+        assert_eq!(envelope_empty_sender.target, Some(title("pane")));
         assert_eq!(envelope_empty_sender.sender, "");
 
         // Test with special characters
         let special_target = "pane-with-special-chars_123!@#";
         let special_sender = "sender.with.dots/and\\slashes";
         let envelope_special =
-            MessageEnvelope::new_targeted(message.clone(), special_target, special_sender);
+            MessageEnvelope::new_targeted(message.clone(), title(special_target), special_sender);
 
-        assert_eq!(
-            envelope_special.target_pane,
-            Some(special_target.to_string())
-        );
+        assert_eq!(envelope_special.target, Some(title(special_target)));
         assert_eq!(envelope_special.sender, special_sender);
-        assert!(envelope_special.is_targeted_to(special_target));
-        assert!(!envelope_special.is_targeted_to("different"));
+        assert!(envelope_special.is_targeted_to(&named(special_target)));
+        assert!(!envelope_special.is_targeted_to(&named("different")));
 
         // Test Unicode characters
         let unicode_target = "pane-测试-🚀";
         let unicode_sender = "sender-тест-🎯";
         let envelope_unicode =
-            MessageEnvelope::new_targeted(message, unicode_target, unicode_sender);
+            MessageEnvelope::new_targeted(message, title(unicode_target), unicode_sender);
 
-        assert_eq!(
-            envelope_unicode.target_pane,
-            Some(unicode_target.to_string())
-        );
+        assert_eq!(envelope_unicode.target, Some(title(unicode_target)));
         assert_eq!(envelope_unicode.sender, unicode_sender);
-        assert!(envelope_unicode.is_targeted_to(unicode_target));
+        assert!(envelope_unicode.is_targeted_to(&named(unicode_target)));
     }
 
     #[test]
     fn test_different_coordination_message_types() {
         let messages = vec![
             CoordinationMessage::StartPlanning {
-                task_id: 1,
+                task_id: TaskId::from(1),
                 task_description: "Plan task".to_string(),
             },
             CoordinationMessage::PlanReady {
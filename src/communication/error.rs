@@ -13,6 +13,8 @@ pub enum CommunicationError {
     PaneNotFound(PaneRole),
     /// Failed to discover panes
     PaneDiscoveryFailed(String),
+    /// The role's pane exited and cannot take input
+    PaneExited(PaneRole),
 }
 
 impl std::fmt::Display for CommunicationError {
@@ -33,6 +35,9 @@ impl std::fmt::Display for CommunicationError {
             CommunicationError::PaneDiscoveryFailed(msg) => {
                 write!(f, "Pane discovery failed: {}", msg)
             }
+            CommunicationError::PaneExited(role) => {
+                write!(f, "Pane for role {:?} has exited", role)
+            }
         }
     }
 }
@@ -124,6 +129,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pane_exited_display() {
+        let error = CommunicationError::PaneExited(PaneRole::Overseer);
+        assert_eq!(format!("{}", error), "Pane for role Overseer has exited");
+    }
+
     #[test]
     fn test_all_pane_roles_in_pane_not_found_error() {
         let roles = vec![
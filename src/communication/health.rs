@@ -0,0 +1,561 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use zellij_tile::prelude::{PaneId, PaneInfo, PaneManifest};
+
+use crate::pane_role::PaneRole;
+
+/// Restarts allowed per role before the coordinator stops recovering it
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// Errors in the restart policy configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestartConfigError {
+    UnknownPolicy { role: PaneRole, name: String },
+    InvalidMaxRestarts(String),
+}
+
+impl fmt::Display for RestartConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestartConfigError::UnknownPolicy { role, name } => write!(
+                f,
+                "Unknown {}_restart policy '{}' (expected none, rerun or reopen)",
+                role.config_key(),
+                name
+            ),
+            RestartConfigError::InvalidMaxRestarts(value) => {
+                write!(f, "Invalid max_restarts '{}'", value)
+            }
+        }
+    }
+}
+
+/// Liveness of a registered pane, as reported by the last `PaneUpdate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneHealth {
+    Running,
+    /// The pane's command exited; a held pane can be re-run in place
+    Exited {
+        exit_status: Option<i32>,
+        held: bool,
+    },
+}
+
+impl PaneHealth {
+    /// Health of a pane from its manifest entry
+    pub fn from_pane(pane: &PaneInfo) -> Self {
+        if pane.exited || pane.is_held {
+            PaneHealth::Exited {
+                exit_status: pane.exit_status,
+                held: pane.is_held,
+            }
+        } else {
+            PaneHealth::Running
+        }
+    }
+
+    /// Check whether the pane can take input
+    pub fn is_alive(&self) -> bool {
+        *self == PaneHealth::Running
+    }
+}
+
+/// A change in a role's pane health
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthEvent {
+    Exited {
+        role: PaneRole,
+        pane_id: PaneId,
+        exit_status: Option<i32>,
+        held: bool,
+    },
+    /// A pane that had exited is running again
+    Recovered { role: PaneRole, pane_id: PaneId },
+}
+
+/// What to do when a role's pane exits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// Leave the pane alone; in-flight work stays failed
+    #[default]
+    None,
+    /// Send the in-flight assistant command again, re-running a held pane first
+    Rerun,
+    /// Re-run the pane's own command with `rerun_command_pane`
+    Reopen,
+}
+
+impl RestartPolicy {
+    /// Parse a policy name from the plugin configuration
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "none" => Some(RestartPolicy::None),
+            "rerun" => Some(RestartPolicy::Rerun),
+            "reopen" => Some(RestartPolicy::Reopen),
+            _ => None,
+        }
+    }
+}
+
+/// Recovery step chosen for an exited pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// The policy is `none`, or the pane cannot be re-run
+    Nothing,
+    /// Re-run the held terminal pane
+    ReopenPane(u32),
+    /// Re-run the held terminal pane and relaunch the assistant once it is back
+    RelaunchAssistant(u32),
+    /// Type the assistant command into the terminal pane's shell again
+    RetypeAssistant(u32),
+    /// The role used up its restarts
+    GaveUp { restarts: u32 },
+}
+
+/// Tracks pane liveness per role and decides how exited panes are recovered
+#[derive(Debug, Clone)]
+pub struct HealthTracker {
+    policies: HashMap<PaneRole, RestartPolicy>,
+    max_restarts: u32,
+    health: HashMap<PaneRole, (PaneId, PaneHealth)>,
+    restarts: HashMap<PaneRole, u32>,
+}
+
+impl Default for HealthTracker {
+    fn default() -> Self {
+        Self {
+            policies: HashMap::new(),
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            health: HashMap::new(),
+            restarts: HashMap::new(),
+        }
+    }
+}
+
+impl HealthTracker {
+    /// Read `<role>_restart` and `max_restarts` from the plugin configuration
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+    ) -> Result<Self, RestartConfigError> {
+        let mut tracker = Self::default();
+
+        for role in PaneRole::ALL.iter() {
+            if let Some(name) = configuration.get(&format!("{}_restart", role.config_key())) {
+                let policy =
+                    RestartPolicy::from_name(name).ok_or(RestartConfigError::UnknownPolicy {
+                        role: *role,
+                        name: name.clone(),
+                    })?;
+                tracker.set_policy(*role, policy);
+            }
+        }
+
+        if let Some(value) = configuration.get("max_restarts") {
+            tracker.max_restarts = value
+                .trim()
+                .parse()
+                .map_err(|_| RestartConfigError::InvalidMaxRestarts(value.clone()))?;
+        }
+
+        Ok(tracker)
+    }
+
+    /// Set the restart policy for a role
+    pub fn set_policy(&mut self, role: PaneRole, policy: RestartPolicy) {
+        self.policies.insert(role, policy);
+    }
+
+    /// Restart policy for a role
+    pub fn policy(&self, role: PaneRole) -> RestartPolicy {
+        self.policies.get(&role).copied().unwrap_or_default()
+    }
+
+    /// Last known health of a role's pane
+    pub fn health(&self, role: PaneRole) -> Option<PaneHealth> {
+        self.health.get(&role).map(|(_, health)| *health)
+    }
+
+    /// Check whether a role's pane can take input; unknown panes are assumed alive
+    pub fn is_alive(&self, role: PaneRole) -> bool {
+        self.health(role).is_none_or(|health| health.is_alive())
+    }
+
+    /// Refresh health for the registered panes and report what changed
+    ///
+    /// Panes that were already exited when first seen are reported as exits too.
+    pub fn update(
+        &mut self,
+        registry: &HashMap<PaneRole, PaneId>,
+        manifest: &PaneManifest,
+    ) -> Vec<HealthEvent> {
+        let mut events = Vec::new();
+
+        for role in PaneRole::ALL.iter() {
+            let pane_id = match registry.get(role) {
+                Some(pane_id) => *pane_id,
+                None => {
+                    self.health.remove(role);
+                    continue;
+                }
+            };
+
+            let pane = match find_pane(manifest, pane_id) {
+                Some(pane) => pane,
+                None => continue,
+            };
+            let current = PaneHealth::from_pane(pane);
+            let previous = self
+                .health
+                .insert(*role, (pane_id, current))
+                .filter(|(id, _)| *id == pane_id)
+                .map(|(_, health)| health);
+
+            match (previous, current) {
+                (Some(before), after) if before == after => {}
+                (Some(PaneHealth::Exited { .. }), PaneHealth::Running) => {
+                    events.push(HealthEvent::Recovered {
+                        role: *role,
+                        pane_id,
+                    })
+                }
+                (_, PaneHealth::Exited { exit_status, held }) => events.push(HealthEvent::Exited {
+                    role: *role,
+                    pane_id,
+                    exit_status,
+                    held,
+                }),
+                _ => {}
+            }
+        }
+
+        events
+    }
+
+    /// Choose how to recover a role whose pane exited, counting the restart
+    ///
+    /// Only held panes can be re-run in place. A pane that is not held is
+    /// still at its shell prompt, so `rerun` types the assistant command into
+    /// it again and the pane is treated as running.
+    pub fn recover(&mut self, role: PaneRole, pane_id: PaneId, held: bool) -> Recovery {
+        let recovery = match (self.policy(role), pane_id) {
+            (RestartPolicy::Rerun, PaneId::Terminal(id)) if held => Recovery::RelaunchAssistant(id),
+            (RestartPolicy::Rerun, PaneId::Terminal(id)) => Recovery::RetypeAssistant(id),
+            (RestartPolicy::Reopen, PaneId::Terminal(id)) if held => Recovery::ReopenPane(id),
+            _ => return Recovery::Nothing,
+        };
+
+        let restarts = self.restarts.entry(role).or_insert(0);
+        if *restarts >= self.max_restarts {
+            return Recovery::GaveUp {
+                restarts: *restarts,
+            };
+        }
+        *restarts += 1;
+
+        if let Recovery::RetypeAssistant(_) = recovery {
+            self.health.insert(role, (pane_id, PaneHealth::Running));
+        }
+        recovery
+    }
+
+    /// Restarts counted for a role since the last reset
+    pub fn restarts(&self, role: PaneRole) -> u32 {
+        self.restarts.get(&role).copied().unwrap_or(0)
+    }
+
+    /// Forget the restarts of every role
+    ///
+    /// Called once the workflow makes progress or switches task, so that
+    /// `max_restarts` limits crash loops rather than the lifetime of the plugin.
+    pub fn reset_restarts(&mut self) {
+        self.restarts.clear();
+    }
+}
+
+/// Manifest entry for a pane ID
+fn find_pane(manifest: &PaneManifest, pane_id: PaneId) -> Option<&PaneInfo> {
+    let (id, is_plugin) = match pane_id {
+        PaneId::Terminal(id) => (id, false),
+        PaneId::Plugin(id) => (id, true),
+    };
+
+    manifest
+        .panes
+        .values()
+        .flatten()
+        .find(|pane| pane.id == id && pane.is_plugin == is_plugin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(id: u32, exited: bool, exit_status: Option<i32>) -> PaneInfo {
+        PaneInfo {
+            id,
+            exited,
+            exit_status,
+            is_held: exited,
+            ..PaneInfo::default()
+        }
+    }
+
+    fn manifest(panes: Vec<PaneInfo>) -> PaneManifest {
+        let mut tabs = HashMap::new();
+        tabs.insert(0, panes);
+        PaneManifest { panes: tabs }
+    }
+
+    fn registry(entries: &[(PaneRole, PaneId)]) -> HashMap<PaneRole, PaneId> {
+        entries.iter().copied().collect()
+    }
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pane_health_from_pane() {
+        assert_eq!(
+            PaneHealth::from_pane(&terminal(1, false, None)),
+            PaneHealth::Running
+        );
+        assert_eq!(
+            PaneHealth::from_pane(&terminal(1, true, Some(2))),
+            PaneHealth::Exited {
+                exit_status: Some(2),
+                held: true
+            }
+        );
+        assert!(!PaneHealth::from_pane(&terminal(1, true, Some(0))).is_alive());
+    }
+
+    #[test]
+    fn test_update_reports_exit_once() {
+        let mut tracker = HealthTracker::default();
+        let roles = registry(&[(PaneRole::Overseer, PaneId::Terminal(1))]);
+
+        assert!(tracker
+            .update(&roles, &manifest(vec![terminal(1, false, None)]))
+            .is_empty());
+        assert!(tracker.is_alive(PaneRole::Overseer));
+
+        let exited = manifest(vec![terminal(1, true, Some(1))]);
+        assert_eq!(
+            tracker.update(&roles, &exited),
+            vec![HealthEvent::Exited {
+                role: PaneRole::Overseer,
+                pane_id: PaneId::Terminal(1),
+                exit_status: Some(1),
+                held: true,
+            }]
+        );
+        assert!(!tracker.is_alive(PaneRole::Overseer));
+
+        // The same state in the next update is not a new exit
+        assert!(tracker.update(&roles, &exited).is_empty());
+    }
+
+    #[test]
+    fn test_update_reports_recovery() {
+        let mut tracker = HealthTracker::default();
+        let roles = registry(&[(PaneRole::Commander, PaneId::Terminal(2))]);
+
+        tracker.update(&roles, &manifest(vec![terminal(2, true, Some(1))]));
+        let events = tracker.update(&roles, &manifest(vec![terminal(2, false, None)]));
+
+        assert_eq!(
+            events,
+            vec![HealthEvent::Recovered {
+                role: PaneRole::Commander,
+                pane_id: PaneId::Terminal(2),
+            }]
+        );
+        assert!(tracker.is_alive(PaneRole::Commander));
+    }
+
+    #[test]
+    fn test_update_reports_pane_exited_when_first_seen() {
+        let mut tracker = HealthTracker::default();
+        let roles = registry(&[(PaneRole::Overseer, PaneId::Terminal(1))]);
+
+        let events = tracker.update(&roles, &manifest(vec![terminal(1, true, None)]));
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn test_update_forgets_unregistered_roles() {
+        let mut tracker = HealthTracker::default();
+        let roles = registry(&[(PaneRole::Overseer, PaneId::Terminal(1))]);
+        tracker.update(&roles, &manifest(vec![terminal(1, true, None)]));
+
+        tracker.update(&HashMap::new(), &manifest(Vec::new()));
+
+        assert_eq!(tracker.health(PaneRole::Overseer), None);
+        assert!(tracker.is_alive(PaneRole::Overseer));
+    }
+
+    #[test]
+    fn test_update_treats_new_pane_for_role_as_fresh() {
+        let mut tracker = HealthTracker::default();
+        tracker.update(
+            &registry(&[(PaneRole::Overseer, PaneId::Terminal(1))]),
+            &manifest(vec![terminal(1, true, None)]),
+        );
+
+        let events = tracker.update(
+            &registry(&[(PaneRole::Overseer, PaneId::Terminal(5))]),
+            &manifest(vec![terminal(5, false, None)]),
+        );
+
+        assert!(events.is_empty());
+        assert!(tracker.is_alive(PaneRole::Overseer));
+    }
+
+    #[test]
+    fn test_update_ignores_plugin_with_same_id() {
+        let mut tracker = HealthTracker::default();
+        let roles = registry(&[(PaneRole::Overseer, PaneId::Terminal(1))]);
+        let plugin = PaneInfo {
+            id: 1,
+            is_plugin: true,
+            exited: true,
+            ..PaneInfo::default()
+        };
+
+        assert!(tracker.update(&roles, &manifest(vec![plugin])).is_empty());
+        assert_eq!(tracker.health(PaneRole::Overseer), None);
+    }
+
+    #[test]
+    fn test_from_configuration() {
+        let config = configuration(&[
+            ("overseer_restart", "rerun"),
+            ("commander_restart", "Reopen"),
+            ("max_restarts", "5"),
+        ]);
+        let tracker = HealthTracker::from_configuration(&config).unwrap();
+
+        assert_eq!(tracker.policy(PaneRole::Overseer), RestartPolicy::Rerun);
+        assert_eq!(tracker.policy(PaneRole::Commander), RestartPolicy::Reopen);
+        assert_eq!(tracker.policy(PaneRole::Editor), RestartPolicy::None);
+        assert_eq!(tracker.max_restarts, 5);
+    }
+
+    #[test]
+    fn test_from_configuration_errors() {
+        let error =
+            HealthTracker::from_configuration(&configuration(&[("overseer_restart", "always")]))
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown overseer_restart policy 'always' (expected none, rerun or reopen)"
+        );
+
+        let error = HealthTracker::from_configuration(&configuration(&[("max_restarts", "-1")]))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RestartConfigError::InvalidMaxRestarts("-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_recover_follows_policy() {
+        let mut tracker = HealthTracker::default();
+        assert_eq!(
+            tracker.recover(PaneRole::Overseer, PaneId::Terminal(1), true),
+            Recovery::Nothing
+        );
+
+        tracker.set_policy(PaneRole::Overseer, RestartPolicy::Rerun);
+        tracker.set_policy(PaneRole::Commander, RestartPolicy::Reopen);
+        assert_eq!(
+            tracker.recover(PaneRole::Overseer, PaneId::Terminal(1), true),
+            Recovery::RelaunchAssistant(1)
+        );
+        assert_eq!(
+            tracker.recover(PaneRole::Commander, PaneId::Terminal(2), true),
+            Recovery::ReopenPane(2)
+        );
+    }
+
+    #[test]
+    fn test_recover_needs_terminal_pane() {
+        let mut tracker = HealthTracker::default();
+        tracker.set_policy(PaneRole::Overseer, RestartPolicy::Rerun);
+        tracker.set_policy(PaneRole::Commander, RestartPolicy::Reopen);
+
+        assert_eq!(
+            tracker.recover(PaneRole::Overseer, PaneId::Plugin(1), true),
+            Recovery::Nothing
+        );
+        // Only a held pane can be re-run in place
+        assert_eq!(
+            tracker.recover(PaneRole::Commander, PaneId::Terminal(2), false),
+            Recovery::Nothing
+        );
+        assert_eq!(tracker.restarts(PaneRole::Commander), 0);
+    }
+
+    #[test]
+    fn test_rerun_retypes_assistant_in_live_pane() {
+        let mut tracker = HealthTracker::default();
+        tracker.set_policy(PaneRole::Overseer, RestartPolicy::Rerun);
+        let roles = registry(&[(PaneRole::Overseer, PaneId::Terminal(1))]);
+        let exited = PaneInfo {
+            is_held: false,
+            ..terminal(1, true, Some(1))
+        };
+        tracker.update(&roles, &manifest(vec![exited]));
+        assert!(!tracker.is_alive(PaneRole::Overseer));
+
+        assert_eq!(
+            tracker.recover(PaneRole::Overseer, PaneId::Terminal(1), false),
+            Recovery::RetypeAssistant(1)
+        );
+        assert!(tracker.is_alive(PaneRole::Overseer));
+        assert_eq!(tracker.restarts(PaneRole::Overseer), 1);
+    }
+
+    #[test]
+    fn test_reset_restarts_renews_budget() {
+        let mut tracker =
+            HealthTracker::from_configuration(&configuration(&[("max_restarts", "1")])).unwrap();
+        tracker.set_policy(PaneRole::Commander, RestartPolicy::Reopen);
+
+        tracker.recover(PaneRole::Commander, PaneId::Terminal(2), true);
+        assert_eq!(
+            tracker.recover(PaneRole::Commander, PaneId::Terminal(2), true),
+            Recovery::GaveUp { restarts: 1 }
+        );
+
+        tracker.reset_restarts();
+        assert_eq!(tracker.restarts(PaneRole::Commander), 0);
+        assert_eq!(
+            tracker.recover(PaneRole::Commander, PaneId::Terminal(2), true),
+            Recovery::ReopenPane(2)
+        );
+    }
+
+    #[test]
+    fn test_recover_gives_up_after_max_restarts() {
+        let mut tracker =
+            HealthTracker::from_configuration(&configuration(&[("max_restarts", "2")])).unwrap();
+        tracker.set_policy(PaneRole::Commander, RestartPolicy::Reopen);
+
+        for _ in 0..2 {
+            assert_eq!(
+                tracker.recover(PaneRole::Commander, PaneId::Terminal(2), true),
+                Recovery::ReopenPane(2)
+            );
+        }
+        assert_eq!(
+            tracker.recover(PaneRole::Commander, PaneId::Terminal(2), true),
+            Recovery::GaveUp { restarts: 2 }
+        );
+    }
+}
@@ -1,9 +1,24 @@
+#[allow(clippy::module_inception)]
 pub mod communication;
 pub mod envelope;
 pub mod error;
+pub mod health;
+pub mod protocol;
+pub mod queue;
 pub mod router;
+pub mod selector;
+pub mod spawner;
 
 pub use communication::{Communication, ParsedMessage};
-pub use envelope::MessageEnvelope;
+pub use envelope::{MessageEnvelope, MessageTarget, Recipient};
 pub use error::CommunicationError;
-pub use router::MessageRouter;
+pub use health::{
+    HealthEvent, HealthTracker, PaneHealth, Recovery, RestartConfigError, RestartPolicy,
+};
+pub use protocol::{LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION};
+pub use queue::{DeadLetter, DeliveryTick, OutboundQueue, RetryConfigError, RetryPolicy};
+pub use router::{MessageRouter, Transport};
+pub use selector::{
+    AmbiguousRole, PaneMatches, PaneScope, PaneSelectors, RoleSelector, SelectorConfigError,
+};
+pub use spawner::{PaneSpawner, SpawnConfigError, SpawnSpec, SpawnState};
@@ -0,0 +1,672 @@
+use serde_json::{json, Map, Value};
+
+use super::envelope::MessageEnvelope;
+use crate::task_id::MAX_TASK_ID_LEN;
+
+/// Wire protocol version written into every envelope the coordinator sends
+///
+/// Version 1 envelopes had no `protocol_version` field and encoded messages
+/// externally tagged (`{"PlanReady": {...}}` or `"StartReview"`); version 2
+/// tags them with a `type` field (`{"type": "PlanReady", ...}`); version 3
+/// replaces the `target_pane` title with a structured `target`.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// First version addressing envelopes with `target` instead of `target_pane`
+pub const TARGET_PROTOCOL_VERSION: u32 = 3;
+
+/// First version tagging messages with a `type` field
+pub const TAGGED_PROTOCOL_VERSION: u32 = 2;
+
+/// Version assumed for envelopes without a `protocol_version` field
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+/// Name of the field carrying a message's variant in version 2
+pub const TYPE_FIELD: &str = "type";
+
+/// Serde default for envelopes that predate the `protocol_version` field
+pub fn legacy_protocol_version() -> u32 {
+    LEGACY_PROTOCOL_VERSION
+}
+
+/// Read and check an envelope's protocol version
+///
+/// A missing field means version 1. Versions newer than this build are
+/// rejected, so a newer sender gets a clear error instead of a field mismatch.
+pub fn envelope_version(envelope: &Value) -> Result<u32, serde_json::Error> {
+    let version = match envelope.get("protocol_version") {
+        None | Some(Value::Null) => LEGACY_PROTOCOL_VERSION,
+        Some(value) => value
+            .as_u64()
+            .filter(|v| *v >= u64::from(LEGACY_PROTOCOL_VERSION) && *v <= u64::from(u32::MAX))
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!("invalid protocol_version {}", value))
+            })? as u32,
+    };
+
+    if version > PROTOCOL_VERSION {
+        return Err(serde::de::Error::custom(format!(
+            "unsupported protocol version {} (expected at most {})",
+            version, PROTOCOL_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// Convert a message in either encoding to the tagged form
+///
+/// Tagged messages are returned unchanged, so this is safe to apply to any
+/// incoming message.
+pub fn upgrade_message(message: Value) -> Value {
+    match message {
+        Value::Object(ref fields) if fields.contains_key(TYPE_FIELD) => message,
+        Value::String(name) => json!({ TYPE_FIELD: name }),
+        Value::Object(fields) if fields.len() == 1 => {
+            let (name, body) = fields.into_iter().next().expect("one field");
+            match body {
+                Value::Object(mut body) => {
+                    body.insert(TYPE_FIELD.to_string(), Value::String(name));
+                    Value::Object(body)
+                }
+                body => {
+                    let mut fields = Map::new();
+                    fields.insert(name, body);
+                    Value::Object(fields)
+                }
+            }
+        }
+        other => other,
+    }
+}
+
+/// Convert an envelope of any supported version to the current layout
+///
+/// The message is upgraded to the tagged form and a `target_pane` title
+/// becomes a `title` target.
+pub fn upgrade_envelope(mut envelope: Value) -> Value {
+    if let Value::Object(ref mut fields) = envelope {
+        if let Some(message) = fields.remove("coordination_message") {
+            fields.insert("coordination_message".to_string(), upgrade_message(message));
+        }
+        if let Some(target_pane) = fields.remove("target_pane") {
+            if !fields.contains_key("target") {
+                let target = match target_pane {
+                    Value::String(title) => json!({ "title": title }),
+                    other => other,
+                };
+                fields.insert("target".to_string(), target);
+            }
+        }
+    }
+    envelope
+}
+
+/// Convert a tagged message to the externally tagged version 1 form
+pub fn downgrade_message(message: Value) -> Value {
+    match message {
+        Value::Object(mut fields) => match fields.remove(TYPE_FIELD) {
+            Some(Value::String(name)) if fields.is_empty() => Value::String(name),
+            Some(Value::String(name)) => json!({ name: Value::Object(fields) }),
+            Some(other) => {
+                fields.insert(TYPE_FIELD.to_string(), other);
+                Value::Object(fields)
+            }
+            None => Value::Object(fields),
+        },
+        other => other,
+    }
+}
+
+/// Serialize an envelope for a receiver that speaks `version`
+pub fn encode_envelope(envelope: &MessageEnvelope, version: u32) -> serde_json::Result<String> {
+    if version >= PROTOCOL_VERSION {
+        let mut envelope = envelope.clone();
+        envelope.protocol_version = PROTOCOL_VERSION;
+        return serde_json::to_string(&envelope);
+    }
+
+    let mut value = serde_json::to_value(envelope)?;
+    if let Value::Object(ref mut fields) = value {
+        // Older receivers only know pane titles
+        fields.remove("target");
+        let target_pane = envelope.target.as_ref().map(|target| target.to_string());
+        fields.insert("target_pane".to_string(), json!(target_pane));
+
+        if version >= TAGGED_PROTOCOL_VERSION {
+            fields.insert("protocol_version".to_string(), json!(version));
+        } else {
+            fields.remove("protocol_version");
+            if let Some(message) = fields.remove("coordination_message") {
+                fields.insert(
+                    "coordination_message".to_string(),
+                    downgrade_message(message),
+                );
+            }
+        }
+    }
+    serde_json::to_string(&value)
+}
+
+/// JSON type of a message field, as published in the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    String,
+    Integer,
+    OptionalInteger,
+    Phase,
+    Role,
+    TaskId,
+}
+
+impl FieldType {
+    fn schema(self) -> Value {
+        match self {
+            FieldType::String => json!({ "type": "string" }),
+            FieldType::Integer => json!({ "type": "integer", "minimum": 0 }),
+            FieldType::OptionalInteger => json!({ "type": ["integer", "null"] }),
+            FieldType::Phase => json!({ "$ref": "#/$defs/WorkflowPhase" }),
+            FieldType::Role => json!({ "$ref": "#/$defs/PaneRole" }),
+            FieldType::TaskId => json!({ "$ref": "#/$defs/TaskId" }),
+        }
+    }
+}
+
+/// Published shape of one `CoordinationMessage` variant
+struct MessageSchema {
+    name: &'static str,
+    description: &'static str,
+    fields: &'static [(&'static str, FieldType)],
+}
+
+const MESSAGE_SCHEMAS: &[MessageSchema] = &[
+    MessageSchema {
+        name: "StartPlanning",
+        description: "Start planning a task",
+        fields: &[
+            ("task_id", FieldType::TaskId),
+            ("task_description", FieldType::String),
+        ],
+    },
+    MessageSchema {
+        name: "PlanReady",
+        description: "The todo list has been written",
+        fields: &[("todo_file_path", FieldType::String)],
+    },
+    MessageSchema {
+        name: "StartImplementation",
+        description: "Start working through the todo list",
+        fields: &[],
+    },
+    MessageSchema {
+        name: "TaskCompleted",
+        description: "A todo item has been completed",
+        fields: &[("task_id", FieldType::String)],
+    },
+    MessageSchema {
+        name: "AllTasksComplete",
+        description: "Every todo item has been completed",
+        fields: &[],
+    },
+    MessageSchema {
+        name: "StartReview",
+        description: "Start reviewing the implementation",
+        fields: &[],
+    },
+    MessageSchema {
+        name: "ReviewComplete",
+        description: "The review has been written",
+        fields: &[("review_file_path", FieldType::String)],
+    },
+    MessageSchema {
+        name: "PhaseTransition",
+        description: "The workflow moved to another phase",
+        fields: &[("from", FieldType::Phase), ("to", FieldType::Phase)],
+    },
+    MessageSchema {
+        name: "AssistantFailed",
+        description: "An assistant's pane exited while it had work in flight",
+        fields: &[
+            ("role", FieldType::Role),
+            ("phase", FieldType::Phase),
+            ("exit_status", FieldType::OptionalInteger),
+        ],
+    },
+    MessageSchema {
+        name: "Ack",
+        description: "Receipt for an envelope that requested an acknowledgement",
+        fields: &[("message_id", FieldType::Integer)],
+    },
+    MessageSchema {
+        name: "FileChanged",
+        description: "A watched file changed",
+        fields: &[
+            ("file_path", FieldType::String),
+            ("event_type", FieldType::String),
+        ],
+    },
+];
+
+const WORKFLOW_PHASES: &[&str] = &[
+    "Initializing",
+    "PlanningInProgress",
+    "PlanReady",
+    "ImplementationInProgress",
+    "ImplementationComplete",
+    "ReviewInProgress",
+    "ReviewComplete",
+    "Finished",
+];
+
+const PANE_ROLES: &[&str] = &["Overseer", "Commander", "TaskList", "Review", "Editor"];
+
+/// Pattern of a valid `TaskId` string
+fn task_id_pattern() -> String {
+    format!("^[A-Za-z0-9][A-Za-z0-9._-]{{0,{}}}$", MAX_TASK_ID_LEN - 1)
+}
+
+/// Schema of a single-key object such as `{"role": "Overseer"}`
+fn target_schema(key: &str, description: &str, value: Value) -> Value {
+    json!({
+        "description": description,
+        "type": "object",
+        "required": [key],
+        "properties": { key: value },
+        "additionalProperties": false
+    })
+}
+
+/// JSON Schema (draft 2020-12) describing every message of the current protocol
+///
+/// A payload is either an envelope or a bare message. The published copy
+/// lives in `schema/zzz-protocol.schema.json`.
+pub fn json_schema() -> Value {
+    let mut defs = Map::new();
+
+    defs.insert(
+        "MessageEnvelope".to_string(),
+        json!({
+            "description": "Coordination message with routing metadata",
+            "type": "object",
+            "required": ["protocol_version", "coordination_message", "sender", "timestamp"],
+            "properties": {
+                "protocol_version": { "const": PROTOCOL_VERSION },
+                "target": {
+                    "description": "Receiver of the message; null or missing broadcasts it",
+                    "oneOf": [{ "type": "null" }, { "$ref": "#/$defs/MessageTarget" }]
+                },
+                "coordination_message": { "$ref": "#/$defs/CoordinationMessage" },
+                "sender": { "type": "string" },
+                "timestamp": {
+                    "description": "Unix timestamp in seconds",
+                    "type": "integer",
+                    "minimum": 0
+                },
+                "message_id": { "type": "integer", "minimum": 0 },
+                "ack_requested": {
+                    "description": "Whether the receiver must reply with an Ack for message_id",
+                    "type": "boolean"
+                }
+            }
+        }),
+    );
+
+    let variants: Vec<Value> = MESSAGE_SCHEMAS
+        .iter()
+        .map(|message| json!({ "$ref": format!("#/$defs/{}", message.name) }))
+        .collect();
+    defs.insert(
+        "CoordinationMessage".to_string(),
+        json!({ "oneOf": variants }),
+    );
+
+    for message in MESSAGE_SCHEMAS {
+        let mut properties = Map::new();
+        properties.insert(TYPE_FIELD.to_string(), json!({ "const": message.name }));
+        let mut required = vec![TYPE_FIELD];
+        for (name, field_type) in message.fields {
+            properties.insert(name.to_string(), field_type.schema());
+            if *field_type != FieldType::OptionalInteger {
+                required.push(name);
+            }
+        }
+
+        defs.insert(
+            message.name.to_string(),
+            json!({
+                "description": message.description,
+                "type": "object",
+                "required": required,
+                "properties": properties
+            }),
+        );
+    }
+
+    defs.insert(
+        "WorkflowPhase".to_string(),
+        json!({ "enum": WORKFLOW_PHASES }),
+    );
+    defs.insert("PaneRole".to_string(), json!({ "enum": PANE_ROLES }));
+    defs.insert(
+        "TaskId".to_string(),
+        json!({
+            "description": "Task identifier such as PROJ-1234; numbers are accepted from older senders",
+            "oneOf": [
+                { "type": "string", "pattern": task_id_pattern() },
+                { "type": "integer", "minimum": 0 }
+            ]
+        }),
+    );
+    defs.insert(
+        "MessageTarget".to_string(),
+        json!({
+            "oneOf": [
+                target_schema("role", "Pane registered for the role", json!({ "$ref": "#/$defs/PaneRole" })),
+                target_schema("pane", "A specific terminal or plugin pane", json!({ "$ref": "#/$defs/PaneId" })),
+                target_schema(
+                    "plugin",
+                    "A plugin by plugin URL, URL file stem or pane title",
+                    json!({ "type": "string" })
+                ),
+                target_schema("title", "A pane by title", json!({ "type": "string" }))
+            ]
+        }),
+    );
+    defs.insert(
+        "PaneId".to_string(),
+        json!({
+            "oneOf": [
+                target_schema("Terminal", "Terminal pane ID", json!({ "type": "integer", "minimum": 0 })),
+                target_schema("Plugin", "Plugin pane ID", json!({ "type": "integer", "minimum": 0 }))
+            ]
+        }),
+    );
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "zzz-protocol.schema.json",
+        "title": "ZZZ coordination protocol",
+        "description": format!(
+            "Payloads piped to the zzz coordinator, protocol version {}",
+            PROTOCOL_VERSION
+        ),
+        "oneOf": [
+            { "$ref": "#/$defs/MessageEnvelope" },
+            { "$ref": "#/$defs/CoordinationMessage" }
+        ],
+        "$defs": defs
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordination_message::CoordinationMessage;
+    use crate::pane_role::PaneRole;
+    use crate::task_id::TaskId;
+    use crate::workflow_phase::WorkflowPhase;
+    use std::collections::BTreeSet;
+
+    const PUBLISHED_SCHEMA: &str = include_str!("../../schema/zzz-protocol.schema.json");
+
+    fn every_message() -> Vec<CoordinationMessage> {
+        vec![
+            CoordinationMessage::StartPlanning {
+                task_id: TaskId::parse("PROJ-7").unwrap(),
+                task_description: "Add a login page".to_string(),
+            },
+            CoordinationMessage::PlanReady {
+                todo_file_path: ".zzz/task-7/todo-list.md".to_string(),
+            },
+            CoordinationMessage::StartImplementation,
+            CoordinationMessage::TaskCompleted {
+                task_id: "1.2".to_string(),
+            },
+            CoordinationMessage::AllTasksComplete,
+            CoordinationMessage::StartReview,
+            CoordinationMessage::ReviewComplete {
+                review_file_path: ".zzz/task-7/review.md".to_string(),
+            },
+            CoordinationMessage::PhaseTransition {
+                from: WorkflowPhase::PlanReady,
+                to: WorkflowPhase::ImplementationInProgress,
+            },
+            CoordinationMessage::AssistantFailed {
+                role: PaneRole::Commander,
+                phase: WorkflowPhase::ImplementationInProgress,
+                exit_status: Some(1),
+            },
+            CoordinationMessage::Ack { message_id: 42 },
+            CoordinationMessage::FileChanged {
+                file_path: "src/lib.rs".to_string(),
+                event_type: "modified".to_string(),
+            },
+        ]
+    }
+
+    /// Minimal check of a value against one of the schema's field types
+    fn matches_field(field_type: FieldType, value: &Value) -> bool {
+        match field_type {
+            FieldType::String => value.is_string(),
+            FieldType::Integer => value.is_u64(),
+            FieldType::OptionalInteger => value.is_null() || value.is_i64(),
+            FieldType::Phase => value
+                .as_str()
+                .is_some_and(|phase| WORKFLOW_PHASES.contains(&phase)),
+            FieldType::Role => value
+                .as_str()
+                .is_some_and(|role| PANE_ROLES.contains(&role)),
+            FieldType::TaskId => {
+                value.is_u64()
+                    || value.as_str().is_some_and(|id| {
+                        regex::Regex::new(&task_id_pattern()).unwrap().is_match(id)
+                    })
+            }
+        }
+    }
+
+    #[test]
+    fn test_messages_serialize_with_type_tag() {
+        let value = serde_json::to_value(CoordinationMessage::StartReview).unwrap();
+        assert_eq!(value, json!({ "type": "StartReview" }));
+
+        let value = serde_json::to_value(CoordinationMessage::PlanReady {
+            todo_file_path: "todo.md".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({ "type": "PlanReady", "todo_file_path": "todo.md" })
+        );
+    }
+
+    #[test]
+    fn test_message_names_match_type_tag() {
+        for message in every_message() {
+            let value = serde_json::to_value(&message).unwrap();
+            assert_eq!(value["type"], message.name());
+        }
+    }
+
+    #[test]
+    fn test_every_message_matches_schema() {
+        let messages = every_message();
+        let sampled: BTreeSet<String> = messages
+            .iter()
+            .map(|m| serde_json::to_value(m).unwrap()[TYPE_FIELD].to_string())
+            .collect();
+        let published: BTreeSet<String> = MESSAGE_SCHEMAS
+            .iter()
+            .map(|m| Value::from(m.name).to_string())
+            .collect();
+        assert_eq!(sampled, published);
+
+        for message in messages {
+            let mut value = serde_json::to_value(&message).unwrap();
+            let fields = value.as_object_mut().unwrap();
+            let name = fields.remove(TYPE_FIELD).unwrap();
+            let schema = MESSAGE_SCHEMAS
+                .iter()
+                .find(|schema| name == schema.name)
+                .unwrap();
+
+            assert_eq!(fields.len(), schema.fields.len(), "{}", schema.name);
+            for (field, field_type) in schema.fields {
+                assert!(
+                    matches_field(*field_type, &fields[*field]),
+                    "{}.{}",
+                    schema.name,
+                    field
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_task_id_schema_matches_validation() {
+        for id in ["PROJ-1234", "7", "fix_login.v2"].iter() {
+            assert!(TaskId::parse(id).is_ok());
+            assert!(matches_field(FieldType::TaskId, &json!(id)));
+        }
+        for id in ["../etc", ".hidden", "PROJ 1", ""].iter() {
+            assert!(TaskId::parse(id).is_err());
+            assert!(!matches_field(FieldType::TaskId, &json!(id)));
+        }
+        assert!(matches_field(FieldType::TaskId, &json!(42)));
+        assert!(!matches_field(FieldType::TaskId, &json!(-1)));
+    }
+
+    #[test]
+    fn test_schema_enums_match_rust_types() {
+        for phase in WORKFLOW_PHASES {
+            let _: WorkflowPhase = serde_json::from_value(json!(phase)).unwrap();
+        }
+        let roles: Vec<Value> = PaneRole::ALL
+            .iter()
+            .map(|role| serde_json::to_value(role).unwrap())
+            .collect();
+        assert_eq!(roles, json!(PANE_ROLES).as_array().unwrap().clone());
+    }
+
+    /// Set `ZZZ_UPDATE_SCHEMA=1` to rewrite the published schema instead of checking it
+    #[test]
+    fn test_published_schema_is_current() {
+        let schema = json_schema();
+        if std::env::var_os("ZZZ_UPDATE_SCHEMA").is_some() {
+            let path = concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/schema/zzz-protocol.schema.json"
+            );
+            let content = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+            std::fs::write(path, content).unwrap();
+            return;
+        }
+
+        let published: Value = serde_json::from_str(PUBLISHED_SCHEMA).unwrap();
+        assert_eq!(
+            published, schema,
+            "schema/zzz-protocol.schema.json is out of date; rerun this test with ZZZ_UPDATE_SCHEMA=1"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_message_accepts_both_encodings() {
+        for message in every_message() {
+            let tagged = serde_json::to_value(&message).unwrap();
+            let legacy = downgrade_message(tagged.clone());
+            assert!(legacy.get(TYPE_FIELD).is_none());
+
+            assert_eq!(upgrade_message(legacy.clone()), tagged);
+            assert_eq!(upgrade_message(tagged.clone()), tagged);
+
+            let parsed: CoordinationMessage =
+                serde_json::from_value(upgrade_message(legacy)).unwrap();
+            assert_eq!(parsed, message);
+        }
+    }
+
+    #[test]
+    fn test_downgrade_produces_externally_tagged_form() {
+        assert_eq!(
+            downgrade_message(json!({ "type": "AllTasksComplete" })),
+            json!("AllTasksComplete")
+        );
+        assert_eq!(
+            downgrade_message(json!({ "type": "Ack", "message_id": 3 })),
+            json!({ "Ack": { "message_id": 3 } })
+        );
+    }
+
+    #[test]
+    fn test_envelope_version_defaults_and_limits() {
+        assert_eq!(envelope_version(&json!({})).unwrap(), 1);
+        assert_eq!(
+            envelope_version(&json!({ "protocol_version": 2 })).unwrap(),
+            2
+        );
+
+        let error = envelope_version(&json!({ "protocol_version": 4 })).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unsupported protocol version 4 (expected at most 3)"
+        );
+
+        for invalid in [json!(0), json!("2"), json!(-1)].iter() {
+            assert!(envelope_version(&json!({ "protocol_version": invalid })).is_err());
+        }
+    }
+
+    #[test]
+    fn test_upgrade_envelope_converts_target_pane() {
+        let upgraded = upgrade_envelope(json!({
+            "target_pane": "Overseer",
+            "coordination_message": "StartReview"
+        }));
+        assert_eq!(upgraded["target"], json!({ "title": "Overseer" }));
+        assert_eq!(
+            upgraded["coordination_message"],
+            json!({ "type": "StartReview" })
+        );
+        assert!(upgraded.get("target_pane").is_none());
+
+        let broadcast = upgrade_envelope(json!({ "target_pane": null }));
+        assert_eq!(broadcast["target"], Value::Null);
+
+        // A structured target wins over a leftover title
+        let both = upgrade_envelope(json!({
+            "target": { "role": "Commander" },
+            "target_pane": "Overseer"
+        }));
+        assert_eq!(both["target"], json!({ "role": "Commander" }));
+    }
+
+    #[test]
+    fn test_encode_envelope_for_each_version() {
+        let envelope = MessageEnvelope::new_ack(9, "commander-plugin", "zzz-coordinator");
+
+        let current: Value =
+            serde_json::from_str(&encode_envelope(&envelope, PROTOCOL_VERSION).unwrap()).unwrap();
+        assert_eq!(current["protocol_version"], 3);
+        assert_eq!(current["target"], json!({ "plugin": "commander-plugin" }));
+        assert_eq!(
+            current["coordination_message"],
+            json!({ "type": "Ack", "message_id": 9 })
+        );
+
+        let tagged: Value =
+            serde_json::from_str(&encode_envelope(&envelope, TAGGED_PROTOCOL_VERSION).unwrap())
+                .unwrap();
+        assert_eq!(tagged["protocol_version"], 2);
+        assert!(tagged.get("target").is_none());
+        assert_eq!(tagged["target_pane"], "commander-plugin");
+        assert_eq!(tagged["coordination_message"]["type"], "Ack");
+
+        let legacy: Value =
+            serde_json::from_str(&encode_envelope(&envelope, LEGACY_PROTOCOL_VERSION).unwrap())
+                .unwrap();
+        assert!(legacy.get("protocol_version").is_none());
+        assert_eq!(
+            legacy["coordination_message"],
+            json!({ "Ack": { "message_id": 9 } })
+        );
+        assert_eq!(legacy["target_pane"], "commander-plugin");
+    }
+}
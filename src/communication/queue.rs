@@ -0,0 +1,491 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::envelope::MessageEnvelope;
+use super::error::CommunicationError;
+use super::router::Transport;
+use crate::logging::{LogComponent, LogRecord};
+
+/// Time a receiver has to acknowledge a message before it is sent again
+pub const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Resends after the first attempt before a message is dead-lettered
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Upper bound on the backed-off acknowledgement timeout
+pub const MAX_ACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Errors in the delivery retry configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetryConfigError {
+    InvalidValue { key: String, value: String },
+}
+
+impl fmt::Display for RetryConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryConfigError::InvalidValue { key, value } => {
+                write!(f, "Invalid {} '{}'", key, value)
+            }
+        }
+    }
+}
+
+/// How long to wait for acknowledgements and how often to resend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub ack_timeout: Duration,
+    pub max_retries: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            ack_timeout: DEFAULT_ACK_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Read `message_ack_timeout_secs` and `message_max_retries` from the plugin configuration
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+    ) -> Result<Self, RetryConfigError> {
+        let mut policy = Self::default();
+        let invalid = |key: &str, value: &str| RetryConfigError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        if let Some(value) = configuration.get("message_ack_timeout_secs") {
+            let secs: u64 = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid("message_ack_timeout_secs", value))?;
+            if secs == 0 {
+                return Err(invalid("message_ack_timeout_secs", value));
+            }
+            policy.ack_timeout = Duration::from_secs(secs);
+        }
+
+        if let Some(value) = configuration.get("message_max_retries") {
+            policy.max_retries = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid("message_max_retries", value))?;
+        }
+
+        Ok(policy)
+    }
+
+    /// Acknowledgement timeout for the given attempt, doubling after each resend
+    pub fn timeout_for_attempt(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.ack_timeout
+            .checked_mul(factor)
+            .unwrap_or(Duration::MAX)
+            .min(MAX_ACK_TIMEOUT.max(self.ack_timeout))
+    }
+}
+
+/// A message waiting for its acknowledgement
+#[derive(Debug, Clone)]
+struct PendingMessage {
+    envelope: MessageEnvelope,
+    transport: Transport,
+    attempts: u32,
+    deadline: Instant,
+}
+
+/// A message that was never acknowledged, kept for the dead-letter log
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub envelope: MessageEnvelope,
+    pub attempts: u32,
+    /// Unix timestamp when the message was given up on
+    pub failed_at: u64,
+}
+
+impl DeadLetter {
+    /// The delivery error this dead letter represents
+    pub fn error(&self) -> CommunicationError {
+        CommunicationError::MessageDeliveryFailed(format!(
+            "message {} to {} unacknowledged after {} attempts",
+            self.envelope.message_id.unwrap_or_default(),
+            self.envelope
+                .target
+                .as_ref()
+                .map_or("all panes".to_string(), |target| target.to_string()),
+            self.attempts
+        ))
+    }
+
+    /// Record for the dead-letter log, carrying the whole dead letter
+    pub fn log_record(&self) -> LogRecord {
+        LogRecord::error(LogComponent::Messaging, &self.error().to_string())
+            .message_id(self.envelope.message_id)
+            .field("dead_letter", self)
+    }
+}
+
+/// Result of checking the queue for expired acknowledgements
+#[derive(Debug, Default)]
+pub struct DeliveryTick {
+    /// Envelopes whose timeout expired and that must be sent again, with
+    /// the transport they were first sent over
+    pub retries: Vec<(MessageEnvelope, Transport)>,
+    /// Envelopes that ran out of retries
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+/// Messages sent with an acknowledgement request, keyed by message ID
+#[derive(Debug)]
+pub struct OutboundQueue {
+    policy: RetryPolicy,
+    next_id: u64,
+    pending: BTreeMap<u64, PendingMessage>,
+}
+
+impl Default for OutboundQueue {
+    fn default() -> Self {
+        Self::new(RetryPolicy::default())
+    }
+}
+
+impl OutboundQueue {
+    /// Create a queue whose message IDs start from the current time, so they
+    /// do not repeat across plugin reloads
+    pub fn new(policy: RetryPolicy) -> Self {
+        let first_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        Self {
+            policy,
+            next_id: first_id.max(1),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Get the retry policy
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// Assign a message ID, request an acknowledgement and start its timeout
+    ///
+    /// Returns the envelope to send.
+    pub fn enqueue(
+        &mut self,
+        envelope: MessageEnvelope,
+        transport: Transport,
+        now: Instant,
+    ) -> MessageEnvelope {
+        let message_id = self.next_id;
+        self.next_id += 1;
+
+        let envelope = envelope.with_ack_request(message_id);
+        self.pending.insert(
+            message_id,
+            PendingMessage {
+                envelope: envelope.clone(),
+                transport,
+                attempts: 1,
+                deadline: now + self.policy.timeout_for_attempt(1),
+            },
+        );
+        envelope
+    }
+
+    /// Settle a message; returns false for unknown or already settled IDs
+    pub fn acknowledge(&mut self, message_id: u64) -> bool {
+        self.pending.remove(&message_id).is_some()
+    }
+
+    /// Collect messages whose timeout expired: resend them with a longer
+    /// timeout, or dead-letter them once their retries are used up
+    pub fn tick(&mut self, now: Instant) -> DeliveryTick {
+        let mut result = DeliveryTick::default();
+        let mut exhausted = Vec::new();
+
+        for (message_id, pending) in self.pending.iter_mut() {
+            if pending.deadline > now {
+                continue;
+            }
+
+            if pending.attempts > self.policy.max_retries {
+                exhausted.push(*message_id);
+            } else {
+                pending.attempts += 1;
+                pending.deadline = now + self.policy.timeout_for_attempt(pending.attempts);
+                result
+                    .retries
+                    .push((pending.envelope.clone(), pending.transport));
+            }
+        }
+
+        let failed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        for message_id in exhausted {
+            if let Some(pending) = self.pending.remove(&message_id) {
+                result.dead_letters.push(DeadLetter {
+                    envelope: pending.envelope,
+                    attempts: pending.attempts,
+                    failed_at,
+                });
+            }
+        }
+
+        result
+    }
+
+    /// Number of messages awaiting acknowledgement
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check whether every message has been settled
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Earliest acknowledgement deadline
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|pending| pending.deadline).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::envelope::MessageTarget;
+    use crate::coordination_message::CoordinationMessage;
+    use crate::file_system::FileSystem;
+    use crate::litellm_config::LiteLLMConfig;
+    use crate::logging::{rotated_path, Logger};
+    use crate::pane_role::PaneRole;
+    use crate::secrets::Redactor;
+    use crate::task_id::TaskId;
+    use std::fs;
+
+    fn envelope() -> MessageEnvelope {
+        MessageEnvelope::new_targeted(
+            CoordinationMessage::StartReview,
+            MessageTarget::Role(PaneRole::Overseer),
+            "test",
+        )
+    }
+
+    fn policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            ack_timeout: Duration::from_secs(2),
+            max_retries,
+        }
+    }
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_enqueue_assigns_increasing_ids() {
+        let mut queue = OutboundQueue::default();
+        let now = Instant::now();
+
+        let first = queue.enqueue(envelope(), Transport::Broadcast, now);
+        let second = queue.enqueue(envelope(), Transport::Broadcast, now);
+
+        assert!(first.ack_requested);
+        assert_eq!(second.message_id.unwrap(), first.message_id.unwrap() + 1);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.next_deadline(), Some(now + DEFAULT_ACK_TIMEOUT));
+    }
+
+    #[test]
+    fn test_acknowledge_settles_message() {
+        let mut queue = OutboundQueue::default();
+        let sent = queue.enqueue(envelope(), Transport::Broadcast, Instant::now());
+        let message_id = sent.message_id.unwrap();
+
+        assert!(queue.acknowledge(message_id));
+        assert!(!queue.acknowledge(message_id));
+        assert!(queue.is_empty());
+        assert_eq!(queue.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_tick_before_deadline_does_nothing() {
+        let mut queue = OutboundQueue::new(policy(3));
+        let now = Instant::now();
+        queue.enqueue(envelope(), Transport::Broadcast, now);
+
+        let tick = queue.tick(now + Duration::from_secs(1));
+        assert!(tick.retries.is_empty());
+        assert!(tick.dead_letters.is_empty());
+    }
+
+    #[test]
+    fn test_tick_retries_with_backoff() {
+        let mut queue = OutboundQueue::new(policy(3));
+        let start = Instant::now();
+        let sent = queue.enqueue(envelope(), Transport::PluginPipe(3), start);
+
+        let at = start + Duration::from_secs(2);
+        let tick = queue.tick(at);
+        assert_eq!(tick.retries.len(), 1);
+        assert_eq!(tick.retries[0].0.message_id, sent.message_id);
+        assert_eq!(tick.retries[0].1, Transport::PluginPipe(3));
+        // The second attempt waits twice as long
+        assert_eq!(queue.next_deadline(), Some(at + Duration::from_secs(4)));
+
+        assert!(queue.tick(at + Duration::from_secs(3)).retries.is_empty());
+    }
+
+    #[test]
+    fn test_tick_dead_letters_after_max_retries() {
+        let mut queue = OutboundQueue::new(policy(2));
+        let mut now = Instant::now();
+        let sent = queue.enqueue(envelope(), Transport::Broadcast, now);
+
+        for _ in 0..2 {
+            now += MAX_ACK_TIMEOUT;
+            assert_eq!(queue.tick(now).retries.len(), 1);
+        }
+
+        now += MAX_ACK_TIMEOUT;
+        let tick = queue.tick(now);
+        assert!(tick.retries.is_empty());
+        assert_eq!(tick.dead_letters.len(), 1);
+
+        let dead = &tick.dead_letters[0];
+        assert_eq!(dead.attempts, 3);
+        assert_eq!(dead.envelope.message_id, sent.message_id);
+        assert!(queue.is_empty());
+
+        match dead.error() {
+            CommunicationError::MessageDeliveryFailed(reason) => {
+                assert!(reason.contains("to Overseer unacknowledged after 3 attempts"))
+            }
+            other => panic!("Expected MessageDeliveryFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_zero_retries_dead_letters_on_first_timeout() {
+        let mut queue = OutboundQueue::new(policy(0));
+        let now = Instant::now();
+        queue.enqueue(envelope(), Transport::Broadcast, now);
+
+        let tick = queue.tick(now + Duration::from_secs(2));
+        assert!(tick.retries.is_empty());
+        assert_eq!(tick.dead_letters.len(), 1);
+    }
+
+    #[test]
+    fn test_timeout_for_attempt_is_capped() {
+        let policy = policy(3);
+        assert_eq!(policy.timeout_for_attempt(1), Duration::from_secs(2));
+        assert_eq!(policy.timeout_for_attempt(2), Duration::from_secs(4));
+        assert_eq!(policy.timeout_for_attempt(3), Duration::from_secs(8));
+        assert_eq!(policy.timeout_for_attempt(40), MAX_ACK_TIMEOUT);
+    }
+
+    #[test]
+    fn test_retry_policy_from_configuration() {
+        let policy = RetryPolicy::from_configuration(&configuration(&[
+            ("message_ack_timeout_secs", "10"),
+            ("message_max_retries", "1"),
+        ]))
+        .unwrap();
+
+        assert_eq!(policy.ack_timeout, Duration::from_secs(10));
+        assert_eq!(policy.max_retries, 1);
+        assert_eq!(
+            RetryPolicy::from_configuration(&BTreeMap::new()).unwrap(),
+            RetryPolicy::default()
+        );
+    }
+
+    #[test]
+    fn test_retry_policy_rejects_invalid_values() {
+        for (key, value) in [
+            ("message_ack_timeout_secs", "0"),
+            ("message_ack_timeout_secs", "soon"),
+            ("message_max_retries", "-2"),
+        ]
+        .iter()
+        {
+            let error =
+                RetryPolicy::from_configuration(&configuration(&[(key, value)])).unwrap_err();
+            assert_eq!(error.to_string(), format!("Invalid {} '{}'", key, value));
+        }
+    }
+
+    #[test]
+    fn test_dead_letter_serializes_envelope() {
+        let dead = DeadLetter {
+            envelope: envelope().with_ack_request(7),
+            attempts: 4,
+            failed_at: 1_700_000_000,
+        };
+
+        let json = serde_json::to_value(&dead).unwrap();
+        assert_eq!(json["attempts"], 4);
+        assert_eq!(json["envelope"]["message_id"], 7);
+    }
+
+    #[test]
+    fn test_dead_letter_log_is_redacted_and_rotated() {
+        let root = tempfile::tempdir().unwrap();
+        let file_system = FileSystem::new(root.path());
+        let task_id = TaskId::from(3);
+        file_system.setup_task_directories(&task_id).unwrap();
+        let redactor = Redactor::from_config(&LiteLLMConfig {
+            api_key: "sk-secret".to_string(),
+            ..LiteLLMConfig::default()
+        });
+        let dead = DeadLetter {
+            envelope: MessageEnvelope::new_targeted(
+                CoordinationMessage::StartPlanning {
+                    task_id: task_id.clone(),
+                    task_description: "Use sk-secret".to_string(),
+                },
+                MessageTarget::Role(PaneRole::Commander),
+                "test",
+            )
+            .with_ack_request(9),
+            attempts: 4,
+            failed_at: 1_700_000_000,
+        };
+        let logger = Logger {
+            max_bytes: 1,
+            max_files: 1,
+            ..Logger::default()
+        };
+
+        for _ in 0..2 {
+            file_system
+                .log_dead_letter(&task_id, &logger, dead.log_record().redacted(&redactor))
+                .unwrap();
+        }
+
+        let path = file_system.get_dead_letter_log_path(&task_id);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-secret"));
+        assert!(content.contains("Use [REDACTED]"));
+        assert_eq!(content.lines().count(), 1);
+        assert!(rotated_path(&path, 1).exists());
+        let record: serde_json::Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(record["message_id"], 9);
+        assert_eq!(record["fields"]["dead_letter"]["attempts"], 4);
+    }
+}
@@ -1,15 +1,51 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use zellij_tile::prelude::*;
 
+use super::envelope::MessageTarget;
 use super::error::CommunicationError;
+use super::health::{HealthEvent, HealthTracker, PaneHealth, Recovery};
+use super::selector::{PaneMatches, PaneSelectors};
+use super::spawner::PaneSpawner;
 use crate::coordination_message::CoordinationMessage;
 use crate::pane_role::PaneRole;
 use crate::zellij_service::ZellijService;
 
+/// How an envelope reaches its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Write the encoded envelope to a terminal pane's input
+    Keystrokes(u32),
+    /// Pipe the envelope to a single plugin
+    PluginPipe(u32),
+    /// Pipe the envelope to every plugin listening on the coordination pipe
+    Broadcast,
+}
+
+impl Transport {
+    /// Whether the receiver can reply with an `Ack`; terminals running an
+    /// assistant cannot
+    pub fn can_acknowledge(self) -> bool {
+        !matches!(self, Transport::Keystrokes(_))
+    }
+}
+
 /// Message router for dispatching coordination messages by pane role
 pub struct MessageRouter<T: ZellijService> {
     /// Mapping from pane roles to their pane IDs
     pane_registry: HashMap<PaneRole, PaneId>,
+    /// Plugin panes in the tab by name; None when several plugins share a name
+    plugin_panes: HashMap<String, Option<u32>>,
+    /// Rules deciding which pane takes which role
+    selectors: PaneSelectors,
+    /// ID of the coordinator plugin's own pane, used to scope discovery to its tab
+    own_plugin_id: Option<u32>,
+    /// Panes opened by the coordinator for roles missing from the layout
+    spawner: PaneSpawner,
+    /// Liveness of registered panes and their restart policies
+    health: HealthTracker,
+    /// Last command typed into each role's pane, kept for re-sending
+    last_commands: RefCell<HashMap<PaneRole, String>>,
     /// Service for interacting with Zellij APIs
     zellij_service: T,
 }
@@ -19,46 +55,226 @@ impl<T: ZellijService> MessageRouter<T> {
     pub fn new(zellij_service: T) -> Self {
         Self {
             pane_registry: HashMap::new(),
+            plugin_panes: HashMap::new(),
+            selectors: PaneSelectors::default(),
+            own_plugin_id: None,
+            spawner: PaneSpawner::default(),
+            health: HealthTracker::default(),
+            last_commands: RefCell::new(HashMap::new()),
             zellij_service,
         }
     }
 
-    /// Discover panes and map them to roles based on their names using real Zellij API
+    /// Replace the rules used to match panes to roles
+    pub fn set_selectors(&mut self, selectors: PaneSelectors) {
+        self.selectors = selectors;
+    }
+
+    /// Set the coordinator's own plugin ID so discovery stays within its tab
+    pub fn set_own_plugin_id(&mut self, plugin_id: u32) {
+        self.own_plugin_id = Some(plugin_id);
+    }
+
+    /// The coordinator's own plugin ID, once known
+    pub fn own_plugin_id(&self) -> Option<u32> {
+        self.own_plugin_id
+    }
+
+    /// Replace the rules used to open panes for missing roles
+    pub fn set_spawner(&mut self, spawner: PaneSpawner) {
+        self.spawner = spawner;
+    }
+
+    /// Get the pane spawner
+    pub fn spawner(&self) -> &PaneSpawner {
+        &self.spawner
+    }
+
+    /// Replace the restart policies for exited panes
+    pub fn set_health_tracker(&mut self, health: HealthTracker) {
+        self.health = health;
+    }
+
+    /// Refresh the health of registered panes from the manifest and report exits and recoveries
+    pub fn update_health(&mut self, pane_manifest: &PaneManifest) -> Vec<HealthEvent> {
+        self.health.update(&self.pane_registry, pane_manifest)
+    }
+
+    /// Last known health of a role's pane
+    pub fn pane_health(&self, role: &PaneRole) -> Option<PaneHealth> {
+        self.health.health(*role)
+    }
+
+    /// Apply the role's restart policy to its exited pane
+    ///
+    /// Re-runs the held pane when the policy asks for it; relaunching the
+    /// assistant is left to the caller, once the pane is running again or
+    /// right away if it never stopped taking input.
+    pub fn recover_pane(&mut self, role: PaneRole, pane_id: PaneId, held: bool) -> Recovery {
+        let recovery = self.health.recover(role, pane_id, held);
+        match recovery {
+            Recovery::ReopenPane(terminal_id) | Recovery::RelaunchAssistant(terminal_id) => {
+                self.zellij_service.rerun_command_pane(terminal_id)
+            }
+            Recovery::RetypeAssistant(_) | Recovery::Nothing | Recovery::GaveUp { .. } => {}
+        }
+        recovery
+    }
+
+    /// Renew every role's restart budget
+    pub fn reset_restarts(&mut self) {
+        self.health.reset_restarts();
+    }
+
+    /// Discover panes in the plugin's tab and map them to roles using the selectors
+    ///
+    /// Roles matched by more than one pane are left unregistered and reported
+    /// in the returned matches. Panes the coordinator spawned keep their role
+    /// regardless of the selectors once they appear in the manifest.
     pub fn discover_panes_with_manifest(
         &mut self,
         pane_manifest: &PaneManifest,
-    ) -> Result<(), CommunicationError> {
+    ) -> Result<PaneMatches, CommunicationError> {
         // Clear existing registry
         self.pane_registry.clear();
 
-        let mut discovered_panes = 0;
-
-        // Iterate through all tabs and their panes
-        for panes in pane_manifest.panes.values() {
-            for pane_info in panes {
-                // Try to match the pane title to a role
-                if let Some(role) = Self::match_pane_name_to_role(&pane_info.title) {
-                    // Create the correct PaneId based on the pane type
-                    let pane_id = if pane_info.is_plugin {
-                        PaneId::Plugin(pane_info.id)
-                    } else {
-                        PaneId::Terminal(pane_info.id)
-                    };
-
-                    // Register the pane with its role
-                    self.pane_registry.insert(role, pane_id);
-                    discovered_panes += 1;
+        let mut matches = self
+            .selectors
+            .match_manifest(pane_manifest, self.own_plugin_id);
+        self.register_plugin_names(pane_manifest, matches.tab);
+        for (role, pane_id) in self.spawner.adopt(pane_manifest) {
+            matches
+                .matched
+                .retain(|(r, id)| *r != role && *id != pane_id);
+            matches.ambiguous.retain(|ambiguous| ambiguous.role != role);
+            matches.matched.push((role, pane_id));
+        }
+        self.pane_registry.extend(matches.matched.iter().copied());
+
+        if matches.matched.is_empty() {
+            let mut reason = "No matching panes found in current layout".to_string();
+            for ambiguous in &matches.ambiguous {
+                reason.push_str(&format!("; {}", ambiguous));
+            }
+            return Err(CommunicationError::PaneDiscoveryFailed(reason));
+        }
+
+        Ok(matches)
+    }
+
+    /// Record the names plugin panes can be addressed by: their title, plugin
+    /// URL and the URL's file stem
+    fn register_plugin_names(&mut self, pane_manifest: &PaneManifest, tab: Option<usize>) {
+        self.plugin_panes.clear();
+
+        for (tab_index, panes) in &pane_manifest.panes {
+            if tab.is_some_and(|own| own != *tab_index) {
+                continue;
+            }
+
+            for pane in panes.iter().filter(|pane| pane.is_plugin) {
+                if self.own_plugin_id == Some(pane.id) {
+                    continue;
+                }
+
+                let mut names = vec![pane.title.clone()];
+                if let Some(url) = &pane.plugin_url {
+                    names.push(url.clone());
+                    let file = url.rsplit(['/', ':']).next().unwrap_or(url);
+                    names.push(file.trim_end_matches(".wasm").to_string());
+                }
+                names.sort();
+                names.dedup();
+
+                for name in names.into_iter().filter(|name| !name.is_empty()) {
+                    self.plugin_panes
+                        .entry(name)
+                        .and_modify(|id| {
+                            if *id != Some(pane.id) {
+                                *id = None;
+                            }
+                        })
+                        .or_insert(Some(pane.id));
                 }
             }
         }
+    }
 
-        if discovered_panes == 0 {
-            return Err(CommunicationError::PaneDiscoveryFailed(
-                "No matching panes found in current layout".to_string(),
-            ));
+    /// Plugin pane known by `name`, unless no or several plugins use that name
+    pub fn plugin_pane_id(&self, name: &str) -> Option<u32> {
+        self.plugin_panes.get(name).copied().flatten()
+    }
+
+    /// Resolve an envelope target to the transport that reaches it
+    ///
+    /// Roles and panes resolve through the registry, refusing panes that have
+    /// exited. Plugin names known from discovery get a pipe of their own;
+    /// other plugin names and titles fall back to the coordination pipe, where
+    /// receivers filter with `MessageEnvelope::is_targeted_to`.
+    pub fn resolve_target(
+        &self,
+        target: Option<&MessageTarget>,
+    ) -> Result<Transport, CommunicationError> {
+        let pane_id = match target {
+            None | Some(MessageTarget::Title(_)) => return Ok(Transport::Broadcast),
+            Some(MessageTarget::Plugin(name)) => {
+                return Ok(self
+                    .plugin_pane_id(name)
+                    .map_or(Transport::Broadcast, Transport::PluginPipe));
+            }
+            Some(MessageTarget::Role(role)) => self.live_pane_id(*role)?,
+            Some(MessageTarget::Pane(pane_id)) => {
+                let registered = self
+                    .pane_registry
+                    .iter()
+                    .find(|(_, id)| *id == pane_id)
+                    .map(|(role, _)| *role);
+                if let Some(role) = registered.filter(|role| !self.health.is_alive(*role)) {
+                    return Err(CommunicationError::PaneExited(role));
+                }
+                *pane_id
+            }
+        };
+
+        Ok(match pane_id {
+            PaneId::Terminal(id) => Transport::Keystrokes(id),
+            PaneId::Plugin(id) => Transport::PluginPipe(id),
+        })
+    }
+
+    /// Open panes for required roles that are neither registered nor already spawned
+    ///
+    /// The panes are registered by a later discovery, once Zellij reports them.
+    pub fn spawn_missing_panes(&mut self) -> Vec<PaneRole> {
+        let roles = self.spawner.roles_to_spawn(&self.get_registered_roles());
+
+        for role in &roles {
+            if let Some(spec) = self.spawner.spec(*role) {
+                self.zellij_service.open_command_pane(
+                    spec.command_to_run(),
+                    PaneSpawner::context(*role, self.own_plugin_id),
+                );
+            }
+            self.spawner.mark_requested(*role);
         }
 
-        Ok(())
+        roles
+    }
+
+    /// Handle a `CommandPaneOpened` event, naming the pane if it was spawned for a role
+    pub fn command_pane_opened(
+        &mut self,
+        terminal_pane_id: u32,
+        context: &BTreeMap<String, String>,
+    ) -> Option<PaneRole> {
+        let role = self
+            .spawner
+            .pane_opened(terminal_pane_id, context, self.own_plugin_id)?;
+        if let Some(spec) = self.spawner.spec(role) {
+            self.zellij_service
+                .rename_terminal_pane(terminal_pane_id, &spec.name);
+        }
+        Some(role)
     }
 
     /// Manually register a pane with a specific role
@@ -71,16 +287,25 @@ impl<T: ZellijService> MessageRouter<T> {
         self.pane_registry.get(role).copied()
     }
 
+    /// Get the pane ID for a role whose pane can take input
+    fn live_pane_id(&self, role: PaneRole) -> Result<PaneId, CommunicationError> {
+        let pane_id = self
+            .get_pane_id(&role)
+            .ok_or(CommunicationError::PaneNotFound(role))?;
+        if !self.health.is_alive(role) {
+            return Err(CommunicationError::PaneExited(role));
+        }
+        Ok(pane_id)
+    }
+
     /// Route a message to a specific pane role
     pub fn route_message_to_role(
         &self,
         message: &CoordinationMessage,
         target_role: PaneRole,
     ) -> Result<(), CommunicationError> {
-        // Look up the pane ID for the target role
-        let pane_id = self
-            .get_pane_id(&target_role)
-            .ok_or(CommunicationError::PaneNotFound(target_role))?;
+        // Look up the pane ID for the target role, refusing panes that have exited
+        let pane_id = self.live_pane_id(target_role)?;
 
         // Serialize the message
         let message_json =
@@ -99,17 +324,23 @@ impl<T: ZellijService> MessageRouter<T> {
         command: &str,
         target_role: PaneRole,
     ) -> Result<(), CommunicationError> {
-        // Look up the pane ID for the target role
-        let pane_id = self
-            .get_pane_id(&target_role)
-            .ok_or(CommunicationError::PaneNotFound(target_role))?;
+        // Look up the pane ID for the target role, refusing panes that have exited
+        let pane_id = self.live_pane_id(target_role)?;
 
         // Write the command directly to the target pane
         self.zellij_service.write_chars_to_pane_id(command, pane_id);
+        self.last_commands
+            .borrow_mut()
+            .insert(target_role, command.to_string());
 
         Ok(())
     }
 
+    /// The last command typed into a role's pane, if any
+    pub fn last_command(&self, role: PaneRole) -> Option<String> {
+        self.last_commands.borrow().get(&role).cloned()
+    }
+
     /// Route a message to multiple pane roles
     pub fn route_message_to_roles(
         &self,
@@ -150,22 +381,20 @@ impl<T: ZellijService> MessageRouter<T> {
         &self.zellij_service
     }
 
-    /// Match pane name to role using pattern matching
+    /// Match pane name to role using the default title selectors
     pub fn match_pane_name_to_role(pane_name: &str) -> Option<PaneRole> {
-        match pane_name.to_lowercase().as_str() {
-            name if name.contains("overseer") => Some(PaneRole::Overseer),
-            name if name.contains("commander") => Some(PaneRole::Commander),
-            name if name.contains("task list") => Some(PaneRole::TaskList),
-            name if name.contains("review") => Some(PaneRole::Review),
-            name if name.contains("editor") => Some(PaneRole::Editor),
-            _ => None,
-        }
+        PaneSelectors::default().role_for(&PaneInfo {
+            title: pane_name.to_string(),
+            ..PaneInfo::default()
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::communication::selector::RoleSelector;
+    use crate::task_id::TaskId;
     use crate::workflow_phase::WorkflowPhase;
     use crate::zellij_service::MockZellijService;
     use std::collections::HashMap;
@@ -329,6 +558,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_discover_panes_leaves_ambiguous_role_unregistered() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut manifest = create_mock_pane_manifest();
+        manifest
+            .panes
+            .get_mut(&0)
+            .unwrap()
+            .push(create_mock_pane_info(6, "Review", false));
+
+        let matches = router.discover_panes_with_manifest(&manifest).unwrap();
+
+        assert!(!router.is_role_registered(&PaneRole::Review));
+        assert!(router.is_role_registered(&PaneRole::Overseer));
+        assert_eq!(matches.ambiguous.len(), 1);
+        assert_eq!(matches.ambiguous[0].role, PaneRole::Review);
+        assert_eq!(
+            matches.ambiguous[0].panes,
+            vec![PaneId::Plugin(4), PaneId::Terminal(6)]
+        );
+    }
+
+    #[test]
+    fn test_discover_panes_uses_configured_selectors() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut selectors = PaneSelectors::default();
+        selectors.set(
+            PaneRole::Commander,
+            RoleSelector {
+                title: Some("Main Editor".to_string()),
+                ..RoleSelector::default()
+            },
+        );
+        router.set_selectors(selectors);
+
+        router
+            .discover_panes_with_manifest(&create_mock_pane_manifest())
+            .unwrap();
+
+        assert_eq!(
+            router.get_pane_id(&PaneRole::Commander),
+            Some(PaneId::Terminal(5))
+        );
+        // The pane was claimed by the Commander, which comes first in role order
+        assert!(!router.is_role_registered(&PaneRole::Editor));
+    }
+
+    #[test]
+    fn test_discover_panes_scoped_to_own_tab() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut manifest = create_mock_pane_manifest();
+        manifest.panes.insert(
+            1,
+            vec![
+                create_mock_pane_info(20, "zzz", true),
+                create_mock_pane_info(21, "Overseer", false),
+            ],
+        );
+        router.set_own_plugin_id(20);
+
+        let matches = router.discover_panes_with_manifest(&manifest).unwrap();
+
+        assert_eq!(matches.tab, Some(1));
+        assert_eq!(router.get_registered_roles(), vec![PaneRole::Overseer]);
+        assert_eq!(
+            router.get_pane_id(&PaneRole::Overseer),
+            Some(PaneId::Terminal(21))
+        );
+    }
+
     #[test]
     fn test_discover_panes_empty_manifest() {
         let mock_service = MockZellijService::new();
@@ -345,6 +647,389 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spawn_missing_panes_on_bare_tab() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut manifest = create_empty_pane_manifest();
+        manifest
+            .panes
+            .insert(0, vec![create_mock_pane_info(9, "Main Editor", false)]);
+
+        router.discover_panes_with_manifest(&manifest).unwrap();
+        let spawned = router.spawn_missing_panes();
+
+        assert_eq!(spawned, vec![PaneRole::Overseer, PaneRole::Commander]);
+        let opened = router.get_zellij_service().get_opened_panes();
+        assert_eq!(opened.len(), 2);
+        assert_eq!(opened[0].0.path, std::path::PathBuf::from("sh"));
+        assert_eq!(
+            opened[0].1.get("zzz_role").map(String::as_str),
+            Some("overseer")
+        );
+        assert_eq!(
+            opened[1].1.get("zzz_role").map(String::as_str),
+            Some("commander")
+        );
+
+        // Still pending, so a second pane update must not open them again
+        assert!(router.spawn_missing_panes().is_empty());
+        assert_eq!(router.get_zellij_service().get_opened_panes().len(), 2);
+    }
+
+    #[test]
+    fn test_spawn_skips_roles_found_in_layout() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut manifest = create_empty_pane_manifest();
+        manifest
+            .panes
+            .insert(0, vec![create_mock_pane_info(1, "Overseer", false)]);
+
+        router.discover_panes_with_manifest(&manifest).unwrap();
+
+        assert_eq!(router.spawn_missing_panes(), vec![PaneRole::Commander]);
+    }
+
+    #[test]
+    fn test_spawned_pane_registered_on_next_pane_update() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        assert!(router
+            .discover_panes_with_manifest(&create_empty_pane_manifest())
+            .is_err());
+        router.spawn_missing_panes();
+
+        let context = PaneSpawner::context(PaneRole::Commander, None);
+        assert_eq!(
+            router.command_pane_opened(12, &context),
+            Some(PaneRole::Commander)
+        );
+        assert_eq!(
+            router.get_zellij_service().get_renamed_panes(),
+            vec![(12, "Commander".to_string())]
+        );
+        assert!(!router.is_role_registered(&PaneRole::Commander));
+
+        // Zellij still titles the pane after its command until the rename lands
+        let mut manifest = create_empty_pane_manifest();
+        manifest
+            .panes
+            .insert(0, vec![create_mock_pane_info(12, "sh", false)]);
+        router.discover_panes_with_manifest(&manifest).unwrap();
+
+        assert_eq!(
+            router.get_pane_id(&PaneRole::Commander),
+            Some(PaneId::Terminal(12))
+        );
+        assert!(!router.is_role_registered(&PaneRole::Overseer));
+    }
+
+    #[test]
+    fn test_spawned_pane_wins_over_ambiguous_layout_match() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        router.spawn_missing_panes();
+        router.command_pane_opened(12, &PaneSpawner::context(PaneRole::Overseer, None));
+
+        let mut manifest = create_empty_pane_manifest();
+        manifest.panes.insert(
+            0,
+            vec![
+                create_mock_pane_info(11, "Overseer (old)", false),
+                create_mock_pane_info(12, "Overseer", false),
+            ],
+        );
+        let matches = router.discover_panes_with_manifest(&manifest).unwrap();
+
+        assert!(matches.ambiguous.is_empty());
+        assert_eq!(
+            router.get_pane_id(&PaneRole::Overseer),
+            Some(PaneId::Terminal(12))
+        );
+    }
+
+    #[test]
+    fn test_spawned_panes_are_tagged_with_owner() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        router.set_own_plugin_id(20);
+        router.spawn_missing_panes();
+
+        let opened = router.get_zellij_service().get_opened_panes();
+        assert!(opened
+            .iter()
+            .all(|(_, context)| context.get("zzz_owner") == Some(&"20".to_string())));
+
+        // A coordinator in another tab spawned its own Commander at the same time
+        let foreign = PaneSpawner::context(PaneRole::Commander, Some(21));
+        assert_eq!(router.command_pane_opened(30, &foreign), None);
+        assert_eq!(
+            router.command_pane_opened(31, &opened[1].1),
+            Some(PaneRole::Commander)
+        );
+    }
+
+    #[test]
+    fn test_command_pane_opened_ignores_unknown_context() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+
+        assert_eq!(router.command_pane_opened(5, &BTreeMap::new()), None);
+        assert!(router.get_zellij_service().get_renamed_panes().is_empty());
+    }
+
+    #[test]
+    fn test_disabled_spawner_opens_nothing() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut configuration = BTreeMap::new();
+        configuration.insert("spawn_missing_panes".to_string(), "false".to_string());
+        router.set_spawner(
+            PaneSpawner::from_configuration(
+                &configuration,
+                &crate::assistant::AssistantConfig::default(),
+                None,
+            )
+            .unwrap(),
+        );
+
+        assert!(router.spawn_missing_panes().is_empty());
+        assert!(router.get_zellij_service().get_opened_panes().is_empty());
+    }
+
+    #[test]
+    fn test_last_command_per_role() {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router.register_pane(PaneRole::Overseer, PaneId::Terminal(1));
+        router.register_pane(PaneRole::Commander, PaneId::Terminal(2));
+        assert_eq!(router.last_command(PaneRole::Overseer), None);
+
+        router
+            .execute_command_in_role("codex plan\n", PaneRole::Overseer)
+            .unwrap();
+        router
+            .execute_command_in_role("codex build\n", PaneRole::Commander)
+            .unwrap();
+        router
+            .execute_command_in_role("codex review\n", PaneRole::Overseer)
+            .unwrap();
+
+        assert_eq!(
+            router.last_command(PaneRole::Overseer).as_deref(),
+            Some("codex review\n")
+        );
+        assert_eq!(
+            router.last_command(PaneRole::Commander).as_deref(),
+            Some("codex build\n")
+        );
+
+        // Commands that could not be delivered are not remembered
+        assert!(router
+            .execute_command_in_role("codex\n", PaneRole::Editor)
+            .is_err());
+        assert_eq!(router.last_command(PaneRole::Editor), None);
+    }
+
+    #[test]
+    fn test_exited_pane_refuses_commands() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut manifest = create_empty_pane_manifest();
+        let mut commander = create_mock_pane_info(2, "Commander", false);
+        commander.exited = true;
+        commander.is_held = true;
+        commander.exit_status = Some(137);
+        manifest.panes.insert(0, vec![commander]);
+
+        router.discover_panes_with_manifest(&manifest).unwrap();
+        let events = router.update_health(&manifest);
+
+        assert_eq!(
+            events,
+            vec![HealthEvent::Exited {
+                role: PaneRole::Commander,
+                pane_id: PaneId::Terminal(2),
+                exit_status: Some(137),
+                held: true,
+            }]
+        );
+        assert!(matches!(
+            router.execute_command_in_role("codex\n", PaneRole::Commander),
+            Err(CommunicationError::PaneExited(PaneRole::Commander))
+        ));
+        assert!(matches!(
+            router.route_message_to_role(
+                &CoordinationMessage::StartImplementation,
+                PaneRole::Commander
+            ),
+            Err(CommunicationError::PaneExited(PaneRole::Commander))
+        ));
+        assert!(router.get_zellij_service().get_sent_messages().is_empty());
+        for target in [
+            MessageTarget::Role(PaneRole::Commander),
+            MessageTarget::Pane(PaneId::Terminal(2)),
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                router.resolve_target(Some(target)),
+                Err(CommunicationError::PaneExited(PaneRole::Commander))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_resolve_role_targets_through_registry() {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router
+            .discover_panes_with_manifest(&create_mock_pane_manifest())
+            .unwrap();
+
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Role(PaneRole::Commander)))
+                .unwrap(),
+            Transport::Keystrokes(2)
+        );
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Role(PaneRole::Overseer)))
+                .unwrap(),
+            Transport::PluginPipe(1)
+        );
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Role(PaneRole::Review)))
+                .unwrap(),
+            Transport::PluginPipe(4)
+        );
+
+        let mut router = MessageRouter::new(MockZellijService::new());
+        assert!(matches!(
+            router.resolve_target(Some(&MessageTarget::Role(PaneRole::Commander))),
+            Err(CommunicationError::PaneNotFound(PaneRole::Commander))
+        ));
+        router.register_pane(PaneRole::Commander, PaneId::Terminal(9));
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Role(PaneRole::Commander)))
+                .unwrap(),
+            Transport::Keystrokes(9)
+        );
+    }
+
+    #[test]
+    fn test_resolve_pane_broadcast_and_title_targets() {
+        let router = MessageRouter::new(MockZellijService::new());
+
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Pane(PaneId::Terminal(7))))
+                .unwrap(),
+            Transport::Keystrokes(7)
+        );
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Pane(PaneId::Plugin(8))))
+                .unwrap(),
+            Transport::PluginPipe(8)
+        );
+        assert_eq!(router.resolve_target(None).unwrap(), Transport::Broadcast);
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Title("Overseer".to_string())))
+                .unwrap(),
+            Transport::Broadcast
+        );
+        assert!(Transport::PluginPipe(8).can_acknowledge());
+        assert!(Transport::Broadcast.can_acknowledge());
+        assert!(!Transport::Keystrokes(7).can_acknowledge());
+    }
+
+    #[test]
+    fn test_resolve_plugin_names_from_discovery() {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router.set_own_plugin_id(10);
+
+        let mut notes = create_mock_pane_info(11, "Notes", true);
+        notes.plugin_url = Some("file:/plugins/zzz-notes.wasm".to_string());
+        let mut own = create_mock_pane_info(10, "zzz", true);
+        own.plugin_url = Some("file:/plugins/zzz.wasm".to_string());
+        let mut manifest = create_mock_pane_manifest();
+        manifest.panes.get_mut(&0).unwrap().extend(vec![notes, own]);
+        router.discover_panes_with_manifest(&manifest).unwrap();
+
+        for name in ["Notes", "zzz-notes", "file:/plugins/zzz-notes.wasm"].iter() {
+            assert_eq!(
+                router
+                    .resolve_target(Some(&MessageTarget::Plugin(name.to_string())))
+                    .unwrap(),
+                Transport::PluginPipe(11),
+                "{}",
+                name
+            );
+        }
+
+        // Several plugins share the test URL, and the coordinator is not a target
+        assert_eq!(router.plugin_pane_id("test://plugin"), None);
+        assert_eq!(router.plugin_pane_id("zzz"), None);
+        assert_eq!(
+            router
+                .resolve_target(Some(&MessageTarget::Plugin("unknown".to_string())))
+                .unwrap(),
+            Transport::Broadcast
+        );
+    }
+
+    #[test]
+    fn test_recover_pane_reruns_held_pane() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+        let mut health = HealthTracker::default();
+        health.set_policy(
+            PaneRole::Overseer,
+            crate::communication::RestartPolicy::Rerun,
+        );
+        router.set_health_tracker(health);
+
+        let recovery = router.recover_pane(PaneRole::Overseer, PaneId::Terminal(4), true);
+
+        assert_eq!(recovery, Recovery::RelaunchAssistant(4));
+        assert_eq!(router.get_zellij_service().get_rerun_panes(), vec![4]);
+    }
+
+    #[test]
+    fn test_recover_pane_retypes_into_live_pane_without_rerun() {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router.register_pane(PaneRole::Overseer, PaneId::Terminal(4));
+        let mut health = HealthTracker::default();
+        health.set_policy(
+            PaneRole::Overseer,
+            crate::communication::RestartPolicy::Rerun,
+        );
+        router.set_health_tracker(health);
+
+        let recovery = router.recover_pane(PaneRole::Overseer, PaneId::Terminal(4), false);
+
+        assert_eq!(recovery, Recovery::RetypeAssistant(4));
+        assert!(router.get_zellij_service().get_rerun_panes().is_empty());
+        router
+            .execute_command_in_role("codex\n", PaneRole::Overseer)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_recover_pane_without_policy_does_nothing() {
+        let mock_service = MockZellijService::new();
+        let mut router = MessageRouter::new(mock_service);
+
+        let recovery = router.recover_pane(PaneRole::Overseer, PaneId::Terminal(4), true);
+
+        assert_eq!(recovery, Recovery::Nothing);
+        assert!(router.get_zellij_service().get_rerun_panes().is_empty());
+    }
+
     #[test]
     fn test_route_message_to_role() {
         let mock_service = MockZellijService::new();
@@ -356,7 +1041,7 @@ mod tests {
 
         // Create a test message
         let message = CoordinationMessage::StartPlanning {
-            task_id: 123,
+            task_id: TaskId::from(123),
             task_description: "Test task".to_string(),
         };
 
@@ -378,7 +1063,7 @@ mod tests {
             task_description,
         } = parsed_message
         {
-            assert_eq!(task_id, 123);
+            assert_eq!(task_id, TaskId::from(123));
             assert_eq!(task_description, "Test task");
         } else {
             panic!("Message was not serialized correctly");
@@ -0,0 +1,600 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+use zellij_tile::prelude::{PaneId, PaneInfo, PaneManifest};
+
+use crate::pane_role::PaneRole;
+
+/// Title fragments matched (case-insensitively) when a role has no selector configured
+const DEFAULT_TITLE_PATTERNS: [(PaneRole, &str); 5] = [
+    (PaneRole::Overseer, "overseer"),
+    (PaneRole::Commander, "commander"),
+    (PaneRole::TaskList, "task list"),
+    (PaneRole::Review, "review"),
+    (PaneRole::Editor, "editor"),
+];
+
+/// Errors in the pane selector configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorConfigError {
+    InvalidRegex {
+        role: PaneRole,
+        pattern: String,
+        error: String,
+    },
+    InvalidScope(String),
+}
+
+impl fmt::Display for SelectorConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorConfigError::InvalidRegex {
+                role,
+                pattern,
+                error,
+            } => write!(
+                f,
+                "Invalid {}_pane_title_regex '{}': {}",
+                role.config_key(),
+                pattern,
+                error
+            ),
+            SelectorConfigError::InvalidScope(value) => {
+                write!(f, "Invalid pane_scope '{}' (expected auto or tab)", value)
+            }
+        }
+    }
+}
+
+/// Criteria a pane must meet to take a role; every criterion that is set must match
+#[derive(Debug, Clone, Default)]
+pub struct RoleSelector {
+    /// Exact pane title
+    pub title: Option<String>,
+    /// Regular expression matched against the pane title
+    pub title_regex: Option<Regex>,
+    /// Case-insensitive fragment of the pane title (the legacy behaviour)
+    pub title_contains: Option<String>,
+    /// Terminal command, either in full or just the program name
+    pub command: Option<String>,
+    /// Plugin location URL
+    pub plugin_url: Option<String>,
+}
+
+impl RoleSelector {
+    /// Selector matching any title that contains `fragment`, ignoring case
+    pub fn title_contains(fragment: &str) -> Self {
+        Self {
+            title_contains: Some(fragment.to_lowercase()),
+            ..Self::default()
+        }
+    }
+
+    /// Check whether no criterion is set
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.title_regex.is_none()
+            && self.title_contains.is_none()
+            && self.command.is_none()
+            && self.plugin_url.is_none()
+    }
+
+    /// Check whether a pane meets every criterion of this selector
+    pub fn matches(&self, pane: &PaneInfo) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let title_ok = self.title.as_ref().is_none_or(|t| pane.title == *t);
+        let regex_ok = self
+            .title_regex
+            .as_ref()
+            .is_none_or(|re| re.is_match(&pane.title));
+        let contains_ok = self
+            .title_contains
+            .as_ref()
+            .is_none_or(|fragment| pane.title.to_lowercase().contains(fragment));
+        let command_ok = self.command.as_ref().is_none_or(|command| {
+            pane.terminal_command
+                .as_deref()
+                .is_some_and(|actual| command_matches(actual, command))
+        });
+        let plugin_ok = self
+            .plugin_url
+            .as_ref()
+            .is_none_or(|url| pane.plugin_url.as_deref() == Some(url.as_str()));
+
+        title_ok && regex_ok && contains_ok && command_ok && plugin_ok
+    }
+}
+
+/// Match a terminal command in full, or by the name of the program it runs
+fn command_matches(actual: &str, expected: &str) -> bool {
+    let actual = actual.trim();
+    if actual == expected.trim() {
+        return true;
+    }
+
+    actual
+        .split_whitespace()
+        .next()
+        .and_then(|program| program.rsplit('/').next())
+        .is_some_and(|program| program == expected.trim())
+}
+
+/// A role whose selector matched more than one pane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousRole {
+    pub role: PaneRole,
+    pub panes: Vec<PaneId>,
+}
+
+impl fmt::Display for AmbiguousRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} matches {} panes ({:?}); configure {}_pane_title or another selector",
+            self.role,
+            self.panes.len(),
+            self.panes,
+            self.role.config_key()
+        )
+    }
+}
+
+/// Outcome of matching a pane manifest against the role selectors
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaneMatches {
+    /// Roles that matched exactly one pane
+    pub matched: Vec<(PaneRole, PaneId)>,
+    /// Roles left unregistered because several panes matched
+    pub ambiguous: Vec<AmbiguousRole>,
+    /// Tab the search was limited to, if the plugin's own tab was found
+    pub tab: Option<usize>,
+}
+
+/// Tabs searched for role panes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaneScope {
+    /// The plugin's own tab, or every tab while the plugin's pane is not in the manifest
+    #[default]
+    Auto,
+    /// Only the plugin's own tab, so coordinators in other tabs keep their panes
+    Tab,
+}
+
+/// Role selectors for every pane role
+#[derive(Debug, Clone)]
+pub struct PaneSelectors {
+    selectors: Vec<(PaneRole, RoleSelector)>,
+    scope: PaneScope,
+}
+
+impl Default for PaneSelectors {
+    fn default() -> Self {
+        Self {
+            selectors: DEFAULT_TITLE_PATTERNS
+                .iter()
+                .map(|(role, fragment)| (*role, RoleSelector::title_contains(fragment)))
+                .collect(),
+            scope: PaneScope::default(),
+        }
+    }
+}
+
+impl PaneSelectors {
+    /// Read `<role>_pane_title`, `<role>_pane_title_regex`, `<role>_pane_command`,
+    /// `<role>_pane_plugin` and `pane_scope` from the plugin configuration
+    ///
+    /// Roles without any of these keys keep the default title match.
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+    ) -> Result<Self, SelectorConfigError> {
+        let scope = match configuration.get("pane_scope").map(|value| value.trim()) {
+            None | Some("") | Some("auto") => PaneScope::Auto,
+            Some("tab") => PaneScope::Tab,
+            Some(other) => return Err(SelectorConfigError::InvalidScope(other.to_string())),
+        };
+        let mut selectors = Self {
+            scope,
+            ..Self::default()
+        };
+
+        for role in PaneRole::ALL.iter() {
+            let key = |suffix: &str| {
+                configuration
+                    .get(&format!("{}_pane_{}", role.config_key(), suffix))
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+
+            let title_regex =
+                match key("title_regex") {
+                    Some(pattern) => Some(Regex::new(&pattern).map_err(|e| {
+                        SelectorConfigError::InvalidRegex {
+                            role: *role,
+                            pattern: pattern.clone(),
+                            error: e.to_string(),
+                        }
+                    })?),
+                    None => None,
+                };
+
+            let selector = RoleSelector {
+                title: key("title"),
+                title_regex,
+                title_contains: None,
+                command: key("command"),
+                plugin_url: key("plugin"),
+            };
+
+            if !selector.is_empty() {
+                selectors.set(*role, selector);
+            }
+        }
+
+        Ok(selectors)
+    }
+
+    /// Tabs searched for role panes
+    pub fn scope(&self) -> PaneScope {
+        self.scope
+    }
+
+    /// Replace the selector for a role
+    pub fn set(&mut self, role: PaneRole, selector: RoleSelector) {
+        match self.selectors.iter_mut().find(|(r, _)| *r == role) {
+            Some(entry) => entry.1 = selector,
+            None => self.selectors.push((role, selector)),
+        }
+    }
+
+    /// First role whose selector matches the pane, in role order
+    pub fn role_for(&self, pane: &PaneInfo) -> Option<PaneRole> {
+        PaneRole::ALL.iter().copied().find(|role| {
+            self.selectors
+                .iter()
+                .any(|(r, selector)| r == role && selector.matches(pane))
+        })
+    }
+
+    /// Match the panes of the plugin's own tab against the selectors
+    ///
+    /// If the plugin's pane cannot be found in the manifest, every tab is
+    /// searched, unless the scope is limited to the plugin's tab.
+    pub fn match_manifest(
+        &self,
+        manifest: &PaneManifest,
+        own_plugin_id: Option<u32>,
+    ) -> PaneMatches {
+        let tab = own_plugin_id.and_then(|id| find_plugin_tab(manifest, id));
+        if tab.is_none() && self.scope == PaneScope::Tab {
+            return PaneMatches::default();
+        }
+
+        let mut tabs: Vec<&usize> = manifest.panes.keys().collect();
+        tabs.sort();
+
+        let mut candidates: Vec<(PaneRole, Vec<PaneId>)> = Vec::new();
+        for tab_index in tabs {
+            if tab.is_some_and(|own| own != *tab_index) {
+                continue;
+            }
+
+            for pane in &manifest.panes[tab_index] {
+                if own_plugin_id.is_some_and(|id| pane.is_plugin && pane.id == id) {
+                    continue;
+                }
+
+                if let Some(role) = self.role_for(pane) {
+                    let pane_id = if pane.is_plugin {
+                        PaneId::Plugin(pane.id)
+                    } else {
+                        PaneId::Terminal(pane.id)
+                    };
+
+                    match candidates.iter_mut().find(|(r, _)| *r == role) {
+                        Some((_, panes)) => panes.push(pane_id),
+                        None => candidates.push((role, vec![pane_id])),
+                    }
+                }
+            }
+        }
+
+        let mut matches = PaneMatches {
+            tab,
+            ..PaneMatches::default()
+        };
+        for (role, panes) in candidates {
+            if panes.len() == 1 {
+                matches.matched.push((role, panes[0]));
+            } else {
+                matches.ambiguous.push(AmbiguousRole { role, panes });
+            }
+        }
+        matches
+    }
+}
+
+/// Tab index holding the plugin pane with the given ID
+pub fn find_plugin_tab(manifest: &PaneManifest, plugin_id: u32) -> Option<usize> {
+    manifest.panes.iter().find_map(|(tab, panes)| {
+        panes
+            .iter()
+            .any(|pane| pane.is_plugin && pane.id == plugin_id)
+            .then_some(*tab)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn pane(id: u32, title: &str) -> PaneInfo {
+        PaneInfo {
+            id,
+            title: title.to_string(),
+            ..PaneInfo::default()
+        }
+    }
+
+    fn terminal(id: u32, title: &str, command: &str) -> PaneInfo {
+        PaneInfo {
+            terminal_command: Some(command.to_string()),
+            ..pane(id, title)
+        }
+    }
+
+    fn plugin(id: u32, title: &str, url: &str) -> PaneInfo {
+        PaneInfo {
+            is_plugin: true,
+            plugin_url: Some(url.to_string()),
+            ..pane(id, title)
+        }
+    }
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn manifest(tabs: Vec<(usize, Vec<PaneInfo>)>) -> PaneManifest {
+        PaneManifest {
+            panes: tabs.into_iter().collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_default_selectors_match_title_fragments() {
+        let selectors = PaneSelectors::default();
+        assert_eq!(
+            selectors.role_for(&pane(1, "Overseer AI")),
+            Some(PaneRole::Overseer)
+        );
+        assert_eq!(
+            selectors.role_for(&pane(2, "code review panel")),
+            Some(PaneRole::Review)
+        );
+        assert_eq!(selectors.role_for(&pane(3, "Scratch")), None);
+    }
+
+    #[test]
+    fn test_exact_title_selector() {
+        let selectors =
+            PaneSelectors::from_configuration(&configuration(&[("review_pane_title", "Review")]))
+                .unwrap();
+
+        assert_eq!(
+            selectors.role_for(&pane(1, "Review")),
+            Some(PaneRole::Review)
+        );
+        assert_eq!(selectors.role_for(&pane(2, "Code Review Panel")), None);
+        // Other roles keep the default
+        assert_eq!(
+            selectors.role_for(&pane(3, "Commander")),
+            Some(PaneRole::Commander)
+        );
+    }
+
+    #[test]
+    fn test_regex_selector() {
+        let selectors = PaneSelectors::from_configuration(&configuration(&[(
+            "overseer_pane_title_regex",
+            "^(Overseer|Planner)$",
+        )]))
+        .unwrap();
+
+        assert_eq!(
+            selectors.role_for(&pane(1, "Planner")),
+            Some(PaneRole::Overseer)
+        );
+        assert_eq!(selectors.role_for(&pane(2, "Overseer 2")), None);
+    }
+
+    #[test]
+    fn test_invalid_regex_is_reported() {
+        let result = PaneSelectors::from_configuration(&configuration(&[(
+            "overseer_pane_title_regex",
+            "(",
+        )]));
+
+        match result {
+            Err(SelectorConfigError::InvalidRegex { role, pattern, .. }) => {
+                assert_eq!(role, PaneRole::Overseer);
+                assert_eq!(pattern, "(");
+            }
+            other => panic!("Expected InvalidRegex, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_command_selector() {
+        let selectors = PaneSelectors::from_configuration(&configuration(&[(
+            "commander_pane_command",
+            "bash",
+        )]))
+        .unwrap();
+
+        assert_eq!(
+            selectors.role_for(&terminal(1, "anything", "/usr/bin/bash --login")),
+            Some(PaneRole::Commander)
+        );
+        assert_eq!(selectors.role_for(&terminal(2, "anything", "zsh")), None);
+        assert_eq!(selectors.role_for(&pane(3, "no command")), None);
+    }
+
+    #[test]
+    fn test_plugin_url_selector() {
+        let selectors = PaneSelectors::from_configuration(&configuration(&[(
+            "task_list_pane_plugin",
+            "file:/plugins/todo.wasm",
+        )]))
+        .unwrap();
+
+        assert_eq!(
+            selectors.role_for(&plugin(1, "Todo", "file:/plugins/todo.wasm")),
+            Some(PaneRole::TaskList)
+        );
+        assert_eq!(
+            selectors.role_for(&plugin(2, "Todo", "zellij:strider")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_all_criteria_must_match() {
+        let selectors = PaneSelectors::from_configuration(&configuration(&[
+            ("commander_pane_title", "Commander"),
+            ("commander_pane_command", "bash"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            selectors.role_for(&terminal(1, "Commander", "bash")),
+            Some(PaneRole::Commander)
+        );
+        assert_eq!(selectors.role_for(&terminal(2, "Commander", "zsh")), None);
+    }
+
+    #[test]
+    fn test_match_manifest_reports_ambiguous_roles() {
+        let selectors = PaneSelectors::default();
+        let manifest = manifest(vec![(
+            0,
+            vec![
+                pane(1, "Overseer"),
+                pane(2, "Review"),
+                pane(3, "Code Review Panel"),
+            ],
+        )]);
+
+        let matches = selectors.match_manifest(&manifest, None);
+        assert_eq!(
+            matches.matched,
+            vec![(PaneRole::Overseer, PaneId::Terminal(1))]
+        );
+        assert_eq!(
+            matches.ambiguous,
+            vec![AmbiguousRole {
+                role: PaneRole::Review,
+                panes: vec![PaneId::Terminal(2), PaneId::Terminal(3)],
+            }]
+        );
+        assert!(format!("{}", matches.ambiguous[0]).contains("configure review_pane_title"));
+    }
+
+    #[test]
+    fn test_match_manifest_is_scoped_to_own_tab() {
+        let selectors = PaneSelectors::default();
+        let manifest = manifest(vec![
+            (0, vec![pane(1, "Overseer"), pane(2, "Commander")]),
+            (
+                1,
+                vec![plugin(9, "zzz", "file:zzz.wasm"), pane(3, "Overseer")],
+            ),
+        ]);
+
+        let matches = selectors.match_manifest(&manifest, Some(9));
+        assert_eq!(matches.tab, Some(1));
+        assert_eq!(
+            matches.matched,
+            vec![(PaneRole::Overseer, PaneId::Terminal(3))]
+        );
+        assert!(matches.ambiguous.is_empty());
+    }
+
+    #[test]
+    fn test_match_manifest_searches_all_tabs_without_own_pane() {
+        let selectors = PaneSelectors::default();
+        let manifest = manifest(vec![
+            (0, vec![pane(1, "Overseer")]),
+            (1, vec![pane(2, "Commander")]),
+        ]);
+
+        let matches = selectors.match_manifest(&manifest, Some(42));
+        assert_eq!(matches.tab, None);
+        assert_eq!(matches.matched.len(), 2);
+    }
+
+    #[test]
+    fn test_find_plugin_tab() {
+        let manifest = manifest(vec![
+            (0, vec![pane(5, "Terminal with same id")]),
+            (2, vec![plugin(5, "zzz", "file:zzz.wasm")]),
+        ]);
+
+        assert_eq!(find_plugin_tab(&manifest, 5), Some(2));
+        assert_eq!(find_plugin_tab(&manifest, 6), None);
+    }
+
+    #[test]
+    fn test_tab_scope_never_searches_other_tabs() {
+        let selectors =
+            PaneSelectors::from_configuration(&configuration(&[("pane_scope", "tab")])).unwrap();
+        assert_eq!(selectors.scope(), PaneScope::Tab);
+
+        let manifest = manifest(vec![
+            (0, vec![pane(1, "Overseer"), pane(2, "Commander")]),
+            (
+                1,
+                vec![plugin(9, "zzz", "file:zzz.wasm"), pane(3, "Commander")],
+            ),
+        ]);
+
+        // Another tab's panes are left to the coordinator running there
+        let matches = selectors.match_manifest(&manifest, Some(42));
+        assert_eq!(matches, PaneMatches::default());
+
+        let matches = selectors.match_manifest(&manifest, Some(9));
+        assert_eq!(matches.tab, Some(1));
+        assert_eq!(
+            matches.matched,
+            vec![(PaneRole::Commander, PaneId::Terminal(3))]
+        );
+    }
+
+    #[test]
+    fn test_pane_scope_configuration() {
+        assert_eq!(PaneSelectors::default().scope(), PaneScope::Auto);
+        assert_eq!(
+            PaneSelectors::from_configuration(&configuration(&[("pane_scope", " auto ")]))
+                .unwrap()
+                .scope(),
+            PaneScope::Auto
+        );
+
+        let error = PaneSelectors::from_configuration(&configuration(&[("pane_scope", "session")]))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SelectorConfigError::InvalidScope("session".to_string())
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid pane_scope 'session' (expected auto or tab)"
+        );
+    }
+}
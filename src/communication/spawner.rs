@@ -0,0 +1,472 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+use zellij_tile::prelude::{CommandToRun, PaneId, PaneManifest};
+
+use crate::assistant::AssistantConfig;
+use crate::pane_role::PaneRole;
+use crate::shell::ShellDialect;
+
+/// Roles the workflow cannot run without, spawned when the layout lacks them
+pub const REQUIRED_ROLES: [PaneRole; 2] = [PaneRole::Overseer, PaneRole::Commander];
+
+/// Context key identifying the role of a spawned pane in `CommandPaneOpened`
+pub const SPAWN_ROLE_CONTEXT_KEY: &str = "zzz_role";
+
+/// Context key holding the plugin ID of the coordinator that spawned the pane
+pub const SPAWN_OWNER_CONTEXT_KEY: &str = "zzz_owner";
+
+/// Errors in the pane spawning configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpawnConfigError {
+    InvalidFlag(String),
+}
+
+impl fmt::Display for SpawnConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnConfigError::InvalidFlag(value) => write!(
+                f,
+                "Invalid spawn_missing_panes '{}' (expected true or false)",
+                value
+            ),
+        }
+    }
+}
+
+/// How to open the pane for a role
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnSpec {
+    /// Title given to the pane once it is open
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl SpawnSpec {
+    /// Spec running the given shell interactively, named after the role
+    pub fn shell(role: PaneRole, shell: ShellDialect) -> Self {
+        Self {
+            name: role.display_name().to_string(),
+            program: shell.name().to_string(),
+            args: Vec::new(),
+            cwd: None,
+        }
+    }
+
+    /// Command handed to Zellij to open the pane
+    pub fn command_to_run(&self) -> CommandToRun {
+        CommandToRun {
+            path: PathBuf::from(&self.program),
+            args: self.args.clone(),
+            cwd: self.cwd.clone(),
+        }
+    }
+}
+
+/// Progress of a pane the coordinator asked Zellij to open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnState {
+    /// The pane was requested but Zellij has not reported its ID yet
+    Requested,
+    /// Zellij opened the pane; it is registered once it shows up in a `PaneUpdate`
+    Opened(u32),
+    /// The pane appeared in the manifest and belongs to the role
+    Adopted(u32),
+}
+
+/// Opens panes for required roles missing from the layout and tracks them until registered
+///
+/// Each role is spawned at most once per plugin instance, so closing a
+/// spawned pane does not make it reappear.
+#[derive(Debug, Clone)]
+pub struct PaneSpawner {
+    enabled: bool,
+    specs: Vec<(PaneRole, SpawnSpec)>,
+    spawns: HashMap<PaneRole, SpawnState>,
+}
+
+impl Default for PaneSpawner {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            specs: REQUIRED_ROLES
+                .iter()
+                .map(|role| (*role, SpawnSpec::shell(*role, ShellDialect::default())))
+                .collect(),
+            spawns: HashMap::new(),
+        }
+    }
+}
+
+impl PaneSpawner {
+    /// Read `spawn_missing_panes`, `<role>_pane_spawn_command` and `<role>_pane_cwd`
+    ///
+    /// Spawned panes run the role's configured shell unless a command is given,
+    /// start in `default_cwd` unless a directory is given, and are titled with
+    /// `<role>_pane_title` when set so that the role's selector still matches.
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+        assistants: &AssistantConfig,
+        default_cwd: Option<PathBuf>,
+    ) -> Result<Self, SpawnConfigError> {
+        let enabled = match configuration
+            .get("spawn_missing_panes")
+            .map(|value| value.trim())
+        {
+            None | Some("true") => true,
+            Some("false") => false,
+            Some(other) => return Err(SpawnConfigError::InvalidFlag(other.to_string())),
+        };
+
+        let specs = REQUIRED_ROLES
+            .iter()
+            .map(|role| {
+                let key = |suffix: &str| {
+                    configuration
+                        .get(&format!("{}_pane_{}", role.config_key(), suffix))
+                        .map(|value| value.trim().to_string())
+                        .filter(|value| !value.is_empty())
+                };
+
+                let mut spec = SpawnSpec::shell(*role, assistants.for_role(*role).shell);
+                if let Some(title) = key("title") {
+                    spec.name = title;
+                }
+                if let Some(command) = key("spawn_command") {
+                    let mut words = command.split_whitespace().map(str::to_string);
+                    spec.program = words.next().unwrap_or_default();
+                    spec.args = words.collect();
+                }
+                spec.cwd = key("cwd")
+                    .map(PathBuf::from)
+                    .or_else(|| default_cwd.clone());
+
+                (*role, spec)
+            })
+            .collect();
+
+        Ok(Self {
+            enabled,
+            specs,
+            spawns: HashMap::new(),
+        })
+    }
+
+    /// Check whether missing panes may be spawned at all
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Spec used to open the pane for a role
+    pub fn spec(&self, role: PaneRole) -> Option<&SpawnSpec> {
+        self.specs
+            .iter()
+            .find(|(r, _)| *r == role)
+            .map(|(_, spec)| spec)
+    }
+
+    /// Spawn progress for a role, if it was ever spawned
+    pub fn state(&self, role: PaneRole) -> Option<SpawnState> {
+        self.spawns.get(&role).copied()
+    }
+
+    /// Required roles that are not registered and have not been spawned before
+    pub fn roles_to_spawn(&self, registered: &[PaneRole]) -> Vec<PaneRole> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        self.specs
+            .iter()
+            .map(|(role, _)| *role)
+            .filter(|role| !registered.contains(role) && !self.spawns.contains_key(role))
+            .collect()
+    }
+
+    /// Context attached to the open request so the opened pane can be traced back to
+    /// its role and to the coordinator that asked for it
+    pub fn context(role: PaneRole, owner: Option<u32>) -> BTreeMap<String, String> {
+        let mut context = BTreeMap::new();
+        context.insert(
+            SPAWN_ROLE_CONTEXT_KEY.to_string(),
+            role.config_key().to_string(),
+        );
+        if let Some(owner) = owner {
+            context.insert(SPAWN_OWNER_CONTEXT_KEY.to_string(), owner.to_string());
+        }
+        context
+    }
+
+    /// Record that a pane was requested for a role
+    pub fn mark_requested(&mut self, role: PaneRole) {
+        self.spawns.insert(role, SpawnState::Requested);
+    }
+
+    /// Record the terminal ID Zellij gave a requested pane
+    ///
+    /// Returns the role if the context belongs to one of our pending spawns.
+    /// Panes spawned by a coordinator with another `owner` are ignored.
+    pub fn pane_opened(
+        &mut self,
+        terminal_pane_id: u32,
+        context: &BTreeMap<String, String>,
+        owner: Option<u32>,
+    ) -> Option<PaneRole> {
+        let context_owner = context
+            .get(SPAWN_OWNER_CONTEXT_KEY)
+            .map(|id| id.parse::<u32>().ok());
+        if context_owner.is_some_and(|id| id != owner) {
+            return None;
+        }
+
+        let role = context
+            .get(SPAWN_ROLE_CONTEXT_KEY)
+            .and_then(|key| PaneRole::from_config_key(key))?;
+
+        match self.spawns.get(&role) {
+            Some(SpawnState::Requested) => {
+                self.spawns
+                    .insert(role, SpawnState::Opened(terminal_pane_id));
+                Some(role)
+            }
+            _ => None,
+        }
+    }
+
+    /// Spawned panes present in the manifest, marking newly seen ones as adopted
+    pub fn adopt(&mut self, manifest: &PaneManifest) -> Vec<(PaneRole, PaneId)> {
+        let mut adopted = Vec::new();
+
+        for role in REQUIRED_ROLES.iter() {
+            let pane_id = match self.spawns.get(role) {
+                Some(SpawnState::Opened(id)) | Some(SpawnState::Adopted(id)) => *id,
+                _ => continue,
+            };
+
+            let present = manifest
+                .panes
+                .values()
+                .flatten()
+                .any(|pane| !pane.is_plugin && pane.id == pane_id);
+            if present {
+                self.spawns.insert(*role, SpawnState::Adopted(pane_id));
+                adopted.push((*role, PaneId::Terminal(pane_id)));
+            }
+        }
+
+        adopted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assistant::RoleAssistant;
+    use std::collections::HashMap;
+    use zellij_tile::prelude::PaneInfo;
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn manifest_with_terminals(ids: &[u32]) -> PaneManifest {
+        let panes = ids
+            .iter()
+            .map(|id| PaneInfo {
+                id: *id,
+                ..PaneInfo::default()
+            })
+            .collect();
+        let mut tabs = HashMap::new();
+        tabs.insert(0, panes);
+        PaneManifest { panes: tabs }
+    }
+
+    #[test]
+    fn test_default_spawns_required_roles_with_sh() {
+        let spawner = PaneSpawner::default();
+
+        assert!(spawner.is_enabled());
+        assert_eq!(
+            spawner.roles_to_spawn(&[]),
+            vec![PaneRole::Overseer, PaneRole::Commander]
+        );
+        let spec = spawner.spec(PaneRole::Overseer).unwrap();
+        assert_eq!(spec.name, "Overseer");
+        assert_eq!(spec.program, "sh");
+        assert!(spawner.spec(PaneRole::Editor).is_none());
+    }
+
+    #[test]
+    fn test_registered_roles_are_not_spawned() {
+        let spawner = PaneSpawner::default();
+        assert_eq!(
+            spawner.roles_to_spawn(&[PaneRole::Overseer, PaneRole::TaskList]),
+            vec![PaneRole::Commander]
+        );
+    }
+
+    #[test]
+    fn test_from_configuration_uses_role_shell_and_default_cwd() {
+        let mut assistants = AssistantConfig::default();
+        assistants.set_role(
+            PaneRole::Commander,
+            RoleAssistant {
+                shell: ShellDialect::Zsh,
+                ..RoleAssistant::default()
+            },
+        );
+
+        let spawner = PaneSpawner::from_configuration(
+            &BTreeMap::new(),
+            &assistants,
+            Some(PathBuf::from("/work/project")),
+        )
+        .unwrap();
+
+        let spec = spawner.spec(PaneRole::Commander).unwrap();
+        assert_eq!(spec.program, "zsh");
+        assert!(spec.args.is_empty());
+        assert_eq!(spec.cwd, Some(PathBuf::from("/work/project")));
+    }
+
+    #[test]
+    fn test_from_configuration_overrides() {
+        let config = configuration(&[
+            ("overseer_pane_spawn_command", "zsh -l"),
+            ("overseer_pane_cwd", "/srv/app"),
+            ("overseer_pane_title", "AI Planner"),
+        ]);
+
+        let spawner =
+            PaneSpawner::from_configuration(&config, &AssistantConfig::default(), None).unwrap();
+
+        let spec = spawner.spec(PaneRole::Overseer).unwrap();
+        assert_eq!(spec.name, "AI Planner");
+        assert_eq!(spec.program, "zsh");
+        assert_eq!(spec.args, vec!["-l".to_string()]);
+        assert_eq!(spec.cwd, Some(PathBuf::from("/srv/app")));
+
+        let command = spec.command_to_run();
+        assert_eq!(command.path, PathBuf::from("zsh"));
+        assert_eq!(command.args, vec!["-l".to_string()]);
+        assert_eq!(command.cwd, Some(PathBuf::from("/srv/app")));
+    }
+
+    #[test]
+    fn test_from_configuration_disabled() {
+        let config = configuration(&[("spawn_missing_panes", "false")]);
+        let spawner =
+            PaneSpawner::from_configuration(&config, &AssistantConfig::default(), None).unwrap();
+
+        assert!(!spawner.is_enabled());
+        assert!(spawner.roles_to_spawn(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_from_configuration_rejects_invalid_flag() {
+        let config = configuration(&[("spawn_missing_panes", "yes")]);
+        let error = PaneSpawner::from_configuration(&config, &AssistantConfig::default(), None)
+            .unwrap_err();
+
+        assert_eq!(error, SpawnConfigError::InvalidFlag("yes".to_string()));
+        assert_eq!(
+            error.to_string(),
+            "Invalid spawn_missing_panes 'yes' (expected true or false)"
+        );
+    }
+
+    #[test]
+    fn test_requested_role_is_not_spawned_again() {
+        let mut spawner = PaneSpawner::default();
+        spawner.mark_requested(PaneRole::Overseer);
+
+        assert_eq!(
+            spawner.state(PaneRole::Overseer),
+            Some(SpawnState::Requested)
+        );
+        assert_eq!(spawner.roles_to_spawn(&[]), vec![PaneRole::Commander]);
+    }
+
+    #[test]
+    fn test_pane_opened_matches_context() {
+        let mut spawner = PaneSpawner::default();
+        spawner.mark_requested(PaneRole::Commander);
+
+        let role = spawner.pane_opened(7, &PaneSpawner::context(PaneRole::Commander, None), None);
+
+        assert_eq!(role, Some(PaneRole::Commander));
+        assert_eq!(
+            spawner.state(PaneRole::Commander),
+            Some(SpawnState::Opened(7))
+        );
+    }
+
+    #[test]
+    fn test_pane_opened_ignores_foreign_and_unrequested_panes() {
+        let mut spawner = PaneSpawner::default();
+
+        assert_eq!(spawner.pane_opened(7, &BTreeMap::new(), None), None);
+        assert_eq!(
+            spawner.pane_opened(7, &PaneSpawner::context(PaneRole::Overseer, None), None),
+            None
+        );
+        assert_eq!(spawner.state(PaneRole::Overseer), None);
+    }
+
+    #[test]
+    fn test_pane_opened_ignores_other_coordinators_spawns() {
+        let mut spawner = PaneSpawner::default();
+        spawner.mark_requested(PaneRole::Commander);
+
+        let foreign = PaneSpawner::context(PaneRole::Commander, Some(8));
+        assert_eq!(spawner.pane_opened(7, &foreign, Some(9)), None);
+        assert_eq!(
+            spawner.state(PaneRole::Commander),
+            Some(SpawnState::Requested)
+        );
+
+        let own = PaneSpawner::context(PaneRole::Commander, Some(9));
+        assert_eq!(own.get(SPAWN_OWNER_CONTEXT_KEY), Some(&"9".to_string()));
+        assert_eq!(
+            spawner.pane_opened(11, &own, Some(9)),
+            Some(PaneRole::Commander)
+        );
+    }
+
+    #[test]
+    fn test_adopt_waits_for_pane_update() {
+        let mut spawner = PaneSpawner::default();
+        spawner.mark_requested(PaneRole::Overseer);
+        spawner.pane_opened(3, &PaneSpawner::context(PaneRole::Overseer, None), None);
+
+        assert!(spawner.adopt(&manifest_with_terminals(&[1, 2])).is_empty());
+        assert_eq!(
+            spawner.state(PaneRole::Overseer),
+            Some(SpawnState::Opened(3))
+        );
+
+        let adopted = spawner.adopt(&manifest_with_terminals(&[1, 2, 3]));
+        assert_eq!(adopted, vec![(PaneRole::Overseer, PaneId::Terminal(3))]);
+        assert_eq!(
+            spawner.state(PaneRole::Overseer),
+            Some(SpawnState::Adopted(3))
+        );
+    }
+
+    #[test]
+    fn test_closed_spawned_pane_is_not_respawned() {
+        let mut spawner = PaneSpawner::default();
+        spawner.mark_requested(PaneRole::Overseer);
+        spawner.pane_opened(3, &PaneSpawner::context(PaneRole::Overseer, None), None);
+        spawner.adopt(&manifest_with_terminals(&[3]));
+
+        assert!(spawner.adopt(&manifest_with_terminals(&[])).is_empty());
+        assert_eq!(spawner.roles_to_spawn(&[]), vec![PaneRole::Commander]);
+    }
+}
@@ -0,0 +1,776 @@
+use serde::Serialize;
+
+use crate::pane_role::PaneRole;
+use crate::task_id::{TaskId, TaskIdError};
+use crate::task_registry::TaskEntry;
+use crate::todo_list::TodoProgress;
+use crate::workflow::{GuardContext, PromptKind, TransitionError, WorkflowEvent, WorkflowMachine};
+use crate::workflow_phase::WorkflowPhase;
+
+/// Name of the pipe that carries control commands (`zellij pipe --name zzz -- status`)
+pub const CONTROL_PIPE: &str = "zzz";
+
+/// Operator command received over the control pipe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Report the workflow state without changing it
+    Status,
+    /// Fire the next event the current phase is waiting on
+    Advance,
+    /// Send the in-flight assistant command again
+    RetryPhase,
+    /// Move straight to a phase, bypassing the transition guards
+    SkipTo(WorkflowPhase),
+    /// Hold back automatic transitions
+    Pause,
+    /// Allow automatic transitions again and catch up on missed ones
+    Resume,
+    /// Abandon the workflow
+    Abort,
+    /// Replace the task description used in later prompts
+    SetDescription(String),
+    /// Accept the todo list even though it has not validated
+    ApprovePlan,
+    /// Send the plan back to the Overseer, with optional feedback
+    RejectPlan(Option<String>),
+    /// Start the review before every todo item is ticked
+    ForceReview,
+    /// Archive the task files and start planning again
+    ResetTask,
+    /// Type the last command into a role's pane again (the working role by default)
+    Resend(Option<PaneRole>),
+    /// List every task in the task index
+    Tasks,
+    /// Make another task the coordinator's active task, starting it with the
+    /// description if it is new
+    SwitchTask {
+        task_id: TaskId,
+        description: Option<String>,
+    },
+}
+
+impl ControlCommand {
+    /// Parse a command line such as `skip-to review-in-progress`
+    ///
+    /// The first word names the command; `set-description` takes the rest of
+    /// the line verbatim, as does `switch-task` after the task ID.
+    pub fn parse(input: &str) -> Result<ControlCommand, ControlError> {
+        let input = input.trim();
+        let (name, argument) = match input.find(char::is_whitespace) {
+            Some(index) => (&input[..index], input[index..].trim()),
+            None => (input, ""),
+        };
+
+        let command = match name {
+            "" => return Err(ControlError::Empty),
+            "status" => ControlCommand::Status,
+            "advance" => ControlCommand::Advance,
+            "retry-phase" => ControlCommand::RetryPhase,
+            "pause" => ControlCommand::Pause,
+            "resume" => ControlCommand::Resume,
+            "abort" => ControlCommand::Abort,
+            "skip-to" => {
+                if argument.is_empty() {
+                    return Err(ControlError::MissingArgument("skip-to"));
+                }
+                let phase = WorkflowPhase::from_name(argument)
+                    .ok_or_else(|| ControlError::UnknownPhase(argument.to_string()))?;
+                return Ok(ControlCommand::SkipTo(phase));
+            }
+            "set-description" => {
+                if argument.is_empty() {
+                    return Err(ControlError::MissingArgument("set-description"));
+                }
+                return Ok(ControlCommand::SetDescription(argument.to_string()));
+            }
+            "approve-plan" => ControlCommand::ApprovePlan,
+            "reject-plan" => {
+                let feedback = Some(argument.to_string()).filter(|text| !text.is_empty());
+                return Ok(ControlCommand::RejectPlan(feedback));
+            }
+            "force-review" => ControlCommand::ForceReview,
+            "reset-task" => ControlCommand::ResetTask,
+            "resend" if argument.is_empty() => ControlCommand::Resend(None),
+            "resend" => {
+                let role = PaneRole::from_config_key(&argument.to_lowercase())
+                    .ok_or_else(|| ControlError::UnknownRole(argument.to_string()))?;
+                return Ok(ControlCommand::Resend(Some(role)));
+            }
+            "tasks" => ControlCommand::Tasks,
+            "switch-task" => {
+                let (task_id, description) = match argument.find(char::is_whitespace) {
+                    Some(index) => (&argument[..index], argument[index..].trim()),
+                    None => (argument, ""),
+                };
+                if task_id.is_empty() {
+                    return Err(ControlError::MissingArgument("switch-task"));
+                }
+                return Ok(ControlCommand::SwitchTask {
+                    task_id: TaskId::parse(task_id).map_err(ControlError::InvalidTaskId)?,
+                    description: Some(description.to_string()).filter(|text| !text.is_empty()),
+                });
+            }
+            other => return Err(ControlError::UnknownCommand(other.to_string())),
+        };
+
+        if !argument.is_empty() {
+            return Err(ControlError::UnexpectedArgument(command.name()));
+        }
+
+        Ok(command)
+    }
+
+    /// Parse a command bound to a key: the pipe name is the command, the payload its argument
+    pub fn parse_keybind(
+        name: &str,
+        payload: Option<&str>,
+    ) -> Result<ControlCommand, ControlError> {
+        Self::parse(&format!("{} {}", name, payload.unwrap_or_default()))
+    }
+
+    /// The state-machine event a manual override fires, if it is one
+    pub fn event(&self) -> Option<WorkflowEvent> {
+        match self {
+            ControlCommand::ApprovePlan => Some(WorkflowEvent::ApprovePlan),
+            ControlCommand::RejectPlan(_) => Some(WorkflowEvent::RejectPlan),
+            ControlCommand::ForceReview => Some(WorkflowEvent::ForceReview),
+            ControlCommand::ResetTask => Some(WorkflowEvent::Reset),
+            ControlCommand::SkipTo(_) => Some(WorkflowEvent::Skip),
+            ControlCommand::Abort => Some(WorkflowEvent::Abort),
+            _ => None,
+        }
+    }
+
+    /// The command's name as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlCommand::Status => "status",
+            ControlCommand::Advance => "advance",
+            ControlCommand::RetryPhase => "retry-phase",
+            ControlCommand::SkipTo(_) => "skip-to",
+            ControlCommand::Pause => "pause",
+            ControlCommand::Resume => "resume",
+            ControlCommand::Abort => "abort",
+            ControlCommand::SetDescription(_) => "set-description",
+            ControlCommand::ApprovePlan => "approve-plan",
+            ControlCommand::RejectPlan(_) => "reject-plan",
+            ControlCommand::ForceReview => "force-review",
+            ControlCommand::ResetTask => "reset-task",
+            ControlCommand::Resend(_) => "resend",
+            ControlCommand::Tasks => "tasks",
+            ControlCommand::SwitchTask { .. } => "switch-task",
+        }
+    }
+}
+
+/// Reasons a control command could not be parsed or carried out
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlError {
+    /// The payload contained no command
+    Empty,
+    UnknownCommand(String),
+    /// The named command requires an argument
+    MissingArgument(&'static str),
+    /// The named command takes no argument
+    UnexpectedArgument(&'static str),
+    UnknownPhase(String),
+    UnknownRole(String),
+    /// The state machine rejected the requested move
+    Transition(TransitionError),
+    /// No assistant is working in the given phase
+    NothingToRetry(WorkflowPhase),
+    /// The given phase has no outgoing transitions
+    NothingToAdvance(WorkflowPhase),
+    /// Nothing has been typed into the role's pane yet
+    NothingToResend(PaneRole),
+    /// Running the command's workflow action failed
+    Action(String),
+    InvalidTaskId(TaskIdError),
+    /// A new task needs a description to start from
+    UnknownTask(TaskId),
+    /// An assistant is still working on the active task
+    TaskBusy(PaneRole),
+    /// The task index could not be read or written
+    Registry(String),
+    /// The configured task ID is invalid, so no task is open
+    NoTask(TaskIdError),
+}
+
+impl std::fmt::Display for ControlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlError::Empty => write!(f, "No command given"),
+            ControlError::UnknownCommand(name) => write!(f, "Unknown command: {}", name),
+            ControlError::MissingArgument(name) => write!(f, "{} requires an argument", name),
+            ControlError::UnexpectedArgument(name) => write!(f, "{} takes no argument", name),
+            ControlError::UnknownPhase(name) => write!(f, "Unknown workflow phase: {}", name),
+            ControlError::UnknownRole(name) => write!(f, "Unknown pane role: {}", name),
+            ControlError::Transition(e) => write!(f, "{}", e),
+            ControlError::NothingToRetry(phase) => {
+                write!(f, "No assistant is working in {:?}", phase)
+            }
+            ControlError::NothingToAdvance(phase) => {
+                write!(f, "Nothing follows {:?}", phase)
+            }
+            ControlError::NothingToResend(role) => {
+                write!(f, "No command has been sent to the {:?} pane", role)
+            }
+            ControlError::Action(e) => write!(f, "Workflow action failed: {}", e),
+            ControlError::InvalidTaskId(e) => write!(f, "{}", e),
+            ControlError::NoTask(e) => write!(
+                f,
+                "No task is open because the configured task_id is invalid ({}); use switch-task",
+                e
+            ),
+            ControlError::UnknownTask(task_id) => write!(
+                f,
+                "Task {} is not in the task index; give a description to start it",
+                task_id
+            ),
+            ControlError::TaskBusy(role) => write!(
+                f,
+                "The {:?} assistant is still working; pause or abort the task first",
+                role
+            ),
+            ControlError::Registry(e) => write!(f, "Task index unavailable: {}", e),
+        }
+    }
+}
+
+impl From<TransitionError> for ControlError {
+    fn from(error: TransitionError) -> Self {
+        ControlError::Transition(error)
+    }
+}
+
+/// Pick the event `advance` fires: the first one leaving the current phase whose guards pass
+///
+/// When none pass, the first candidate's rejection is returned so the caller
+/// can tell the operator what is missing.
+pub fn advance_event(
+    machine: &WorkflowMachine,
+    guards: &GuardContext,
+) -> Result<WorkflowEvent, ControlError> {
+    let candidates = machine.available_events();
+    let first = *candidates
+        .first()
+        .ok_or(ControlError::NothingToAdvance(machine.phase()))?;
+
+    match candidates
+        .iter()
+        .copied()
+        .find(|event| machine.check(*event, guards).is_ok())
+    {
+        Some(event) => Ok(event),
+        None => Err(machine.check(first, guards).unwrap_err().into()),
+    }
+}
+
+/// Assistant whose work the current phase is waiting on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ActiveAssistant {
+    pub role: PaneRole,
+    pub prompt: PromptKind,
+}
+
+/// Todo list counts as reported to the CLI
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TodoSummary {
+    pub completed: usize,
+    pub total: usize,
+    pub percentage: u8,
+}
+
+impl From<TodoProgress> for TodoSummary {
+    fn from(progress: TodoProgress) -> Self {
+        Self {
+            completed: progress.completed,
+            total: progress.total,
+            percentage: progress.percentage(),
+        }
+    }
+}
+
+/// Workflow state included in every control reply
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkflowStatus {
+    pub task_id: TaskId,
+    pub task_description: String,
+    pub phase: WorkflowPhase,
+    pub paused: bool,
+    pub aborted: bool,
+    pub fix_rounds: u32,
+    /// Events the current phase can be left on
+    pub next_events: Vec<WorkflowEvent>,
+    pub in_flight: Option<ActiveAssistant>,
+    pub todo: Option<TodoSummary>,
+    /// Assistant work lost to exited panes
+    pub failed_work: usize,
+    /// Sent messages still waiting for an acknowledgement
+    pub pending_acks: usize,
+}
+
+impl WorkflowStatus {
+    /// Describe the machine's state; the remaining fields start empty
+    pub fn new(task_id: &TaskId, task_description: &str, machine: &WorkflowMachine) -> Self {
+        Self {
+            task_id: task_id.clone(),
+            task_description: task_description.to_string(),
+            phase: machine.phase(),
+            paused: false,
+            aborted: false,
+            fix_rounds: machine.fix_rounds(),
+            next_events: machine.available_events(),
+            in_flight: machine
+                .in_flight()
+                .map(|(role, prompt)| ActiveAssistant { role, prompt }),
+            todo: None,
+            failed_work: 0,
+            pending_acks: 0,
+        }
+    }
+}
+
+/// JSON reply written back to the CLI pipe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ControlReply {
+    pub ok: bool,
+    /// Name of the command, or the unrecognized word
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub status: WorkflowStatus,
+    /// Every task in the task index, for `tasks`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<Vec<TaskEntry>>,
+}
+
+impl ControlReply {
+    /// Reply to a command that was carried out
+    pub fn success(command: &str, message: Option<String>, status: WorkflowStatus) -> Self {
+        Self {
+            ok: true,
+            command: command.to_string(),
+            message,
+            error: None,
+            status,
+            tasks: None,
+        }
+    }
+
+    /// Reply to a command that was rejected or failed
+    pub fn failure(command: &str, error: &ControlError, status: WorkflowStatus) -> Self {
+        Self {
+            ok: false,
+            command: command.to_string(),
+            message: None,
+            error: Some(error.to_string()),
+            status,
+            tasks: None,
+        }
+    }
+
+    /// Attach the task index listing
+    pub fn with_tasks(self, tasks: Vec<TaskEntry>) -> Self {
+        Self {
+            tasks: Some(tasks),
+            ..self
+        }
+    }
+
+    /// Serialize the reply as a single line of JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|e| {
+            serde_json::json!({ "ok": false, "command": self.command, "error": e.to_string() })
+                .to_string()
+        })
+    }
+}
+
+/// The command word of a payload, used to label replies to unparsable commands
+pub fn command_word(input: &str) -> &str {
+    input.split_whitespace().next().unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::transition::ready_context;
+
+    #[test]
+    fn test_parse_simple_commands() {
+        let cases = [
+            ("status", ControlCommand::Status),
+            ("advance", ControlCommand::Advance),
+            ("retry-phase", ControlCommand::RetryPhase),
+            ("pause", ControlCommand::Pause),
+            ("resume", ControlCommand::Resume),
+            ("abort", ControlCommand::Abort),
+            ("tasks", ControlCommand::Tasks),
+            ("  status\n", ControlCommand::Status),
+        ];
+
+        for (input, expected) in cases.iter() {
+            assert_eq!(ControlCommand::parse(input).as_ref(), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_skip_to_phase() {
+        assert_eq!(
+            ControlCommand::parse("skip-to review-in-progress"),
+            Ok(ControlCommand::SkipTo(WorkflowPhase::ReviewInProgress))
+        );
+        assert_eq!(
+            ControlCommand::parse("skip-to  Finished "),
+            Ok(ControlCommand::SkipTo(WorkflowPhase::Finished))
+        );
+        assert_eq!(
+            ControlCommand::parse("skip-to reviewing"),
+            Err(ControlError::UnknownPhase("reviewing".to_string()))
+        );
+        assert_eq!(
+            ControlCommand::parse("skip-to"),
+            Err(ControlError::MissingArgument("skip-to"))
+        );
+    }
+
+    #[test]
+    fn test_parse_set_description_keeps_rest_of_line() {
+        assert_eq!(
+            ControlCommand::parse("set-description Add OAuth  login, then logout\n"),
+            Ok(ControlCommand::SetDescription(
+                "Add OAuth  login, then logout".to_string()
+            ))
+        );
+        assert_eq!(
+            ControlCommand::parse("set-description   "),
+            Err(ControlError::MissingArgument("set-description"))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert_eq!(ControlCommand::parse("  "), Err(ControlError::Empty));
+        assert_eq!(
+            ControlCommand::parse("restart"),
+            Err(ControlError::UnknownCommand("restart".to_string()))
+        );
+        assert_eq!(
+            ControlCommand::parse("pause now"),
+            Err(ControlError::UnexpectedArgument("pause"))
+        );
+        // Commands are case-sensitive, like their CLI counterparts
+        assert!(ControlCommand::parse("STATUS").is_err());
+    }
+
+    #[test]
+    fn test_name_roundtrips_through_parse() {
+        let commands = vec![
+            ControlCommand::Status,
+            ControlCommand::Advance,
+            ControlCommand::RetryPhase,
+            ControlCommand::SkipTo(WorkflowPhase::PlanReady),
+            ControlCommand::Pause,
+            ControlCommand::Resume,
+            ControlCommand::Abort,
+            ControlCommand::SetDescription("New".to_string()),
+            ControlCommand::ApprovePlan,
+            ControlCommand::RejectPlan(None),
+            ControlCommand::ForceReview,
+            ControlCommand::ResetTask,
+            ControlCommand::Resend(None),
+            ControlCommand::Tasks,
+            ControlCommand::SwitchTask {
+                task_id: TaskId::from(2),
+                description: None,
+            },
+        ];
+
+        for command in commands {
+            let line = match &command {
+                ControlCommand::SkipTo(_) => format!("{} plan-ready", command.name()),
+                ControlCommand::SetDescription(_) => format!("{} New", command.name()),
+                ControlCommand::SwitchTask { .. } => format!("{} 2", command.name()),
+                _ => command.name().to_string(),
+            };
+            assert_eq!(ControlCommand::parse(&line), Ok(command));
+        }
+    }
+
+    #[test]
+    fn test_parse_switch_task() {
+        assert_eq!(
+            ControlCommand::parse("switch-task PROJ-1234"),
+            Ok(ControlCommand::SwitchTask {
+                task_id: TaskId::parse("PROJ-1234").unwrap(),
+                description: None,
+            })
+        );
+        assert_eq!(
+            ControlCommand::parse("switch-task PROJ-7  Add OAuth  login\n"),
+            Ok(ControlCommand::SwitchTask {
+                task_id: TaskId::parse("PROJ-7").unwrap(),
+                description: Some("Add OAuth  login".to_string()),
+            })
+        );
+        assert_eq!(
+            ControlCommand::parse("switch-task"),
+            Err(ControlError::MissingArgument("switch-task"))
+        );
+        assert_eq!(
+            ControlCommand::parse("switch-task ../task-1"),
+            Err(ControlError::InvalidTaskId(TaskIdError::InvalidCharacter(
+                '/'
+            )))
+        );
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        assert_eq!(
+            ControlCommand::parse("reject-plan Split step 3 into smaller steps"),
+            Ok(ControlCommand::RejectPlan(Some(
+                "Split step 3 into smaller steps".to_string()
+            )))
+        );
+        assert_eq!(
+            ControlCommand::parse("reject-plan"),
+            Ok(ControlCommand::RejectPlan(None))
+        );
+        assert_eq!(
+            ControlCommand::parse("resend Commander"),
+            Ok(ControlCommand::Resend(Some(PaneRole::Commander)))
+        );
+        assert_eq!(
+            ControlCommand::parse("resend reviewer"),
+            Err(ControlError::UnknownRole("reviewer".to_string()))
+        );
+        assert_eq!(
+            ControlCommand::parse("force-review now"),
+            Err(ControlError::UnexpectedArgument("force-review"))
+        );
+    }
+
+    #[test]
+    fn test_parse_keybind() {
+        assert_eq!(
+            ControlCommand::parse_keybind("approve-plan", None),
+            Ok(ControlCommand::ApprovePlan)
+        );
+        assert_eq!(
+            ControlCommand::parse_keybind("reject-plan", Some("Too coarse")),
+            Ok(ControlCommand::RejectPlan(Some("Too coarse".to_string())))
+        );
+        assert_eq!(
+            ControlCommand::parse_keybind("resend", Some("overseer")),
+            Ok(ControlCommand::Resend(Some(PaneRole::Overseer)))
+        );
+        assert!(matches!(
+            ControlCommand::parse_keybind("toggle-floating", None),
+            Err(ControlError::UnknownCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_overrides_map_to_events() {
+        assert_eq!(
+            ControlCommand::ApprovePlan.event(),
+            Some(WorkflowEvent::ApprovePlan)
+        );
+        assert_eq!(
+            ControlCommand::RejectPlan(None).event(),
+            Some(WorkflowEvent::RejectPlan)
+        );
+        assert_eq!(
+            ControlCommand::ForceReview.event(),
+            Some(WorkflowEvent::ForceReview)
+        );
+        assert_eq!(
+            ControlCommand::ResetTask.event(),
+            Some(WorkflowEvent::Reset)
+        );
+        assert_eq!(ControlCommand::Abort.event(), Some(WorkflowEvent::Abort));
+        assert_eq!(ControlCommand::Status.event(), None);
+        assert_eq!(ControlCommand::Resend(None).event(), None);
+    }
+
+    #[test]
+    fn test_advance_event_picks_first_passing_candidate() {
+        let machine = WorkflowMachine::restore(WorkflowPhase::ReviewComplete, Vec::new());
+        let context = GuardContext {
+            review_has_blocking_findings: false,
+            ..ready_context()
+        };
+
+        assert_eq!(advance_event(&machine, &context), Ok(WorkflowEvent::Finish));
+
+        let context = GuardContext {
+            review_has_blocking_findings: true,
+            ..ready_context()
+        };
+        assert_eq!(
+            advance_event(&machine, &context),
+            Ok(WorkflowEvent::RequestFixes)
+        );
+    }
+
+    #[test]
+    fn test_advance_event_reports_first_rejection() {
+        let machine = WorkflowMachine::new();
+
+        let result = advance_event(&machine, &GuardContext::default());
+
+        assert!(matches!(
+            result,
+            Err(ControlError::Transition(TransitionError::GuardFailed {
+                event: WorkflowEvent::StartPlanning,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_advance_event_from_finished() {
+        let machine = WorkflowMachine::restore(WorkflowPhase::Finished, Vec::new());
+
+        assert_eq!(
+            advance_event(&machine, &ready_context()),
+            Err(ControlError::NothingToAdvance(WorkflowPhase::Finished))
+        );
+    }
+
+    #[test]
+    fn test_status_describes_machine() {
+        let machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        let status = WorkflowStatus::new(&TaskId::from(7), "Add login", &machine);
+
+        assert_eq!(status.phase, WorkflowPhase::PlanningInProgress);
+        assert_eq!(status.next_events, vec![WorkflowEvent::PlanReady]);
+        assert_eq!(
+            status.in_flight,
+            Some(ActiveAssistant {
+                role: PaneRole::Overseer,
+                prompt: PromptKind::Planning,
+            })
+        );
+        assert!(!status.paused);
+        assert_eq!(status.todo, None);
+    }
+
+    #[test]
+    fn test_todo_summary_from_progress() {
+        let summary = TodoSummary::from(TodoProgress {
+            completed: 1,
+            total: 4,
+        });
+
+        assert_eq!(summary.percentage, 25);
+    }
+
+    #[test]
+    fn test_success_reply_json() {
+        let machine = WorkflowMachine::new();
+        let status = WorkflowStatus {
+            paused: true,
+            ..WorkflowStatus::new(&TaskId::from(7), "Add login", &machine)
+        };
+
+        let reply = ControlReply::success("pause", Some("Paused".to_string()), status);
+        let value: serde_json::Value = serde_json::from_str(&reply.to_json()).unwrap();
+
+        assert_eq!(value["ok"], true);
+        assert_eq!(value["command"], "pause");
+        assert_eq!(value["message"], "Paused");
+        assert!(value.get("error").is_none());
+        assert_eq!(value["status"]["task_id"], "7");
+        assert_eq!(value["status"]["phase"], "Initializing");
+        assert_eq!(value["status"]["paused"], true);
+        assert_eq!(
+            value["status"]["next_events"],
+            serde_json::json!(["StartPlanning"])
+        );
+        assert!(value["status"]["in_flight"].is_null());
+        assert!(!reply.to_json().contains('\n'));
+    }
+
+    #[test]
+    fn test_failure_reply_json() {
+        let machine = WorkflowMachine::restore(WorkflowPhase::Finished, Vec::new());
+        let status = WorkflowStatus::new(&TaskId::from(7), "Add login", &machine);
+
+        let reply = ControlReply::failure(
+            "advance",
+            &ControlError::NothingToAdvance(WorkflowPhase::Finished),
+            status,
+        );
+        let value: serde_json::Value = serde_json::from_str(&reply.to_json()).unwrap();
+
+        assert_eq!(value["ok"], false);
+        assert_eq!(value["error"], "Nothing follows Finished");
+        assert!(value.get("message").is_none());
+        assert_eq!(value["status"]["phase"], "Finished");
+    }
+
+    #[test]
+    fn test_tasks_reply_lists_index() {
+        let machine = WorkflowMachine::new();
+        let status = WorkflowStatus::new(&TaskId::from(7), "Add login", &machine);
+        let entry = TaskEntry {
+            task_id: TaskId::parse("PROJ-1").unwrap(),
+            description: "Add login".to_string(),
+            phase: WorkflowPhase::PlanReady,
+            created_at: 1,
+            updated_at: 2,
+        };
+
+        let plain: serde_json::Value =
+            serde_json::from_str(&ControlReply::success("status", None, status.clone()).to_json())
+                .unwrap();
+        assert!(plain.get("tasks").is_none());
+
+        let reply = ControlReply::success("tasks", None, status).with_tasks(vec![entry]);
+        let value: serde_json::Value = serde_json::from_str(&reply.to_json()).unwrap();
+        assert_eq!(value["tasks"][0]["task_id"], "PROJ-1");
+        assert_eq!(value["tasks"][0]["phase"], "PlanReady");
+    }
+
+    #[test]
+    fn test_command_word() {
+        assert_eq!(command_word("  skip-to plan-ready"), "skip-to");
+        assert_eq!(command_word(""), "");
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            ControlError::UnknownCommand("restart".to_string()).to_string(),
+            "Unknown command: restart"
+        );
+        assert_eq!(
+            ControlError::Transition(TransitionError::AlreadyInPhase(WorkflowPhase::Finished))
+                .to_string(),
+            "The workflow is already in Finished"
+        );
+        assert_eq!(
+            ControlError::NothingToRetry(WorkflowPhase::PlanReady).to_string(),
+            "No assistant is working in PlanReady"
+        );
+        assert_eq!(
+            ControlError::UnknownTask(TaskId::parse("PROJ-9").unwrap()).to_string(),
+            "Task PROJ-9 is not in the task index; give a description to start it"
+        );
+        assert_eq!(
+            ControlError::TaskBusy(PaneRole::Commander).to_string(),
+            "The Commander assistant is still working; pause or abort the task first"
+        );
+        assert_eq!(
+            ControlError::NoTask(TaskIdError::Empty).to_string(),
+            "No task is open because the configured task_id is invalid (Task ID is empty); \
+             use switch-task"
+        );
+    }
+}
@@ -1,11 +1,18 @@
+use crate::pane_role::PaneRole;
+use crate::task_id::TaskId;
 use crate::workflow_phase::WorkflowPhase;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Messages exchanged between the coordinator and the panes
+///
+/// Encoded with a `type` field naming the variant; see `communication::protocol`
+/// for the older externally tagged form that is still accepted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum CoordinationMessage {
     // CLI initiates workflow with task details
     StartPlanning {
-        task_id: u32,
+        task_id: TaskId,
         task_description: String,
     },
 
@@ -33,9 +40,40 @@ pub enum CoordinationMessage {
         to: WorkflowPhase,
     },
 
+    // An assistant's pane exited while it had work in flight
+    AssistantFailed {
+        role: PaneRole,
+        phase: WorkflowPhase,
+        exit_status: Option<i32>,
+    },
+
+    // Receipt for an envelope that requested an acknowledgement
+    Ack {
+        message_id: u64,
+    },
+
     // File system events
     FileChanged {
         file_path: String,
         event_type: String,
     },
 }
+
+impl CoordinationMessage {
+    /// Name of the variant, as written in the `type` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            CoordinationMessage::StartPlanning { .. } => "StartPlanning",
+            CoordinationMessage::PlanReady { .. } => "PlanReady",
+            CoordinationMessage::StartImplementation => "StartImplementation",
+            CoordinationMessage::TaskCompleted { .. } => "TaskCompleted",
+            CoordinationMessage::AllTasksComplete => "AllTasksComplete",
+            CoordinationMessage::StartReview => "StartReview",
+            CoordinationMessage::ReviewComplete { .. } => "ReviewComplete",
+            CoordinationMessage::PhaseTransition { .. } => "PhaseTransition",
+            CoordinationMessage::AssistantFailed { .. } => "AssistantFailed",
+            CoordinationMessage::Ack { .. } => "Ack",
+            CoordinationMessage::FileChanged { .. } => "FileChanged",
+        }
+    }
+}
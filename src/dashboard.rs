@@ -0,0 +1,801 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use zellij_tile::prelude::*;
+
+use crate::communication::PaneHealth;
+use crate::pane_role::PaneRole;
+use crate::review::{FindingStatus, Review};
+use crate::task_id::TaskId;
+use crate::todo_list::{TodoItem, TodoList, TodoSection};
+use crate::workflow::PhaseChange;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Panes at least this tall show the dashboard instead of the status line
+pub const MIN_DASHBOARD_ROWS: usize = 10;
+
+/// Messages kept in the dashboard's log; older ones are dropped
+pub const MAX_LOG_ENTRIES: usize = 500;
+
+/// Rows taken by the title, tabs, summary and key help
+const CHROME_ROWS: usize = 4;
+
+/// A page of the dashboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    History,
+    Todo,
+    Review,
+    Panes,
+    Log,
+}
+
+impl Section {
+    /// Every section in tab order
+    pub const ALL: [Section; 5] = [
+        Section::History,
+        Section::Todo,
+        Section::Review,
+        Section::Panes,
+        Section::Log,
+    ];
+
+    /// Title shown on the section's tab
+    pub fn title(&self) -> &'static str {
+        match self {
+            Section::History => "History",
+            Section::Todo => "Todo",
+            Section::Review => "Review",
+            Section::Panes => "Panes",
+            Section::Log => "Log",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or(0)
+    }
+
+    fn next(&self) -> Section {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(&self) -> Section {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// A message shown in the dashboard's log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Unix timestamp when the message was seen
+    pub timestamp: u64,
+    pub text: String,
+}
+
+/// A registered role's pane and its liveness
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaneStatus {
+    pub role: PaneRole,
+    pub pane_id: Option<PaneId>,
+    pub health: Option<PaneHealth>,
+}
+
+/// Coordinator state the dashboard displays
+#[derive(Debug, Clone)]
+pub struct DashboardData<'a> {
+    pub task_id: &'a TaskId,
+    pub phase: WorkflowPhase,
+    pub paused: bool,
+    pub aborted: bool,
+    pub history: &'a [PhaseChange],
+    pub todo_list: Option<&'a TodoList>,
+    pub review: Option<&'a Review>,
+    /// One entry per role, registered or not
+    pub panes: Vec<PaneStatus>,
+}
+
+/// A line of the todo tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoLine {
+    pub indent: usize,
+    pub text: String,
+    /// `None` for section headings
+    pub completed: Option<bool>,
+}
+
+/// Content of the selected section, before it is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionBody {
+    Table {
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    Tree(Vec<TodoLine>),
+    Lines(Vec<String>),
+}
+
+impl SectionBody {
+    /// Number of scrollable rows
+    pub fn len(&self) -> usize {
+        match self {
+            SectionBody::Table { rows, .. } => rows.len(),
+            SectionBody::Tree(lines) => lines.len(),
+            SectionBody::Lines(lines) => lines.len(),
+        }
+    }
+
+    /// Check whether there is nothing to show
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Full-screen view of the workflow with keyboard navigation
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    section: Section,
+    /// First visible row of each section
+    offsets: HashMap<Section, usize>,
+    /// Keep the log scrolled to its newest message
+    follow_log: bool,
+    /// Body rows drawn last time, used as the page size
+    page_rows: usize,
+    log: VecDeque<LogEntry>,
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dashboard {
+    /// Create a dashboard showing the phase history
+    pub fn new() -> Self {
+        Self {
+            section: Section::History,
+            offsets: HashMap::new(),
+            follow_log: true,
+            page_rows: MIN_DASHBOARD_ROWS - CHROME_ROWS,
+            log: VecDeque::new(),
+        }
+    }
+
+    /// The section currently shown
+    pub fn section(&self) -> Section {
+        self.section
+    }
+
+    /// Messages in the log, oldest first
+    pub fn log(&self) -> &VecDeque<LogEntry> {
+        &self.log
+    }
+
+    /// Append a message to the log, dropping the oldest beyond `MAX_LOG_ENTRIES`
+    pub fn record(&mut self, timestamp: u64, text: &str) {
+        self.log.push_back(LogEntry {
+            timestamp,
+            text: text.to_string(),
+        });
+        while self.log.len() > MAX_LOG_ENTRIES {
+            self.log.pop_front();
+        }
+    }
+
+    /// Apply a key press; returns whether the dashboard changed
+    ///
+    /// Tab, ←/→ (or h/l) and 1-5 switch sections; ↑/↓ (or k/j), PgUp/PgDn and
+    /// Home/End (or g/G) scroll.
+    pub fn handle_key(&mut self, key: &KeyWithModifier) -> bool {
+        if key.is_key_with_shift_modifier(BareKey::Tab) {
+            self.section = self.section.previous();
+            return true;
+        }
+        if !key.has_no_modifiers() {
+            return false;
+        }
+
+        let offset = self.offsets.get(&self.section).copied().unwrap_or(0);
+        let page = self.page_rows.max(1);
+        let new_offset = match key.bare_key {
+            BareKey::Tab | BareKey::Right | BareKey::Char('l') => {
+                self.section = self.section.next();
+                return true;
+            }
+            BareKey::Left | BareKey::Char('h') => {
+                self.section = self.section.previous();
+                return true;
+            }
+            BareKey::Char(c @ '1'..='5') => {
+                self.section = Section::ALL[c as usize - '1' as usize];
+                return true;
+            }
+            BareKey::Up | BareKey::Char('k') => offset.saturating_sub(1),
+            BareKey::Down | BareKey::Char('j') => offset.saturating_add(1),
+            BareKey::PageUp => offset.saturating_sub(page),
+            BareKey::PageDown => offset.saturating_add(page),
+            BareKey::Home | BareKey::Char('g') => 0,
+            BareKey::End | BareKey::Char('G') => usize::MAX,
+            _ => return false,
+        };
+
+        if self.section == Section::Log {
+            // Scrolling up stops following new messages until End is pressed
+            self.follow_log = new_offset == usize::MAX;
+        }
+        self.offsets.insert(self.section, new_offset);
+        true
+    }
+
+    /// Rows of a section to draw, clamping its scroll offset to the content
+    pub fn visible(&mut self, section: Section, len: usize, height: usize) -> Range<usize> {
+        let last_start = len.saturating_sub(height);
+        let offset = if section == Section::Log && self.follow_log {
+            last_start
+        } else {
+            self.offsets
+                .get(&section)
+                .copied()
+                .unwrap_or(0)
+                .min(last_start)
+        };
+
+        self.offsets.insert(section, offset);
+        offset..(offset + height).min(len)
+    }
+
+    /// Build the content of a section
+    pub fn body(&self, section: Section, data: &DashboardData) -> SectionBody {
+        match section {
+            Section::History => SectionBody::Table {
+                header: strings(&["Time (UTC)", "From", "To", "Event"]),
+                rows: history_rows(data.history),
+            },
+            Section::Todo => SectionBody::Tree(data.todo_list.map(todo_lines).unwrap_or_default()),
+            Section::Review => SectionBody::Table {
+                header: strings(&["Severity", "Status", "Location", "Finding"]),
+                rows: data.review.map(finding_rows).unwrap_or_default(),
+            },
+            Section::Panes => SectionBody::Table {
+                header: strings(&["Role", "Pane", "Health"]),
+                rows: pane_rows(&data.panes),
+            },
+            Section::Log => SectionBody::Lines(
+                self.log
+                    .iter()
+                    .map(|entry| format!("{}  {}", format_timestamp(entry.timestamp), entry.text))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// One-line summary shown above a section's content
+    pub fn summary(&self, section: Section, data: &DashboardData) -> String {
+        match section {
+            Section::History => format!("{} phase changes", data.history.len()),
+            Section::Todo => match data.todo_list {
+                Some(todo_list) => {
+                    let progress = todo_list.progress();
+                    format!(
+                        "Progress {}/{} ({}%)",
+                        progress.completed,
+                        progress.total,
+                        progress.percentage()
+                    )
+                }
+                None => "No todo list yet".to_string(),
+            },
+            Section::Review => match data.review {
+                Some(review) => format!(
+                    "{} open findings, {} blocking",
+                    finding_rows(review).len(),
+                    review.blocking_findings().len()
+                ),
+                None => "No review yet".to_string(),
+            },
+            Section::Panes => {
+                let alive = data
+                    .panes
+                    .iter()
+                    .filter(|pane| pane.health.is_some_and(|health| health.is_alive()))
+                    .count();
+                let registered = data.panes.iter().filter(|p| p.pane_id.is_some()).count();
+                format!("{} of {} registered panes running", alive, registered)
+            }
+            Section::Log => format!("{} messages", self.log.len()),
+        }
+    }
+
+    /// Draw the dashboard over the whole plugin pane
+    pub fn render(&mut self, data: &DashboardData, rows: usize, cols: usize) {
+        let mut title = format!("ZZZ · task {} · {:?}", data.task_id, data.phase);
+        if data.paused {
+            title.push_str(" · paused");
+        }
+        if data.aborted {
+            title.push_str(" · aborted");
+        }
+        print_text_with_coordinates(
+            Text::new(&title).color_range(0, 0..3),
+            0,
+            0,
+            Some(cols),
+            None,
+        );
+
+        let (tabs, selected) = tab_line(self.section);
+        print_text_with_coordinates(
+            Text::new(&tabs).color_range(2, selected),
+            0,
+            1,
+            Some(cols),
+            None,
+        );
+
+        let section = self.section;
+        let body = self.body(section, data);
+        let height = rows.saturating_sub(CHROME_ROWS);
+        // Tables spend one row on their header
+        let content_rows = match body {
+            SectionBody::Table { .. } => height.saturating_sub(1),
+            _ => height,
+        };
+        self.page_rows = content_rows;
+        let visible = self.visible(section, body.len(), content_rows);
+
+        let summary = format!(
+            "{}  [{}-{}/{}]",
+            self.summary(section, data),
+            if body.is_empty() {
+                0
+            } else {
+                visible.start + 1
+            },
+            visible.end,
+            body.len()
+        );
+        print_text_with_coordinates(Text::new(&summary), 0, 2, Some(cols), None);
+
+        match body {
+            SectionBody::Table { header, rows } => {
+                let table = rows[visible]
+                    .iter()
+                    .fold(Table::new().add_row(header), |table, row| {
+                        table.add_row(row.clone())
+                    });
+                print_table_with_coordinates(table, 0, 3, Some(cols), Some(height));
+            }
+            SectionBody::Tree(lines) => {
+                let items = lines[visible]
+                    .iter()
+                    .map(|line| {
+                        let item = NestedListItem::new(&line.text).indent(line.indent);
+                        match line.completed {
+                            Some(true) => item.color_range(2, ..),
+                            None => item.color_range(0, ..),
+                            Some(false) => item,
+                        }
+                    })
+                    .collect();
+                print_nested_list_with_coordinates(items, 0, 3, Some(cols), Some(height));
+            }
+            SectionBody::Lines(lines) => {
+                for (y, line) in lines[visible].iter().enumerate() {
+                    print_text_with_coordinates(Text::new(line), 0, 3 + y, Some(cols), None);
+                }
+            }
+        }
+
+        print_text_with_coordinates(
+            Text::new("Tab/←→ section · ↑↓ PgUp/PgDn scroll · Home/End · 1-5 jump")
+                .color_range(3, ..),
+            0,
+            rows.saturating_sub(1),
+            Some(cols),
+            None,
+        );
+    }
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+/// The tab bar and the character range of the selected tab
+fn tab_line(selected: Section) -> (String, Range<usize>) {
+    let mut line = String::new();
+    let mut range = 0..0;
+    for (i, section) in Section::ALL.iter().enumerate() {
+        let tab = format!(" {} {} ", i + 1, section.title());
+        if *section == selected {
+            let start = line.chars().count();
+            range = start..start + tab.chars().count();
+        }
+        line.push_str(&tab);
+    }
+    (line, range)
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM:SS` in UTC
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Table rows for the phase history, oldest first
+pub fn history_rows(history: &[PhaseChange]) -> Vec<Vec<String>> {
+    history
+        .iter()
+        .map(|change| {
+            vec![
+                format_timestamp(change.timestamp),
+                format!("{:?}", change.from),
+                format!("{:?}", change.to),
+                format!("{:?}", change.event),
+            ]
+        })
+        .collect()
+}
+
+/// Flatten the todo list into indented headings and items
+pub fn todo_lines(todo_list: &TodoList) -> Vec<TodoLine> {
+    let mut lines = Vec::new();
+    push_items(&todo_list.items, 0, &mut lines);
+    for section in &todo_list.sections {
+        push_section(section, 0, &mut lines);
+    }
+    lines
+}
+
+fn push_section(section: &TodoSection, indent: usize, lines: &mut Vec<TodoLine>) {
+    lines.push(TodoLine {
+        indent,
+        text: section.title.clone(),
+        completed: None,
+    });
+    push_items(&section.items, indent + 1, lines);
+    for subsection in &section.subsections {
+        push_section(subsection, indent + 1, lines);
+    }
+}
+
+fn push_items(items: &[TodoItem], indent: usize, lines: &mut Vec<TodoLine>) {
+    for item in items {
+        let checkbox = if item.is_completed() { "[x]" } else { "[ ]" };
+        let text = match &item.number {
+            Some(number) => format!("{} {} {}", checkbox, number, item.text),
+            None => format!("{} {}", checkbox, item.text),
+        };
+        lines.push(TodoLine {
+            indent,
+            text,
+            completed: Some(item.is_completed()),
+        });
+        push_items(&item.children, indent + 1, lines);
+    }
+}
+
+/// Table rows for the review findings that have not been rejected
+pub fn finding_rows(review: &Review) -> Vec<Vec<String>> {
+    review
+        .findings
+        .iter()
+        .filter(|finding| finding.status != FindingStatus::Rejected)
+        .map(|finding| {
+            vec![
+                finding.severity.as_str().to_string(),
+                format!("{:?}", finding.status).to_lowercase(),
+                finding
+                    .location
+                    .as_ref()
+                    .map(|location| location.to_string())
+                    .unwrap_or_default(),
+                finding.description.replace('\n', " "),
+            ]
+        })
+        .collect()
+}
+
+/// Table rows describing each role's pane
+pub fn pane_rows(panes: &[PaneStatus]) -> Vec<Vec<String>> {
+    panes
+        .iter()
+        .map(|pane| {
+            let pane_id = match pane.pane_id {
+                Some(PaneId::Terminal(id)) => format!("terminal {}", id),
+                Some(PaneId::Plugin(id)) => format!("plugin {}", id),
+                None => "-".to_string(),
+            };
+            let health = match (pane.pane_id, pane.health) {
+                (None, _) => "not registered".to_string(),
+                (Some(_), None) | (Some(_), Some(PaneHealth::Running)) => "running".to_string(),
+                (Some(_), Some(PaneHealth::Exited { exit_status, held })) => {
+                    let status = exit_status
+                        .map(|code| format!("exited ({})", code))
+                        .unwrap_or_else(|| "exited".to_string());
+                    if held {
+                        format!("{}, held", status)
+                    } else {
+                        status
+                    }
+                }
+            };
+            vec![pane.role.display_name().to_string(), pane_id, health]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::WorkflowEvent;
+
+    fn key(bare_key: BareKey) -> KeyWithModifier {
+        KeyWithModifier::new(bare_key)
+    }
+
+    fn data<'a>(task_id: &'a TaskId, history: &'a [PhaseChange]) -> DashboardData<'a> {
+        DashboardData {
+            task_id,
+            phase: WorkflowPhase::PlanningInProgress,
+            paused: false,
+            aborted: false,
+            history,
+            todo_list: None,
+            review: None,
+            panes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+        // Leap day
+        assert_eq!(format_timestamp(951_825_600), "2000-02-29 12:00:00");
+    }
+
+    #[test]
+    fn test_section_navigation_wraps() {
+        let mut dashboard = Dashboard::new();
+        assert_eq!(dashboard.section(), Section::History);
+
+        assert!(dashboard.handle_key(&key(BareKey::Left)));
+        assert_eq!(dashboard.section(), Section::Log);
+        assert!(dashboard.handle_key(&key(BareKey::Tab)));
+        assert_eq!(dashboard.section(), Section::History);
+        assert!(dashboard.handle_key(&key(BareKey::Char('3'))));
+        assert_eq!(dashboard.section(), Section::Review);
+        assert!(dashboard.handle_key(&key(BareKey::Tab).with_shift_modifier()));
+        assert_eq!(dashboard.section(), Section::Todo);
+    }
+
+    #[test]
+    fn test_unbound_keys_are_ignored() {
+        let mut dashboard = Dashboard::new();
+
+        assert!(!dashboard.handle_key(&key(BareKey::Char('x'))));
+        assert!(!dashboard.handle_key(&key(BareKey::Char('j')).with_ctrl_modifier()));
+        assert_eq!(dashboard.section(), Section::History);
+    }
+
+    #[test]
+    fn test_scrolling_is_clamped_to_content() {
+        let mut dashboard = Dashboard::new();
+        for _ in 0..3 {
+            dashboard.handle_key(&key(BareKey::Down));
+        }
+        assert_eq!(dashboard.visible(Section::History, 10, 4), 3..7);
+
+        // Past the end the last page stays visible
+        dashboard.handle_key(&key(BareKey::End));
+        assert_eq!(dashboard.visible(Section::History, 10, 4), 6..10);
+        dashboard.handle_key(&key(BareKey::Up));
+        assert_eq!(dashboard.visible(Section::History, 10, 4), 5..9);
+
+        dashboard.handle_key(&key(BareKey::Home));
+        assert_eq!(dashboard.visible(Section::History, 10, 4), 0..4);
+        // Short content is shown whole
+        assert_eq!(dashboard.visible(Section::History, 2, 4), 0..2);
+    }
+
+    #[test]
+    fn test_page_keys_use_last_drawn_height() {
+        let mut dashboard = Dashboard::new();
+        dashboard.page_rows = 5;
+
+        dashboard.handle_key(&key(BareKey::PageDown));
+        assert_eq!(dashboard.visible(Section::History, 20, 5), 5..10);
+        dashboard.handle_key(&key(BareKey::PageUp));
+        assert_eq!(dashboard.visible(Section::History, 20, 5), 0..5);
+    }
+
+    #[test]
+    fn test_log_follows_newest_until_scrolled_up() {
+        let mut dashboard = Dashboard::new();
+        dashboard.handle_key(&key(BareKey::Char('5')));
+        for i in 0..10 {
+            dashboard.record(i, &format!("message {}", i));
+        }
+        assert_eq!(dashboard.visible(Section::Log, 10, 4), 6..10);
+
+        dashboard.handle_key(&key(BareKey::Up));
+        dashboard.record(10, "message 10");
+        assert_eq!(dashboard.visible(Section::Log, 11, 4), 5..9);
+
+        dashboard.handle_key(&key(BareKey::End));
+        assert_eq!(dashboard.visible(Section::Log, 11, 4), 7..11);
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let mut dashboard = Dashboard::new();
+        for i in 0..(MAX_LOG_ENTRIES as u64 + 5) {
+            dashboard.record(i, "message");
+        }
+
+        assert_eq!(dashboard.log().len(), MAX_LOG_ENTRIES);
+        assert_eq!(dashboard.log()[0].timestamp, 5);
+    }
+
+    #[test]
+    fn test_history_rows() {
+        let history = vec![PhaseChange {
+            from: WorkflowPhase::Initializing,
+            to: WorkflowPhase::PlanningInProgress,
+            event: WorkflowEvent::StartPlanning,
+            timestamp: 1_700_000_000,
+        }];
+
+        assert_eq!(
+            history_rows(&history),
+            vec![vec![
+                "2023-11-14 22:13:20".to_string(),
+                "Initializing".to_string(),
+                "PlanningInProgress".to_string(),
+                "StartPlanning".to_string(),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_todo_lines_follow_tree() {
+        let todo_list = TodoList::parse(
+            "- [x] Loose item\n\
+             # Backend\n\
+             - [ ] **1.1** Add model\n\
+             \x20 - [x] Write migration\n\
+             ## Tests\n\
+             - [ ] Cover model\n",
+        );
+
+        let lines = todo_lines(&todo_list);
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0].text, "[x] Loose item");
+        assert_eq!(lines[1].text, "Backend");
+        assert_eq!(lines[1].completed, None);
+        assert_eq!(lines[2].text, "[ ] 1.1 Add model");
+        assert_eq!(lines[2].indent, 1);
+        assert_eq!(lines[3].text, "[x] Write migration");
+        assert_eq!(lines[3].indent, 2);
+        assert_eq!(lines[4].text, "Tests");
+        assert_eq!(lines[4].indent, 1);
+        assert_eq!(lines[5].indent, 2);
+    }
+
+    #[test]
+    fn test_finding_rows_skip_rejected() {
+        let review = Review::parse(
+            "- **major** `src/lib.rs:10` Missing error handling\n\
+             - **minor** Typo in docs (rejected)\n\
+             - **info** Consider renaming\n",
+        );
+
+        let rows = finding_rows(&review);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], "major");
+        assert_eq!(rows[0][1], "open");
+        assert_eq!(rows[0][2], "src/lib.rs:10");
+        assert_eq!(rows[1][0], "info");
+        assert_eq!(rows[1][2], "");
+    }
+
+    #[test]
+    fn test_pane_rows_describe_health() {
+        let panes = vec![
+            PaneStatus {
+                role: PaneRole::Overseer,
+                pane_id: Some(PaneId::Terminal(1)),
+                health: Some(PaneHealth::Running),
+            },
+            PaneStatus {
+                role: PaneRole::Commander,
+                pane_id: Some(PaneId::Terminal(2)),
+                health: Some(PaneHealth::Exited {
+                    exit_status: Some(1),
+                    held: true,
+                }),
+            },
+            PaneStatus {
+                role: PaneRole::Review,
+                pane_id: None,
+                health: None,
+            },
+        ];
+
+        assert_eq!(
+            pane_rows(&panes),
+            vec![
+                strings(&["Overseer", "terminal 1", "running"]),
+                strings(&["Commander", "terminal 2", "exited (1), held"]),
+                strings(&["Review", "-", "not registered"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_body_and_summary_per_section() {
+        let dashboard = Dashboard::new();
+        let todo_list = TodoList::parse("- [x] One\n- [ ] Two\n");
+        let task_id = TaskId::from(7);
+        let data = DashboardData {
+            todo_list: Some(&todo_list),
+            ..data(&task_id, &[])
+        };
+
+        assert_eq!(dashboard.body(Section::Todo, &data).len(), 2);
+        assert_eq!(
+            dashboard.summary(Section::Todo, &data),
+            "Progress 1/2 (50%)"
+        );
+        assert_eq!(dashboard.summary(Section::Review, &data), "No review yet");
+        assert!(dashboard.body(Section::History, &data).is_empty());
+        assert!(matches!(
+            dashboard.body(Section::Panes, &data),
+            SectionBody::Table { ref header, .. } if header.len() == 3
+        ));
+    }
+
+    #[test]
+    fn test_log_body_has_timestamps() {
+        let mut dashboard = Dashboard::new();
+        dashboard.record(0, "Broadcast from CLI: StartReview");
+
+        assert_eq!(
+            dashboard.body(Section::Log, &data(&TaskId::from(7), &[])),
+            SectionBody::Lines(vec![
+                "1970-01-01 00:00:00  Broadcast from CLI: StartReview".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tab_line_highlights_selected() {
+        let (line, range) = tab_line(Section::Todo);
+
+        assert_eq!(line, " 1 History  2 Todo  3 Review  4 Panes  5 Log ");
+        let selected: String = line.chars().skip(range.start).take(range.len()).collect();
+        assert_eq!(selected, " 2 Todo ");
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+use crate::logging::{LogRecord, Logger};
+use crate::task_id::TaskId;
+
 /// Custom error types for file operations
 #[derive(Debug)]
 pub enum FileSystemError {
@@ -10,6 +14,8 @@ pub enum FileSystemError {
     Timeout,
     PermissionDenied,
     ConcurrentAccess,
+    /// Files readable only by their owner cannot be created on this platform
+    PrivateFilesUnsupported,
 }
 
 impl From<io::Error> for FileSystemError {
@@ -23,9 +29,39 @@ impl From<io::Error> for FileSystemError {
 }
 
 /// Handles file system operations for the ZZZ plugin
-pub struct FileSystem;
+///
+/// Task files live under a root directory, `.zzz` in the plugin's working
+/// directory unless `base_directory` says otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    /// Directory holding the task directories, as the plugin opens it
+    root: PathBuf,
+    /// The same directory as the assistant panes see it
+    pane_root: PathBuf,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::from_configuration(&BTreeMap::new(), Path::new(Self::HOST_DIRECTORY))
+    }
+}
 
 impl FileSystem {
+    /// Where Zellij mounts the plugin's working directory (the pane cwd)
+    pub const HOST_DIRECTORY: &'static str = "/host";
+
+    /// Root directory used when `base_directory` is not configured
+    pub const DEFAULT_BASE_DIRECTORY: &'static str = ".zzz";
+
+    /// File name of the todo list written by the planner
+    pub const TODO_LIST_FILE: &'static str = "todo-list.md";
+
+    /// File name of the review written by the reviewer
+    pub const REVIEW_FILE: &'static str = "review.md";
+
+    /// File name of the operator's feedback on a rejected plan
+    pub const PLAN_FEEDBACK_FILE: &'static str = "plan-feedback.md";
+
     /// Maximum number of retry attempts for file operations
     const MAX_RETRIES: u32 = 3;
 
@@ -35,27 +71,118 @@ impl FileSystem {
     /// Timeout for file operations
     const OPERATION_TIMEOUT: Duration = Duration::from_secs(5);
 
+    /// Create a file system rooted at `root`, which the panes see at the same path
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        let root = root.into();
+        Self {
+            pane_root: root.clone(),
+            root,
+        }
+    }
+
+    /// Read `base_directory` from the plugin configuration
+    ///
+    /// A relative directory is resolved against `working_directory`, which is
+    /// where the plugin sees the panes' working directory; the panes keep
+    /// seeing it relative to their own.
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+        working_directory: &Path,
+    ) -> Self {
+        let base_directory = configuration
+            .get("base_directory")
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .unwrap_or(Self::DEFAULT_BASE_DIRECTORY);
+
+        Self {
+            root: working_directory.join(base_directory),
+            pane_root: PathBuf::from(base_directory),
+        }
+    }
+
+    /// Directory holding the task directories
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Task directory as the assistant panes see it
+    pub fn pane_task_directory_path(&self, task_id: &TaskId) -> PathBuf {
+        self.pane_root.join(task_id.directory_name())
+    }
+
     /// Atomically writes content to a file using temporary file + rename pattern
     pub fn write_file_atomic<P: AsRef<Path>>(
         path: P,
         content: &str,
     ) -> Result<(), FileSystemError> {
         let path = path.as_ref();
-        let temp_path = path.with_extension("tmp");
+        Self::write_file_atomic_via(path, &path.with_extension("tmp"), content)
+    }
+
+    /// Atomically writes content to a file through the given temporary file
+    ///
+    /// Writers that share a target but not a temporary file never see each
+    /// other's partial writes; the last rename wins.
+    pub fn write_file_atomic_via<P: AsRef<Path>>(
+        path: P,
+        temp_path: &Path,
+        content: &str,
+    ) -> Result<(), FileSystemError> {
+        let path = path.as_ref();
 
         Self::with_retry(|| {
             // Write to temporary file first
-            let mut temp_file = fs::File::create(&temp_path)?;
+            let mut temp_file = fs::File::create(temp_path)?;
             temp_file.write_all(content.as_bytes())?;
             temp_file.sync_all()?;
             drop(temp_file);
 
             // Atomically rename to final location
+            fs::rename(temp_path, path)?;
+            Ok(())
+        })
+    }
+
+    /// Atomically writes content that only the owner may read (mode 0600)
+    #[cfg(unix)]
+    pub fn write_private_file_atomic<P: AsRef<Path>>(
+        path: P,
+        content: &str,
+    ) -> Result<(), FileSystemError> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let path = path.as_ref();
+        let temp_path = path.with_extension("tmp");
+
+        Self::with_retry(|| {
+            let _ = fs::remove_file(&temp_path);
+            let mut temp_file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .open(&temp_path)?;
+            temp_file.write_all(content.as_bytes())?;
+            temp_file.sync_all()?;
+            drop(temp_file);
+
             fs::rename(&temp_path, path)?;
             Ok(())
         })
     }
 
+    /// Refuses to write owner-only content where file modes cannot be set
+    ///
+    /// Under WASI a new file gets the host's umask, usually world-readable, so
+    /// nothing is written rather than exposing the content.
+    #[cfg(not(unix))]
+    pub fn write_private_file_atomic<P: AsRef<Path>>(
+        _path: P,
+        _content: &str,
+    ) -> Result<(), FileSystemError> {
+        Err(FileSystemError::PrivateFilesUnsupported)
+    }
+
     /// Safely reads file content with retry logic for concurrent access
     pub fn read_file_safe<P: AsRef<Path>>(path: P) -> Result<String, FileSystemError> {
         let path = path.as_ref();
@@ -130,124 +257,189 @@ impl FileSystem {
             }
         }
     }
+
     /// Creates the directory structure for a given task ID
     /// Creates .zzz/task-{task_id}/ directory structure
-    pub fn create_task_directory(task_id: u32) -> Result<PathBuf, std::io::Error> {
-        let task_dir = Self::get_task_directory_path(task_id);
+    pub fn create_task_directory(&self, task_id: &TaskId) -> Result<PathBuf, std::io::Error> {
+        let task_dir = self.get_task_directory_path(task_id);
         fs::create_dir_all(&task_dir)?;
         Ok(task_dir)
     }
 
     /// Gets the path to the task directory for the given task_id
-    pub fn get_task_directory_path(task_id: u32) -> PathBuf {
-        PathBuf::from("/host/.zzz").join(format!("task-{}", task_id))
+    pub fn get_task_directory_path(&self, task_id: &TaskId) -> PathBuf {
+        self.root.join(task_id.directory_name())
     }
 
     /// Creates the main .zzz directory if it doesn't exist
-    pub fn create_zzz_directory() -> Result<(), std::io::Error> {
-        fs::create_dir_all("/host/.zzz")
+    pub fn create_zzz_directory(&self) -> Result<(), std::io::Error> {
+        fs::create_dir_all(&self.root)
+    }
+
+    /// Move a numeric task's directory from the name it had before task IDs
+    /// were strings
+    ///
+    /// IDs used to be parsed as numbers, so `task_id "007"` was kept in
+    /// `task-7`. Returns whether a directory was moved.
+    ///
+    /// Only numeric IDs are covered. A non-numeric ID such as `PROJ-1234` used
+    /// to fall back to `task-0`, which may hold several tasks' files, so that
+    /// directory is never moved.
+    pub fn migrate_numeric_task_directory(&self, task_id: &TaskId) -> Result<bool, std::io::Error> {
+        let legacy_dir = match task_id.as_number() {
+            Some(n) => self.get_task_directory_path(&TaskId::from(n)),
+            None => return Ok(false),
+        };
+        let task_dir = self.get_task_directory_path(task_id);
+        if legacy_dir == task_dir || task_dir.exists() || !legacy_dir.is_dir() {
+            return Ok(false);
+        }
+
+        fs::rename(&legacy_dir, &task_dir)?;
+        Ok(true)
     }
 
     /// Sets up the complete directory structure for the given task
-    pub fn setup_task_directories(task_id: u32) -> Result<PathBuf, std::io::Error> {
+    pub fn setup_task_directories(&self, task_id: &TaskId) -> Result<PathBuf, std::io::Error> {
         // First ensure .zzz directory exists
-        Self::create_zzz_directory()?;
+        self.create_zzz_directory()?;
 
         // Then create the specific task directory
-        let task_dir = Self::create_task_directory(task_id)?;
+        let task_dir = self.create_task_directory(task_id)?;
 
         // Create logs subdirectory
-        let logs_dir = Self::get_logs_dir_path(task_id);
+        let logs_dir = self.get_logs_dir_path(task_id);
         fs::create_dir_all(&logs_dir)?;
 
         Ok(task_dir)
     }
 
     /// Gets the path to the todo-list.md file for the given task_id
-    pub fn get_todo_list_path(task_id: u32) -> PathBuf {
-        Self::get_task_directory_path(task_id).join("todo-list.md")
+    pub fn get_todo_list_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(Self::TODO_LIST_FILE)
     }
 
     /// Gets the path to the review.md file for the given task_id
-    pub fn get_review_path(task_id: u32) -> PathBuf {
-        Self::get_task_directory_path(task_id).join("review.md")
+    pub fn get_review_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(Self::REVIEW_FILE)
+    }
+
+    /// Gets the path a finished review round is archived to for the given task_id
+    pub fn get_review_archive_path(&self, task_id: &TaskId, round: u32) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(format!("review-round-{}.md", round))
+    }
+
+    /// Gets the path to the operator's feedback on a rejected plan
+    pub fn get_plan_feedback_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(Self::PLAN_FEEDBACK_FILE)
+    }
+
+    /// Gets the path the `n`th rejected todo list is archived to
+    pub fn get_rejected_plan_path(&self, task_id: &TaskId, n: u32) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(format!("todo-list-rejected-{}.md", n))
+    }
+
+    /// Gets the directory the task files are moved to by the `n`th reset
+    pub fn get_reset_archive_dir(&self, task_id: &TaskId, n: u32) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(format!("reset-{}", n))
     }
 
     /// Gets the path to the plan.md file for the given task_id
-    pub fn get_plan_path(task_id: u32) -> PathBuf {
-        Self::get_task_directory_path(task_id).join("plan.md")
+    pub fn get_plan_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id).join("plan.md")
+    }
+
+    /// Gets the path to the persisted coordinator state for the given task_id
+    pub fn get_state_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id).join("state.json")
+    }
+
+    /// Gets the path to the index of every task under the root
+    pub fn get_index_path(&self) -> PathBuf {
+        self.root.join("index.json")
+    }
+
+    /// Gets the path to the task's own entry in the task registry
+    pub fn get_task_entry_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id).join("entry.json")
+    }
+
+    /// Gets the path to the env file holding a role's assistant secrets
+    pub fn get_secret_env_path(&self, task_id: &TaskId, role_key: &str) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(format!("{}.env", role_key))
+    }
+
+    /// Gets the path to a role's `n`th prompt file
+    pub fn get_prompt_path(&self, task_id: &TaskId, role_key: &str, n: u32) -> PathBuf {
+        self.get_task_directory_path(task_id)
+            .join(format!("prompt-{}-{}.md", role_key, n))
     }
 
     /// Gets the path to the logs directory for the given task_id
-    pub fn get_logs_dir_path(task_id: u32) -> PathBuf {
-        Self::get_task_directory_path(task_id).join("logs")
+    pub fn get_logs_dir_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_task_directory_path(task_id).join("logs")
     }
 
     /// Gets the path to the overseer.log file for the given task_id
-    pub fn get_overseer_log_path(task_id: u32) -> PathBuf {
-        Self::get_logs_dir_path(task_id).join("overseer.log")
+    pub fn get_overseer_log_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_logs_dir_path(task_id).join("overseer.log")
     }
 
     /// Gets the path to the commander.log file for the given task_id
-    pub fn get_commander_log_path(task_id: u32) -> PathBuf {
-        Self::get_logs_dir_path(task_id).join("commander.log")
+    pub fn get_commander_log_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_logs_dir_path(task_id).join("commander.log")
     }
 
     /// Gets the path to the coordinator.log file for the given task_id
-    pub fn get_coordinator_log_path(task_id: u32) -> PathBuf {
-        Self::get_logs_dir_path(task_id).join("coordinator.log")
-    }
-
-    /// Writes a timestamped log entry to the overseer log
-    pub fn log_overseer(task_id: u32, message: &str) -> Result<(), FileSystemError> {
-        let log_path = Self::get_overseer_log_path(task_id);
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let entry = format!("[{}] {}\n", timestamp, message);
-        Self::append_to_file(log_path, &entry)
-    }
-
-    /// Writes a timestamped log entry to the commander log
-    pub fn log_commander(task_id: u32, message: &str) -> Result<(), FileSystemError> {
-        let log_path = Self::get_commander_log_path(task_id);
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let entry = format!("[{}] {}\n", timestamp, message);
-        Self::append_to_file(log_path, &entry)
-    }
-
-    /// Writes a timestamped log entry to the coordinator log
-    pub fn log_coordinator(task_id: u32, message: &str) -> Result<(), FileSystemError> {
-        let log_path = Self::get_coordinator_log_path(task_id);
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let entry = format!("[{}] {}\n", timestamp, message);
-        Self::append_to_file(log_path, &entry)
-    }
-
-    /// Generic logging function that can write to any log file
-    pub fn log_to_file<P: AsRef<Path>>(path: P, message: &str) -> Result<(), FileSystemError> {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let entry = format!("[{}] {}\n", timestamp, message);
-        Self::append_to_file(path, &entry)
+    pub fn get_coordinator_log_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_logs_dir_path(task_id).join("coordinator.log")
+    }
+
+    /// Gets the path to the log of coordination messages that were never acknowledged
+    pub fn get_dead_letter_log_path(&self, task_id: &TaskId) -> PathBuf {
+        self.get_logs_dir_path(task_id).join("dead-letters.log")
+    }
+
+    /// Writes a record to the coordinator log
+    pub fn log_coordinator(
+        &self,
+        task_id: &TaskId,
+        logger: &Logger,
+        record: LogRecord,
+    ) -> Result<(), FileSystemError> {
+        logger.write(
+            self.get_coordinator_log_path(task_id),
+            &record.task(task_id),
+        )
+    }
+
+    /// Writes a record to the task's dead-letter log
+    pub fn log_dead_letter(
+        &self,
+        task_id: &TaskId,
+        logger: &Logger,
+        record: LogRecord,
+    ) -> Result<(), FileSystemError> {
+        logger.write(
+            self.get_dead_letter_log_path(task_id),
+            &record.task(task_id),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::logging::LogComponent;
     use std::fs;
     use std::io::ErrorKind;
-    use std::thread;
     use std::time::Duration;
     use tempfile::{tempdir, TempDir};
 
@@ -255,6 +447,10 @@ mod tests {
         tempdir().expect("Failed to create temporary directory")
     }
 
+    fn host_file_system() -> FileSystem {
+        FileSystem::default()
+    }
+
     #[test]
     fn test_filesystem_error_from_io_error() {
         let permission_error =
@@ -303,6 +499,37 @@ mod tests {
         assert!(!temp_path.exists());
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_write_private_file_atomic() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = create_test_dir();
+        let file_path = temp_dir.path().join("overseer.env");
+
+        FileSystem::write_private_file_atomic(&file_path, "export A='1'\n").unwrap();
+        FileSystem::write_private_file_atomic(&file_path, "export A='2'\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "export A='2'\n");
+        assert!(!file_path.with_extension("tmp").exists());
+        let mode = fs::metadata(&file_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    #[cfg(not(unix))]
+    fn test_write_private_file_atomic_refuses_without_modes() {
+        let temp_dir = create_test_dir();
+        let file_path = temp_dir.path().join("overseer.env");
+
+        let result = FileSystem::write_private_file_atomic(&file_path, "export A='1'\n");
+        assert!(matches!(
+            result,
+            Err(FileSystemError::PrivateFilesUnsupported)
+        ));
+        assert!(!file_path.exists());
+    }
+
     #[test]
     fn test_write_file_atomic_creates_parent_dir() {
         let temp_dir = create_test_dir();
@@ -459,79 +686,145 @@ mod tests {
 
     #[test]
     fn test_get_task_directory_path() {
-        let task_id = 42;
+        let task_id = TaskId::from(42);
         let expected_path = PathBuf::from("/host/.zzz/task-42");
-        let actual_path = FileSystem::get_task_directory_path(task_id);
+        let actual_path = host_file_system().get_task_directory_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_get_todo_list_path() {
-        let task_id = 123;
+        let task_id = TaskId::from(123);
         let expected_path = PathBuf::from("/host/.zzz/task-123/todo-list.md");
-        let actual_path = FileSystem::get_todo_list_path(task_id);
+        let actual_path = host_file_system().get_todo_list_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_get_review_path() {
-        let task_id = 456;
+        let task_id = TaskId::from(456);
         let expected_path = PathBuf::from("/host/.zzz/task-456/review.md");
-        let actual_path = FileSystem::get_review_path(task_id);
+        let actual_path = host_file_system().get_review_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_get_plan_path() {
-        let task_id = 789;
+        let task_id = TaskId::from(789);
         let expected_path = PathBuf::from("/host/.zzz/task-789/plan.md");
-        let actual_path = FileSystem::get_plan_path(task_id);
+        let actual_path = host_file_system().get_plan_path(&task_id);
+        assert_eq!(actual_path, expected_path);
+    }
+
+    #[test]
+    fn test_get_plan_override_paths() {
+        assert_eq!(
+            host_file_system().get_plan_feedback_path(&TaskId::from(123)),
+            PathBuf::from("/host/.zzz/task-123/plan-feedback.md")
+        );
+        assert_eq!(
+            host_file_system().get_rejected_plan_path(&TaskId::from(123), 2),
+            PathBuf::from("/host/.zzz/task-123/todo-list-rejected-2.md")
+        );
+        assert_eq!(
+            host_file_system().get_reset_archive_dir(&TaskId::from(123), 1),
+            PathBuf::from("/host/.zzz/task-123/reset-1")
+        );
+    }
+
+    #[test]
+    fn test_get_review_archive_path() {
+        let path = host_file_system().get_review_archive_path(&TaskId::from(123), 2);
+        assert_eq!(path, PathBuf::from("/host/.zzz/task-123/review-round-2.md"));
+    }
+
+    #[test]
+    fn test_get_secret_env_path() {
+        let path = host_file_system().get_secret_env_path(&TaskId::from(123), "overseer");
+        assert_eq!(path, PathBuf::from("/host/.zzz/task-123/overseer.env"));
+    }
+
+    #[test]
+    fn test_get_prompt_path() {
+        let path = host_file_system().get_prompt_path(&TaskId::from(123), "commander", 2);
+        assert_eq!(
+            path,
+            PathBuf::from("/host/.zzz/task-123/prompt-commander-2.md")
+        );
+    }
+
+    #[test]
+    fn test_get_state_path() {
+        let task_id = TaskId::from(321);
+        let expected_path = PathBuf::from("/host/.zzz/task-321/state.json");
+        let actual_path = host_file_system().get_state_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
+    #[test]
+    fn test_get_index_path() {
+        assert_eq!(
+            host_file_system().get_index_path(),
+            PathBuf::from("/host/.zzz/index.json")
+        );
+        assert_eq!(
+            FileSystem::new("/tmp/zzz").get_index_path(),
+            PathBuf::from("/tmp/zzz/index.json")
+        );
+    }
+
     #[test]
     fn test_get_logs_dir_path() {
-        let task_id = 100;
+        let task_id = TaskId::from(100);
         let expected_path = PathBuf::from("/host/.zzz/task-100/logs");
-        let actual_path = FileSystem::get_logs_dir_path(task_id);
+        let actual_path = host_file_system().get_logs_dir_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_get_overseer_log_path() {
-        let task_id = 200;
+        let task_id = TaskId::from(200);
         let expected_path = PathBuf::from("/host/.zzz/task-200/logs/overseer.log");
-        let actual_path = FileSystem::get_overseer_log_path(task_id);
+        let actual_path = host_file_system().get_overseer_log_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_get_commander_log_path() {
-        let task_id = 300;
+        let task_id = TaskId::from(300);
         let expected_path = PathBuf::from("/host/.zzz/task-300/logs/commander.log");
-        let actual_path = FileSystem::get_commander_log_path(task_id);
+        let actual_path = host_file_system().get_commander_log_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_get_coordinator_log_path() {
-        let task_id = 400;
+        let task_id = TaskId::from(400);
         let expected_path = PathBuf::from("/host/.zzz/task-400/logs/coordinator.log");
-        let actual_path = FileSystem::get_coordinator_log_path(task_id);
+        let actual_path = host_file_system().get_coordinator_log_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
+    #[test]
+    fn test_get_dead_letter_log_path() {
+        let expected_path = PathBuf::from("/host/.zzz/task-400/logs/dead-letters.log");
+        assert_eq!(
+            host_file_system().get_dead_letter_log_path(&TaskId::from(400)),
+            expected_path
+        );
+    }
+
     #[test]
     fn test_path_consistency() {
-        let task_id = 500;
-        let task_dir = FileSystem::get_task_directory_path(task_id);
-        let logs_dir = FileSystem::get_logs_dir_path(task_id);
-        let todo_path = FileSystem::get_todo_list_path(task_id);
-        let review_path = FileSystem::get_review_path(task_id);
-        let plan_path = FileSystem::get_plan_path(task_id);
-        let overseer_log = FileSystem::get_overseer_log_path(task_id);
-        let commander_log = FileSystem::get_commander_log_path(task_id);
-        let coordinator_log = FileSystem::get_coordinator_log_path(task_id);
+        let task_id = TaskId::from(500);
+        let task_dir = host_file_system().get_task_directory_path(&task_id);
+        let logs_dir = host_file_system().get_logs_dir_path(&task_id);
+        let todo_path = host_file_system().get_todo_list_path(&task_id);
+        let review_path = host_file_system().get_review_path(&task_id);
+        let plan_path = host_file_system().get_plan_path(&task_id);
+        let overseer_log = host_file_system().get_overseer_log_path(&task_id);
+        let commander_log = host_file_system().get_commander_log_path(&task_id);
+        let coordinator_log = host_file_system().get_coordinator_log_path(&task_id);
 
         // Ensure all paths are under the task directory
         assert!(todo_path.starts_with(&task_dir));
@@ -545,95 +838,30 @@ mod tests {
         assert!(coordinator_log.starts_with(&logs_dir));
     }
 
-    #[test]
-    fn test_log_to_file() {
-        let temp_dir = create_test_dir();
-        let log_path = temp_dir.path().join("test.log");
-        let message = "Test log message";
-
-        let result = FileSystem::log_to_file(&log_path, message);
-        assert!(result.is_ok());
-
-        let content = fs::read_to_string(&log_path).unwrap();
-        assert!(content.contains(message));
-        assert!(content.starts_with('['));
-        assert!(content.ends_with("Test log message\n"));
-    }
-
-    #[test]
-    fn test_log_to_file_multiple_entries() {
-        let temp_dir = create_test_dir();
-        let log_path = temp_dir.path().join("multi.log");
-
-        let messages = ["First message", "Second message", "Third message"];
-        for message in &messages {
-            let result = FileSystem::log_to_file(&log_path, message);
-            assert!(result.is_ok());
-        }
-
-        let content = fs::read_to_string(&log_path).unwrap();
-        for message in &messages {
-            assert!(content.contains(message));
-        }
-
-        let lines: Vec<&str> = content.lines().collect();
-        assert_eq!(lines.len(), 3);
-    }
-
-    #[test]
-    fn test_log_timestamp_format() {
-        let temp_dir = create_test_dir();
-        let log_path = temp_dir.path().join("timestamp.log");
-        let message = "Timestamp test";
-
-        let before_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let result = FileSystem::log_to_file(&log_path, message);
-        assert!(result.is_ok());
-
-        let after_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let content = fs::read_to_string(&log_path).unwrap();
-        let line = content.lines().next().unwrap();
-
-        // Extract timestamp from log line format: [timestamp] message
-        let timestamp_str = &line[1..line.find(']').unwrap()];
-        let logged_timestamp: u64 = timestamp_str.parse().unwrap();
-
-        assert!(logged_timestamp >= before_timestamp);
-        assert!(logged_timestamp <= after_timestamp);
-    }
-
     #[test]
     fn test_log_overseer_creates_correct_path() {
         // We can't easily test the actual logging in the /host/.zzz directory
         // since it's outside our temp directory, but we can test the path generation
         // and ensure the function doesn't panic
-        let task_id = 999;
+        let task_id = TaskId::from(999);
         let expected_path = PathBuf::from("/host/.zzz/task-999/logs/overseer.log");
-        let actual_path = FileSystem::get_overseer_log_path(task_id);
+        let actual_path = host_file_system().get_overseer_log_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_log_commander_creates_correct_path() {
-        let task_id = 888;
+        let task_id = TaskId::from(888);
         let expected_path = PathBuf::from("/host/.zzz/task-888/logs/commander.log");
-        let actual_path = FileSystem::get_commander_log_path(task_id);
+        let actual_path = host_file_system().get_commander_log_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
     #[test]
     fn test_log_coordinator_creates_correct_path() {
-        let task_id = 777;
+        let task_id = TaskId::from(777);
         let expected_path = PathBuf::from("/host/.zzz/task-777/logs/coordinator.log");
-        let actual_path = FileSystem::get_coordinator_log_path(task_id);
+        let actual_path = host_file_system().get_coordinator_log_path(&task_id);
         assert_eq!(actual_path, expected_path);
     }
 
@@ -851,37 +1079,6 @@ mod tests {
         assert_eq!(result.unwrap(), new_content);
     }
 
-    #[test]
-    fn test_logging_workflow() {
-        let temp_dir = create_test_dir();
-        let log_file = temp_dir.path().join("workflow.log");
-
-        // Simulate a series of operations with logging
-        let operations = [
-            "Starting operation",
-            "Processing data",
-            "Intermediate result: 42",
-            "Finalizing operation",
-            "Operation completed successfully",
-        ];
-
-        for operation in &operations {
-            let result = FileSystem::log_to_file(&log_file, operation);
-            assert!(result.is_ok());
-        }
-
-        // Verify all operations were logged
-        let content = fs::read_to_string(&log_file).unwrap();
-        let lines: Vec<&str> = content.lines().collect();
-        assert_eq!(lines.len(), operations.len());
-
-        for (i, operation) in operations.iter().enumerate() {
-            assert!(lines[i].contains(operation));
-            assert!(lines[i].starts_with('['));
-            assert!(lines[i].contains("] "));
-        }
-    }
-
     #[test]
     fn test_concurrent_append_operations() {
         let temp_dir = create_test_dir();
@@ -916,7 +1113,7 @@ mod tests {
         let lines: Vec<&str> = content.lines().collect();
 
         // Check that we have at least some messages (concurrent operations might vary)
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
         assert!(lines.len() <= 15); // 5 threads * 3 messages each
 
         // Verify all threads and messages are represented
@@ -1087,21 +1284,23 @@ mod tests {
         // Test with various task IDs including edge cases
         let test_cases = [0, 1, 42, 999, 1000, 9999, u32::MAX];
 
-        for &task_id in &test_cases {
-            let task_dir = FileSystem::get_task_directory_path(task_id);
+        for &id in &test_cases {
+            let task_id = TaskId::from(id);
+            let task_dir = host_file_system().get_task_directory_path(&task_id);
             assert!(task_dir.to_string_lossy().contains(&task_id.to_string()));
 
-            let todo_path = FileSystem::get_todo_list_path(task_id);
+            let todo_path = host_file_system().get_todo_list_path(&task_id);
             assert!(todo_path.to_string_lossy().contains(&task_id.to_string()));
             assert!(todo_path.to_string_lossy().contains("todo-list.md"));
 
-            let log_path = FileSystem::get_overseer_log_path(task_id);
+            let log_path = host_file_system().get_overseer_log_path(&task_id);
             assert!(log_path.to_string_lossy().contains(&task_id.to_string()));
             assert!(log_path.to_string_lossy().contains("overseer.log"));
         }
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_filesystem_constants() {
         // Verify the constants are reasonable
         assert!(FileSystem::MAX_RETRIES > 0);
@@ -1113,4 +1312,184 @@ mod tests {
         assert!(FileSystem::OPERATION_TIMEOUT >= Duration::from_secs(1));
         assert!(FileSystem::OPERATION_TIMEOUT <= Duration::from_secs(60)); // Reasonable upper bound
     }
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_root_is_zzz_in_host_directory() {
+        let file_system = FileSystem::default();
+
+        assert_eq!(file_system.root(), Path::new("/host/.zzz"));
+        assert_eq!(
+            file_system.pane_task_directory_path(&TaskId::from(42)),
+            PathBuf::from(".zzz/task-42")
+        );
+    }
+
+    #[test]
+    fn test_from_configuration_base_directory() {
+        let file_system = FileSystem::from_configuration(
+            &configuration(&[("base_directory", " work/.zzz ")]),
+            Path::new("/host"),
+        );
+        assert_eq!(file_system.root(), Path::new("/host/work/.zzz"));
+        assert_eq!(
+            file_system.get_todo_list_path(&TaskId::from(1)),
+            PathBuf::from("/host/work/.zzz/task-1/todo-list.md")
+        );
+        assert_eq!(
+            file_system.pane_task_directory_path(&TaskId::from(1)),
+            PathBuf::from("work/.zzz/task-1")
+        );
+
+        // Absolute directories are used as they are
+        let file_system = FileSystem::from_configuration(
+            &configuration(&[("base_directory", "/srv/zzz")]),
+            Path::new("/host"),
+        );
+        assert_eq!(file_system.root(), Path::new("/srv/zzz"));
+        assert_eq!(
+            file_system.pane_task_directory_path(&TaskId::from(1)),
+            PathBuf::from("/srv/zzz/task-1")
+        );
+
+        // Blank values fall back to the default
+        let file_system = FileSystem::from_configuration(
+            &configuration(&[("base_directory", "  ")]),
+            Path::new("/repo"),
+        );
+        assert_eq!(file_system.root(), Path::new("/repo/.zzz"));
+    }
+
+    #[test]
+    fn test_new_uses_root_for_panes() {
+        let file_system = FileSystem::new("/tmp/zzz");
+
+        assert_eq!(
+            file_system.get_review_path(&TaskId::from(3)),
+            PathBuf::from("/tmp/zzz/task-3/review.md")
+        );
+        assert_eq!(
+            file_system.pane_task_directory_path(&TaskId::from(3)),
+            PathBuf::from("/tmp/zzz/task-3")
+        );
+    }
+
+    #[test]
+    fn test_setup_task_directories_under_root() {
+        let temp_dir = create_test_dir();
+        let file_system = FileSystem::new(temp_dir.path().join(".zzz"));
+
+        let task_dir = file_system
+            .setup_task_directories(&TaskId::from(7))
+            .unwrap();
+
+        assert_eq!(task_dir, temp_dir.path().join(".zzz/task-7"));
+        assert!(file_system.get_logs_dir_path(&TaskId::from(7)).is_dir());
+        // Setting up twice is harmless
+        assert!(file_system.setup_task_directories(&TaskId::from(7)).is_ok());
+    }
+
+    #[test]
+    fn test_coordinator_log_is_written_under_root() {
+        let temp_dir = create_test_dir();
+        let file_system = FileSystem::new(temp_dir.path());
+        file_system
+            .setup_task_directories(&TaskId::from(2))
+            .unwrap();
+
+        let task_id = TaskId::from(2);
+        let logger = Logger::default();
+        file_system
+            .log_coordinator(
+                &task_id,
+                &logger,
+                LogRecord::warn(LogComponent::Workflow, "coordinating"),
+            )
+            .unwrap();
+
+        let log = fs::read_to_string(file_system.get_coordinator_log_path(&task_id)).unwrap();
+        let coordinator: serde_json::Value = serde_json::from_str(log.trim_end()).unwrap();
+        assert_eq!(coordinator["task_id"], "2");
+        assert_eq!(coordinator["level"], "warn");
+        assert_eq!(coordinator["component"], "workflow");
+    }
+
+    #[test]
+    fn test_missing_root_fails_to_log() {
+        let temp_dir = create_test_dir();
+        let file_system = FileSystem::new(temp_dir.path().join("missing"));
+
+        assert!(file_system
+            .log_coordinator(
+                &TaskId::from(1),
+                &Logger::default(),
+                LogRecord::info(LogComponent::Coordinator, "lost")
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_string_task_ids_name_directories() {
+        let task_id = TaskId::parse("PROJ-1234").unwrap();
+
+        assert_eq!(
+            host_file_system().get_todo_list_path(&task_id),
+            PathBuf::from("/host/.zzz/task-PROJ-1234/todo-list.md")
+        );
+        assert_eq!(
+            host_file_system().pane_task_directory_path(&task_id),
+            PathBuf::from(".zzz/task-PROJ-1234")
+        );
+    }
+
+    #[test]
+    fn test_migrate_numeric_task_directory() {
+        let temp_dir = create_test_dir();
+        let file_system = FileSystem::new(temp_dir.path());
+        file_system
+            .setup_task_directories(&TaskId::from(7))
+            .unwrap();
+        fs::write(file_system.get_state_path(&TaskId::from(7)), "{}").unwrap();
+
+        let padded = TaskId::parse("007").unwrap();
+        assert!(file_system.migrate_numeric_task_directory(&padded).unwrap());
+        assert!(file_system.get_state_path(&padded).exists());
+        assert!(!file_system
+            .get_task_directory_path(&TaskId::from(7))
+            .exists());
+
+        // Already migrated
+        assert!(!file_system.migrate_numeric_task_directory(&padded).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_leaves_other_directories_alone() {
+        let temp_dir = create_test_dir();
+        let file_system = FileSystem::new(temp_dir.path());
+        file_system
+            .setup_task_directories(&TaskId::from(7))
+            .unwrap();
+
+        // Canonical numbers and non-numeric IDs have nothing to migrate
+        assert!(!file_system
+            .migrate_numeric_task_directory(&TaskId::from(7))
+            .unwrap());
+        assert!(!file_system
+            .migrate_numeric_task_directory(&TaskId::parse("PROJ-7").unwrap())
+            .unwrap());
+
+        // An existing directory for the new ID wins
+        let padded = TaskId::parse("07").unwrap();
+        file_system.setup_task_directories(&padded).unwrap();
+        assert!(!file_system.migrate_numeric_task_directory(&padded).unwrap());
+        assert!(file_system
+            .get_task_directory_path(&TaskId::from(7))
+            .exists());
+    }
 }
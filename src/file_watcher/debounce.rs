@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::{FileChange, FileChangeKind};
+
+/// Coalesces bursts of changes per file until the file has been quiet for `delay`
+#[derive(Debug)]
+pub struct Debouncer {
+    delay: Duration,
+    pending: HashMap<PathBuf, (FileChangeKind, Instant)>,
+}
+
+impl Debouncer {
+    /// Create a debouncer that waits `delay` after the last change of a file
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Record a change observed at `now`
+    pub fn push(&mut self, change: FileChange, now: Instant) {
+        let merged = match self.pending.get(&change.path) {
+            Some((previous, _)) => Self::merge(*previous, change.kind),
+            None => Some(change.kind),
+        };
+
+        match merged {
+            Some(kind) => {
+                self.pending.insert(change.path, (kind, now));
+            }
+            None => {
+                self.pending.remove(&change.path);
+            }
+        }
+    }
+
+    /// Take every change whose file has been quiet for at least the delay
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<FileChange> {
+        let delay = self.delay;
+        let mut ready: Vec<FileChange> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, last_seen))| now.saturating_duration_since(*last_seen) >= delay)
+            .map(|(path, (kind, _))| FileChange::new(path.clone(), *kind))
+            .collect();
+
+        for change in &ready {
+            self.pending.remove(&change.path);
+        }
+
+        ready.sort_by(|a, b| a.path.cmp(&b.path));
+        ready
+    }
+
+    /// Check whether any change is still waiting to settle
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Combine a pending change with a newer one; `None` means they cancel out
+    fn merge(previous: FileChangeKind, next: FileChangeKind) -> Option<FileChangeKind> {
+        match (previous, next) {
+            (FileChangeKind::Created, FileChangeKind::Modified) => Some(FileChangeKind::Created),
+            (FileChangeKind::Created, FileChangeKind::Removed) => None,
+            (FileChangeKind::Removed, FileChangeKind::Created) => Some(FileChangeKind::Modified),
+            (_, next) => Some(next),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(500);
+
+    fn change(path: &str, kind: FileChangeKind) -> FileChange {
+        FileChange::new(path, kind)
+    }
+
+    #[test]
+    fn test_change_is_held_until_quiet() {
+        let mut debouncer = Debouncer::new(DELAY);
+        let start = Instant::now();
+
+        debouncer.push(change("/a", FileChangeKind::Modified), start);
+        assert!(debouncer.drain_ready(start).is_empty());
+        assert!(debouncer
+            .drain_ready(start + Duration::from_millis(499))
+            .is_empty());
+
+        let ready = debouncer.drain_ready(start + DELAY);
+        assert_eq!(ready, vec![change("/a", FileChangeKind::Modified)]);
+        assert!(!debouncer.has_pending());
+    }
+
+    #[test]
+    fn test_new_change_restarts_the_delay() {
+        let mut debouncer = Debouncer::new(DELAY);
+        let start = Instant::now();
+
+        debouncer.push(change("/a", FileChangeKind::Modified), start);
+        debouncer.push(
+            change("/a", FileChangeKind::Modified),
+            start + Duration::from_millis(400),
+        );
+
+        assert!(debouncer.drain_ready(start + DELAY).is_empty());
+        assert_eq!(
+            debouncer
+                .drain_ready(start + Duration::from_millis(900))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_burst_collapses_to_single_change() {
+        let mut debouncer = Debouncer::new(DELAY);
+        let start = Instant::now();
+
+        debouncer.push(change("/a", FileChangeKind::Created), start);
+        debouncer.push(change("/a", FileChangeKind::Modified), start);
+        debouncer.push(change("/a", FileChangeKind::Modified), start);
+
+        let ready = debouncer.drain_ready(start + DELAY);
+        assert_eq!(ready, vec![change("/a", FileChangeKind::Created)]);
+    }
+
+    #[test]
+    fn test_created_then_removed_cancels_out() {
+        let mut debouncer = Debouncer::new(DELAY);
+        let start = Instant::now();
+
+        debouncer.push(change("/a", FileChangeKind::Created), start);
+        debouncer.push(change("/a", FileChangeKind::Removed), start);
+
+        assert!(!debouncer.has_pending());
+        assert!(debouncer.drain_ready(start + DELAY).is_empty());
+    }
+
+    #[test]
+    fn test_removed_then_created_is_modified() {
+        let mut debouncer = Debouncer::new(DELAY);
+        let start = Instant::now();
+
+        debouncer.push(change("/a", FileChangeKind::Removed), start);
+        debouncer.push(change("/a", FileChangeKind::Created), start);
+
+        let ready = debouncer.drain_ready(start + DELAY);
+        assert_eq!(ready, vec![change("/a", FileChangeKind::Modified)]);
+    }
+
+    #[test]
+    fn test_files_are_debounced_independently() {
+        let mut debouncer = Debouncer::new(DELAY);
+        let start = Instant::now();
+
+        debouncer.push(change("/b", FileChangeKind::Modified), start);
+        debouncer.push(
+            change("/a", FileChangeKind::Modified),
+            start + Duration::from_millis(300),
+        );
+
+        let first = debouncer.drain_ready(start + DELAY);
+        assert_eq!(first, vec![change("/b", FileChangeKind::Modified)]);
+        assert!(debouncer.has_pending());
+
+        let second = debouncer.drain_ready(start + Duration::from_millis(800));
+        assert_eq!(second, vec![change("/a", FileChangeKind::Modified)]);
+    }
+}
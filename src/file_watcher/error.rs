@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+/// Errors that can occur while setting up file watching
+#[derive(Debug)]
+pub enum WatchError {
+    /// The path cannot be watched (e.g. it has no parent directory)
+    InvalidPath(PathBuf),
+    /// The underlying watcher backend reported an error
+    Backend(String),
+}
+
+impl std::fmt::Display for WatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchError::InvalidPath(path) => {
+                write!(f, "Cannot watch path: {}", path.display())
+            }
+            WatchError::Backend(msg) => write!(f, "File watcher failed: {}", msg),
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl From<notify::Error> for WatchError {
+    fn from(error: notify::Error) -> Self {
+        WatchError::Backend(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            format!("{}", WatchError::InvalidPath(PathBuf::from("/"))),
+            "Cannot watch path: /"
+        );
+        assert_eq!(
+            format!("{}", WatchError::Backend("boom".to_string())),
+            "File watcher failed: boom"
+        );
+    }
+}
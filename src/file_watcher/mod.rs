@@ -0,0 +1,84 @@
+pub mod debounce;
+pub mod error;
+pub mod monitor;
+#[cfg(not(target_family = "wasm"))]
+pub mod native;
+pub mod polling;
+
+use std::path::{Path, PathBuf};
+
+pub use debounce::Debouncer;
+pub use error::WatchError;
+pub use monitor::TaskFileMonitor;
+#[cfg(not(target_family = "wasm"))]
+pub use native::NotifyWatcher;
+pub use polling::PollingWatcher;
+
+/// Kind of change observed on a watched file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl FileChangeKind {
+    /// Name used as the `event_type` of `CoordinationMessage::FileChanged`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileChangeKind::Created => "created",
+            FileChangeKind::Modified => "modified",
+            FileChangeKind::Removed => "removed",
+        }
+    }
+}
+
+/// A raw change reported by a watcher backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub kind: FileChangeKind,
+}
+
+impl FileChange {
+    pub fn new<P: Into<PathBuf>>(path: P, kind: FileChangeKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+        }
+    }
+}
+
+/// Backend that reports changes to individual files
+///
+/// Files do not need to exist when they are watched; creating them later is
+/// reported as `Created`.
+pub trait FileWatcher {
+    /// Start watching a file
+    fn watch(&mut self, path: &Path) -> Result<(), WatchError>;
+
+    /// Stop watching a file
+    fn unwatch(&mut self, path: &Path) -> Result<(), WatchError>;
+
+    /// Collect the changes observed since the previous call
+    fn poll_changes(&mut self) -> Vec<FileChange>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_kind_names() {
+        assert_eq!(FileChangeKind::Created.as_str(), "created");
+        assert_eq!(FileChangeKind::Modified.as_str(), "modified");
+        assert_eq!(FileChangeKind::Removed.as_str(), "removed");
+    }
+
+    #[test]
+    fn test_file_change_new() {
+        let change = FileChange::new("/tmp/todo-list.md", FileChangeKind::Modified);
+        assert_eq!(change.path, PathBuf::from("/tmp/todo-list.md"));
+        assert_eq!(change.kind, FileChangeKind::Modified);
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{Debouncer, FileWatcher, WatchError};
+use crate::coordination_message::CoordinationMessage;
+
+/// Default quiet period before a file change is reported
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Watches task files and turns settled changes into `FileChanged` messages
+pub struct TaskFileMonitor<W: FileWatcher> {
+    watcher: W,
+    debouncer: Debouncer,
+}
+
+impl<W: FileWatcher> TaskFileMonitor<W> {
+    /// Create a monitor that reports a file once it has been quiet for `debounce`
+    pub fn new(watcher: W, debounce: Duration) -> Self {
+        Self {
+            watcher,
+            debouncer: Debouncer::new(debounce),
+        }
+    }
+
+    /// Start watching a file
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> Result<(), WatchError> {
+        self.watcher.watch(path.as_ref())
+    }
+
+    /// Stop watching a file
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) -> Result<(), WatchError> {
+        self.watcher.unwatch(path.as_ref())
+    }
+
+    /// Poll the backend and return the changes that have settled by `now`
+    pub fn tick(&mut self, now: Instant) -> Vec<CoordinationMessage> {
+        for change in self.watcher.poll_changes() {
+            self.debouncer.push(change, now);
+        }
+
+        self.debouncer
+            .drain_ready(now)
+            .into_iter()
+            .map(|change| CoordinationMessage::FileChanged {
+                file_path: change.path.to_string_lossy().into_owned(),
+                event_type: change.kind.as_str().to_string(),
+            })
+            .collect()
+    }
+
+    /// Check whether changes are still waiting to settle
+    pub fn has_pending(&self) -> bool {
+        self.debouncer.has_pending()
+    }
+
+    /// Get a reference to the watcher backend (for testing)
+    #[cfg(test)]
+    pub fn get_watcher(&self) -> &W {
+        &self.watcher
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_watcher::{FileChange, FileChangeKind, PollingWatcher};
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    /// Watcher that replays scripted changes
+    #[derive(Default)]
+    struct ScriptedWatcher {
+        watched: Vec<PathBuf>,
+        queued: Vec<FileChange>,
+    }
+
+    impl FileWatcher for ScriptedWatcher {
+        fn watch(&mut self, path: &Path) -> Result<(), WatchError> {
+            self.watched.push(path.to_path_buf());
+            Ok(())
+        }
+
+        fn unwatch(&mut self, path: &Path) -> Result<(), WatchError> {
+            self.watched.retain(|p| p != path);
+            Ok(())
+        }
+
+        fn poll_changes(&mut self) -> Vec<FileChange> {
+            std::mem::take(&mut self.queued)
+        }
+    }
+
+    #[test]
+    fn test_tick_emits_debounced_file_changed() {
+        let mut monitor = TaskFileMonitor::new(ScriptedWatcher::default(), DEFAULT_DEBOUNCE);
+        monitor.watch("/task/todo-list.md").unwrap();
+        assert_eq!(
+            monitor.get_watcher().watched,
+            vec![PathBuf::from("/task/todo-list.md")]
+        );
+
+        let start = Instant::now();
+        monitor.watcher.queued = vec![
+            FileChange::new("/task/todo-list.md", FileChangeKind::Created),
+            FileChange::new("/task/todo-list.md", FileChangeKind::Modified),
+        ];
+        assert!(monitor.tick(start).is_empty());
+        assert!(monitor.has_pending());
+
+        let messages = monitor.tick(start + DEFAULT_DEBOUNCE);
+        assert_eq!(
+            messages,
+            vec![CoordinationMessage::FileChanged {
+                file_path: "/task/todo-list.md".to_string(),
+                event_type: "created".to_string(),
+            }]
+        );
+        assert!(!monitor.has_pending());
+    }
+
+    #[test]
+    fn test_continued_writes_delay_the_message() {
+        let mut monitor = TaskFileMonitor::new(ScriptedWatcher::default(), DEFAULT_DEBOUNCE);
+        let start = Instant::now();
+
+        monitor.watcher.queued = vec![FileChange::new("/t/review.md", FileChangeKind::Modified)];
+        monitor.tick(start);
+
+        monitor.watcher.queued = vec![FileChange::new("/t/review.md", FileChangeKind::Modified)];
+        assert!(monitor.tick(start + Duration::from_secs(1)).is_empty());
+        assert!(monitor.tick(start + DEFAULT_DEBOUNCE).is_empty());
+        assert_eq!(monitor.tick(start + Duration::from_secs(3)).len(), 1);
+    }
+
+    #[test]
+    fn test_unwatch_delegates_to_backend() {
+        let mut monitor = TaskFileMonitor::new(ScriptedWatcher::default(), DEFAULT_DEBOUNCE);
+        monitor.watch("/t/review.md").unwrap();
+        monitor.unwatch("/t/review.md").unwrap();
+        assert!(monitor.get_watcher().watched.is_empty());
+    }
+
+    #[test]
+    fn test_polling_backend_end_to_end() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("review.md");
+        let mut monitor = TaskFileMonitor::new(PollingWatcher::new(), Duration::from_millis(10));
+        monitor.watch(&path).unwrap();
+
+        let start = Instant::now();
+        fs::write(&path, "## Findings").unwrap();
+        assert!(monitor.tick(start).is_empty());
+
+        let messages = monitor.tick(start + Duration::from_millis(10));
+        assert_eq!(
+            messages,
+            vec![CoordinationMessage::FileChanged {
+                file_path: path.to_string_lossy().into_owned(),
+                event_type: "created".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+
+use super::{FileChange, FileChangeKind, FileWatcher, WatchError};
+
+/// Watcher backed by the platform's native notification API via `notify`
+///
+/// Not available under WASI; used for native builds and tests. The parent
+/// directory is watched so that files can be watched before they exist.
+pub struct NotifyWatcher {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<notify::Event>>,
+    files: HashSet<PathBuf>,
+    directories: HashMap<PathBuf, usize>,
+}
+
+impl NotifyWatcher {
+    pub fn new() -> Result<Self, WatchError> {
+        let (sender, receiver) = channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })?;
+
+        Ok(Self {
+            watcher,
+            receiver,
+            files: HashSet::new(),
+            directories: HashMap::new(),
+        })
+    }
+
+    fn parent_of(path: &Path) -> Result<PathBuf, WatchError> {
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(_)) if !parent.as_os_str().is_empty() => Ok(parent.to_path_buf()),
+            _ => Err(WatchError::InvalidPath(path.to_path_buf())),
+        }
+    }
+
+    fn change_kind(kind: &EventKind, path: &Path) -> Option<FileChangeKind> {
+        match kind {
+            EventKind::Create(_) => Some(FileChangeKind::Created),
+            EventKind::Remove(_) => Some(FileChangeKind::Removed),
+            // Renames (e.g. atomic writes) report both ends; check which side we are on
+            EventKind::Modify(ModifyKind::Name(_)) => Some(if path.exists() {
+                FileChangeKind::Modified
+            } else {
+                FileChangeKind::Removed
+            }),
+            EventKind::Modify(ModifyKind::Metadata(_)) => None,
+            EventKind::Modify(_) => Some(FileChangeKind::Modified),
+            _ => None,
+        }
+    }
+}
+
+impl FileWatcher for NotifyWatcher {
+    fn watch(&mut self, path: &Path) -> Result<(), WatchError> {
+        let directory = Self::parent_of(path)?;
+        if !self.files.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+
+        let count = self.directories.entry(directory.clone()).or_insert(0);
+        if *count == 0 {
+            if let Err(e) = self.watcher.watch(&directory, RecursiveMode::NonRecursive) {
+                self.files.remove(path);
+                self.directories.remove(&directory);
+                return Err(e.into());
+            }
+        }
+        *count += 1;
+
+        Ok(())
+    }
+
+    fn unwatch(&mut self, path: &Path) -> Result<(), WatchError> {
+        if !self.files.remove(path) {
+            return Ok(());
+        }
+
+        let directory = Self::parent_of(path)?;
+        if let Some(count) = self.directories.get_mut(&directory) {
+            *count -= 1;
+            if *count == 0 {
+                self.directories.remove(&directory);
+                self.watcher.unwatch(&directory)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn poll_changes(&mut self) -> Vec<FileChange> {
+        let mut changes = Vec::new();
+
+        while let Ok(result) = self.receiver.try_recv() {
+            let event = match result {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+
+            for path in &event.paths {
+                if !self.files.contains(path) {
+                    continue;
+                }
+                if let Some(kind) = Self::change_kind(&event.kind, path) {
+                    changes.push(FileChange::new(path.clone(), kind));
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    /// Poll until a change arrives or the deadline passes
+    fn wait_for_changes(watcher: &mut NotifyWatcher) -> Vec<FileChange> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut changes = Vec::new();
+        while Instant::now() < deadline {
+            changes.extend(watcher.poll_changes());
+            if !changes.is_empty() {
+                // Give the backend a moment to deliver the rest of the burst
+                thread::sleep(Duration::from_millis(100));
+                changes.extend(watcher.poll_changes());
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        changes
+    }
+
+    #[test]
+    fn test_reports_changes_to_watched_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("todo-list.md");
+
+        let mut watcher = NotifyWatcher::new().unwrap();
+        watcher.watch(&path).unwrap();
+
+        fs::write(&path, "- [ ] One").unwrap();
+        let changes = wait_for_changes(&mut watcher);
+
+        assert!(!changes.is_empty());
+        assert!(changes.iter().all(|c| c.path == path));
+        assert_eq!(changes[0].kind, FileChangeKind::Created);
+    }
+
+    #[test]
+    fn test_ignores_other_files_in_directory() {
+        let temp_dir = tempdir().unwrap();
+        let watched = temp_dir.path().join("review.md");
+        let other = temp_dir.path().join("notes.md");
+
+        let mut watcher = NotifyWatcher::new().unwrap();
+        watcher.watch(&watched).unwrap();
+
+        fs::write(&other, "ignored").unwrap();
+        fs::write(&watched, "LGTM").unwrap();
+        let changes = wait_for_changes(&mut watcher);
+
+        assert!(!changes.is_empty());
+        assert!(changes.iter().all(|c| c.path == watched));
+    }
+
+    #[test]
+    fn test_unwatch_last_file_releases_directory() {
+        let temp_dir = tempdir().unwrap();
+        let first = temp_dir.path().join("todo-list.md");
+        let second = temp_dir.path().join("review.md");
+
+        let mut watcher = NotifyWatcher::new().unwrap();
+        watcher.watch(&first).unwrap();
+        watcher.watch(&second).unwrap();
+        assert_eq!(watcher.directories.get(temp_dir.path()), Some(&2));
+
+        watcher.unwatch(&first).unwrap();
+        assert_eq!(watcher.directories.get(temp_dir.path()), Some(&1));
+
+        watcher.unwatch(&second).unwrap();
+        assert!(watcher.directories.is_empty());
+    }
+
+    #[test]
+    fn test_watch_rejects_relative_file_name() {
+        let mut watcher = NotifyWatcher::new().unwrap();
+        assert!(matches!(
+            watcher.watch(Path::new("todo-list.md")),
+            Err(WatchError::InvalidPath(_))
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::{FileChange, FileChangeKind, FileWatcher, WatchError};
+
+/// Seconds between polls when driven by Zellij timers
+pub const DEFAULT_POLL_INTERVAL_SECS: f64 = 1.0;
+
+/// Size and modification time used to detect changes between polls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Watcher that compares file metadata on every poll
+///
+/// This is the backend used inside the plugin, where native file notification
+/// APIs are unavailable under WASI. The plugin calls `poll_changes` from its
+/// `Event::Timer` handler and re-arms the timer with `set_timeout`.
+#[derive(Debug, Default)]
+pub struct PollingWatcher {
+    stamps: HashMap<PathBuf, Option<FileStamp>>,
+}
+
+impl PollingWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether a path is currently being watched
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.stamps.contains_key(path)
+    }
+}
+
+impl FileWatcher for PollingWatcher {
+    fn watch(&mut self, path: &Path) -> Result<(), WatchError> {
+        if path.file_name().is_none() {
+            return Err(WatchError::InvalidPath(path.to_path_buf()));
+        }
+
+        // Files that already exist are the baseline, not a change
+        self.stamps
+            .insert(path.to_path_buf(), FileStamp::read(path));
+        Ok(())
+    }
+
+    fn unwatch(&mut self, path: &Path) -> Result<(), WatchError> {
+        self.stamps.remove(path);
+        Ok(())
+    }
+
+    fn poll_changes(&mut self) -> Vec<FileChange> {
+        let mut changes = Vec::new();
+
+        for (path, previous) in self.stamps.iter_mut() {
+            let current = FileStamp::read(path);
+            let kind = match (*previous, current) {
+                (None, Some(_)) => Some(FileChangeKind::Created),
+                (Some(_), None) => Some(FileChangeKind::Removed),
+                (Some(old), Some(new)) if old != new => Some(FileChangeKind::Modified),
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                changes.push(FileChange::new(path.clone(), kind));
+            }
+            *previous = current;
+        }
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_existing_file_is_baseline() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("todo-list.md");
+        fs::write(&path, "- [ ] One").unwrap();
+
+        let mut watcher = PollingWatcher::new();
+        watcher.watch(&path).unwrap();
+
+        assert!(watcher.is_watching(&path));
+        assert!(watcher.poll_changes().is_empty());
+    }
+
+    #[test]
+    fn test_detects_create_modify_remove() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("todo-list.md");
+
+        let mut watcher = PollingWatcher::new();
+        watcher.watch(&path).unwrap();
+        assert!(watcher.poll_changes().is_empty());
+
+        fs::write(&path, "- [ ] One").unwrap();
+        assert_eq!(
+            watcher.poll_changes(),
+            vec![FileChange::new(&path, FileChangeKind::Created)]
+        );
+        assert!(watcher.poll_changes().is_empty());
+
+        // A different length is detected even within the same mtime tick
+        fs::write(&path, "- [x] One\n- [ ] Two").unwrap();
+        assert_eq!(
+            watcher.poll_changes(),
+            vec![FileChange::new(&path, FileChangeKind::Modified)]
+        );
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            watcher.poll_changes(),
+            vec![FileChange::new(&path, FileChangeKind::Removed)]
+        );
+    }
+
+    #[test]
+    fn test_unwatch_stops_reporting() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("review.md");
+
+        let mut watcher = PollingWatcher::new();
+        watcher.watch(&path).unwrap();
+        watcher.unwatch(&path).unwrap();
+        fs::write(&path, "LGTM").unwrap();
+
+        assert!(!watcher.is_watching(&path));
+        assert!(watcher.poll_changes().is_empty());
+    }
+
+    #[test]
+    fn test_directories_are_not_files() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("review.md");
+
+        let mut watcher = PollingWatcher::new();
+        watcher.watch(&path).unwrap();
+        fs::create_dir(&path).unwrap();
+
+        assert!(watcher.poll_changes().is_empty());
+    }
+
+    #[test]
+    fn test_watch_rejects_path_without_file_name() {
+        let mut watcher = PollingWatcher::new();
+        assert!(matches!(
+            watcher.watch(Path::new("/")),
+            Err(WatchError::InvalidPath(_))
+        ));
+    }
+}
@@ -1,5 +1,24 @@
+pub mod assistant;
+pub mod communication;
+pub mod control;
+pub mod coordination_message;
+pub mod dashboard;
 pub mod file_system;
+pub mod file_watcher;
 pub mod litellm_config;
+pub mod logging;
+pub mod notification;
+pub mod pane_role;
+pub mod persistence;
+pub mod review;
+pub mod secrets;
+pub mod shell;
+pub mod status_bar;
+pub mod task_id;
+pub mod task_registry;
+pub mod todo_list;
+pub mod workflow;
+pub mod workflow_phase;
 pub mod zellij_service;
 
 pub use file_system::FileSystem;
@@ -1,6 +1,8 @@
 #[derive(Debug, Clone)]
 pub struct LiteLLMConfig {
     pub api_key: String,
+    /// Path to a file holding the API key, read by the pane's shell at launch
+    pub api_key_file: Option<String>,
     pub url: String,
 }
 
@@ -8,7 +10,17 @@ impl Default for LiteLLMConfig {
     fn default() -> Self {
         Self {
             api_key: String::new(),
+            api_key_file: None,
             url: "https://litellm.example.in".to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+impl LiteLLMConfig {
+    /// Check whether an endpoint and a key (inline or key file) are configured
+    pub fn is_configured(&self) -> bool {
+        let has_key =
+            !self.api_key.is_empty() || self.api_key_file.as_ref().is_some_and(|p| !p.is_empty());
+        has_key && !self.url.is_empty()
+    }
+}
@@ -0,0 +1,557 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::file_system::{FileSystem, FileSystemError};
+use crate::secrets::Redactor;
+use crate::task_id::TaskId;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Size a log may reach before it is rotated
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Rotated copies kept next to a log (`coordinator.log.1` is the newest)
+pub const DEFAULT_MAX_LOG_FILES: u32 = 5;
+
+/// Severity of a log record, from least to most severe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Parse a level name as written in the configuration, ignoring case
+    pub fn from_name(name: &str) -> Option<LogLevel> {
+        match name.trim().to_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Part of the plugin a record comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogComponent {
+    /// Plugin lifecycle and configuration
+    Coordinator,
+    /// Phase transitions and workflow actions
+    Workflow,
+    /// Coordination messages, acknowledgements and delivery
+    Messaging,
+    /// Pane discovery, spawning and health
+    Panes,
+    /// Commands from the control pipe and keybindings
+    Control,
+    /// Task files, snapshots and the task index
+    Files,
+}
+
+/// One line of a JSON-lines log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogRecord {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub level: LogLevel,
+    pub component: LogComponent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<TaskId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<WorkflowPhase>,
+    /// ID of the coordination message the record is about, to correlate
+    /// sends, acknowledgements and dead letters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<u64>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+}
+
+impl LogRecord {
+    /// Record stamped with the current time
+    pub fn new(level: LogLevel, component: LogComponent, message: &str) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            level,
+            component,
+            task_id: None,
+            phase: None,
+            message_id: None,
+            message: message.to_string(),
+            fields: Map::new(),
+        }
+    }
+
+    pub fn debug(component: LogComponent, message: &str) -> Self {
+        Self::new(LogLevel::Debug, component, message)
+    }
+
+    pub fn info(component: LogComponent, message: &str) -> Self {
+        Self::new(LogLevel::Info, component, message)
+    }
+
+    pub fn warn(component: LogComponent, message: &str) -> Self {
+        Self::new(LogLevel::Warn, component, message)
+    }
+
+    pub fn error(component: LogComponent, message: &str) -> Self {
+        Self::new(LogLevel::Error, component, message)
+    }
+
+    pub fn task(self, task_id: &TaskId) -> Self {
+        Self {
+            task_id: Some(task_id.clone()),
+            ..self
+        }
+    }
+
+    pub fn phase(self, phase: WorkflowPhase) -> Self {
+        Self {
+            phase: Some(phase),
+            ..self
+        }
+    }
+
+    pub fn message_id(self, message_id: Option<u64>) -> Self {
+        Self { message_id, ..self }
+    }
+
+    /// Attach a structured field; values that fail to serialize are skipped
+    pub fn field<T: Serialize>(mut self, key: &str, value: T) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.fields.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    /// Mask secrets in the message and in string field values
+    pub fn redacted(mut self, redactor: &Redactor) -> Self {
+        self.message = redactor.redact(&self.message);
+        for value in self.fields.values_mut() {
+            redact_value(value, redactor);
+        }
+        self
+    }
+
+    /// The record as a single line of JSON, without the newline
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|e| {
+            serde_json::json!({
+                "timestamp": self.timestamp,
+                "level": self.level,
+                "component": self.component,
+                "message": self.message,
+                "fields": { "serialization_error": e.to_string() }
+            })
+            .to_string()
+        })
+    }
+}
+
+fn redact_value(value: &mut Value, redactor: &Redactor) {
+    match value {
+        Value::String(text) => *text = redactor.redact(text),
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_value(item, redactor)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|item| redact_value(item, redactor)),
+        _ => {}
+    }
+}
+
+/// Errors in the logging configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogConfigError {
+    InvalidLevel(String),
+    InvalidValue { key: String, value: String },
+}
+
+impl fmt::Display for LogConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogConfigError::InvalidLevel(level) => write!(
+                f,
+                "Invalid log_level '{}' (expected debug, info, warn or error)",
+                level
+            ),
+            LogConfigError::InvalidValue { key, value } => {
+                write!(f, "Invalid {} '{}'", key, value)
+            }
+        }
+    }
+}
+
+/// Writes records at or above a minimum level, rotating logs that grow too large
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Logger {
+    pub min_level: LogLevel,
+    /// Size in bytes a log may reach before it is rotated
+    pub max_bytes: u64,
+    /// Rotated copies kept; with none, a full log is started afresh
+    pub max_files: u32,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self {
+            min_level: LogLevel::default(),
+            max_bytes: DEFAULT_MAX_LOG_BYTES,
+            max_files: DEFAULT_MAX_LOG_FILES,
+        }
+    }
+}
+
+impl Logger {
+    /// Read `log_level`, `log_max_bytes` and `log_max_files` from the plugin configuration
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+    ) -> Result<Self, LogConfigError> {
+        let mut logger = Self::default();
+        let invalid = |key: &str, value: &str| LogConfigError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        if let Some(level) = configuration.get("log_level") {
+            logger.min_level = LogLevel::from_name(level)
+                .ok_or_else(|| LogConfigError::InvalidLevel(level.clone()))?;
+        }
+
+        if let Some(value) = configuration.get("log_max_bytes") {
+            logger.max_bytes = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|bytes| *bytes > 0)
+                .ok_or_else(|| invalid("log_max_bytes", value))?;
+        }
+
+        if let Some(value) = configuration.get("log_max_files") {
+            logger.max_files = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid("log_max_files", value))?;
+        }
+
+        Ok(logger)
+    }
+
+    /// Check whether records of `level` are written
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level >= self.min_level
+    }
+
+    /// Append a record to the log at `path`, rotating the log first if the
+    /// record would take it past `max_bytes`
+    pub fn write<P: AsRef<Path>>(
+        &self,
+        path: P,
+        record: &LogRecord,
+    ) -> Result<(), FileSystemError> {
+        if !self.enabled(record.level) {
+            return Ok(());
+        }
+
+        let path = path.as_ref();
+        let line = format!("{}\n", record.to_json_line());
+        let size = fs::metadata(path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        if size > 0 && size + line.len() as u64 > self.max_bytes {
+            self.rotate(path)?;
+        }
+
+        FileSystem::append_to_file(path, &line)
+    }
+
+    /// Rotate the log at `path` once it has grown past `max_bytes`
+    ///
+    /// For logs appended to by other processes, such as the role transcripts,
+    /// which cannot be rotated as a record is written. Returns whether the log
+    /// was rotated.
+    pub fn rotate_if_full(&self, path: &Path) -> io::Result<bool> {
+        let size = fs::metadata(path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        if size <= self.max_bytes {
+            return Ok(false);
+        }
+
+        self.rotate(path)?;
+        Ok(true)
+    }
+
+    /// Shift `log.1`… up by one, dropping the oldest, and move the log to `log.1`
+    pub fn rotate(&self, path: &Path) -> io::Result<()> {
+        if self.max_files == 0 {
+            return remove_if_exists(path);
+        }
+
+        remove_if_exists(&rotated_path(path, self.max_files))?;
+        for n in (1..self.max_files).rev() {
+            let from = rotated_path(path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, n + 1))?;
+            }
+        }
+        fs::rename(path, rotated_path(path, 1))
+    }
+}
+
+/// Path of the `n`th rotated copy of a log, e.g. `coordinator.log.2`
+pub fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", n));
+    path.with_file_name(name)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::litellm_config::LiteLLMConfig;
+    use tempfile::tempdir;
+
+    fn configuration(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn read_records(path: &Path) -> Vec<Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_record_serializes_as_one_json_line() {
+        let record = LogRecord::info(LogComponent::Messaging, "Sent PlanReady")
+            .task(&TaskId::parse("PROJ-1").unwrap())
+            .phase(WorkflowPhase::PlanReady)
+            .message_id(Some(4))
+            .field("target", "Commander")
+            .field("attempt", 2);
+
+        let line = record.to_json_line();
+        assert!(!line.contains('\n'));
+        let value: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["level"], "info");
+        assert_eq!(value["component"], "messaging");
+        assert_eq!(value["task_id"], "PROJ-1");
+        assert_eq!(value["phase"], "PlanReady");
+        assert_eq!(value["message_id"], 4);
+        assert_eq!(value["message"], "Sent PlanReady");
+        assert_eq!(
+            value["fields"],
+            serde_json::json!({ "target": "Commander", "attempt": 2 })
+        );
+    }
+
+    #[test]
+    fn test_optional_parts_are_omitted() {
+        let value: Value = serde_json::from_str(
+            &LogRecord::warn(LogComponent::Coordinator, "Plain").to_json_line(),
+        )
+        .unwrap();
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["component", "level", "message", "timestamp"]);
+    }
+
+    #[test]
+    fn test_redacted_masks_message_and_fields() {
+        let config = LiteLLMConfig {
+            api_key: "sk-secret".to_string(),
+            ..LiteLLMConfig::default()
+        };
+        let redactor = Redactor::from_config(&config);
+
+        let record = LogRecord::debug(LogComponent::Panes, "Typed sk-secret")
+            .field("command", "KEY=sk-secret codex")
+            .field("args", vec!["sk-secret"])
+            .field("count", 1)
+            .redacted(&redactor);
+
+        let line = record.to_json_line();
+        assert!(!line.contains("sk-secret"));
+        assert_eq!(record.message, "Typed [REDACTED]");
+        assert_eq!(record.fields["count"], 1);
+    }
+
+    #[test]
+    fn test_level_names_and_order() {
+        assert_eq!(LogLevel::from_name(" WARNING "), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::from_name("error"), Some(LogLevel::Error));
+        assert_eq!(LogLevel::from_name("trace"), None);
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Warn < LogLevel::Error);
+    }
+
+    #[test]
+    fn test_from_configuration() {
+        assert_eq!(
+            Logger::from_configuration(&BTreeMap::new()).unwrap(),
+            Logger::default()
+        );
+
+        let logger = Logger::from_configuration(&configuration(&[
+            ("log_level", "debug"),
+            ("log_max_bytes", "2048"),
+            ("log_max_files", "0"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            logger,
+            Logger {
+                min_level: LogLevel::Debug,
+                max_bytes: 2048,
+                max_files: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_configuration_rejects_invalid_values() {
+        assert_eq!(
+            Logger::from_configuration(&configuration(&[("log_level", "loud")])),
+            Err(LogConfigError::InvalidLevel("loud".to_string()))
+        );
+        assert_eq!(
+            Logger::from_configuration(&configuration(&[("log_max_bytes", "0")])),
+            Err(LogConfigError::InvalidValue {
+                key: "log_max_bytes".to_string(),
+                value: "0".to_string()
+            })
+        );
+        assert!(Logger::from_configuration(&configuration(&[("log_max_files", "-1")])).is_err());
+        assert_eq!(
+            LogConfigError::InvalidLevel("loud".to_string()).to_string(),
+            "Invalid log_level 'loud' (expected debug, info, warn or error)"
+        );
+    }
+
+    #[test]
+    fn test_write_filters_by_level() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("coordinator.log");
+        let logger = Logger {
+            min_level: LogLevel::Warn,
+            ..Logger::default()
+        };
+
+        logger
+            .write(&path, &LogRecord::info(LogComponent::Coordinator, "quiet"))
+            .unwrap();
+        assert!(!path.exists());
+
+        logger
+            .write(&path, &LogRecord::error(LogComponent::Coordinator, "loud"))
+            .unwrap();
+        let records = read_records(&path);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["message"], "loud");
+    }
+
+    #[test]
+    fn test_write_rotates_by_size() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("coordinator.log");
+        let record = LogRecord::info(LogComponent::Coordinator, "0123456789");
+        let line_len = record.to_json_line().len() as u64 + 1;
+        let logger = Logger {
+            max_bytes: line_len * 2,
+            max_files: 2,
+            ..Logger::default()
+        };
+
+        for _ in 0..7 {
+            logger.write(&path, &record).unwrap();
+        }
+
+        // Two records per file: 7 = 1 current + 2 + 2 rotated, the oldest 2 dropped
+        assert_eq!(read_records(&path).len(), 1);
+        assert_eq!(read_records(&rotated_path(&path, 1)).len(), 2);
+        assert_eq!(read_records(&rotated_path(&path, 2)).len(), 2);
+        assert!(!rotated_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_rotation_without_kept_files_starts_afresh() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("coordinator.log");
+        let logger = Logger {
+            max_bytes: 1,
+            max_files: 0,
+            ..Logger::default()
+        };
+
+        for message in ["first", "second"].iter() {
+            logger
+                .write(&path, &LogRecord::info(LogComponent::Coordinator, message))
+                .unwrap();
+        }
+
+        let records = read_records(&path);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["message"], "second");
+        assert!(!rotated_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_rotate_if_full() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("commander.log");
+        let logger = Logger {
+            max_bytes: 10,
+            ..Logger::default()
+        };
+
+        // A missing or small log is left alone
+        assert!(!logger.rotate_if_full(&path).unwrap());
+        fs::write(&path, "[1] short\n").unwrap();
+        assert!(!logger.rotate_if_full(&path).unwrap());
+
+        fs::write(&path, "[1] grew past the limit\n").unwrap();
+        assert!(logger.rotate_if_full(&path).unwrap());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+            "[1] grew past the limit\n"
+        );
+    }
+
+    #[test]
+    fn test_rotated_path() {
+        assert_eq!(
+            rotated_path(Path::new("/host/.zzz/task-1/logs/coordinator.log"), 3),
+            PathBuf::from("/host/.zzz/task-1/logs/coordinator.log.3")
+        );
+    }
+}
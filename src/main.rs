@@ -1,56 +1,115 @@
-mod communication;
-mod coordination_message;
-mod file_system;
-mod litellm_config;
-mod notification;
-mod pane_role;
-mod workflow_phase;
-mod zellij_service;
-
-use communication::{
-    Communication, CommunicationError, MessageEnvelope, MessageRouter, ParsedMessage,
-};
-use coordination_message::CoordinationMessage;
-use file_system::{FileSystem, FileSystemError};
-use litellm_config::LiteLLMConfig;
-use notification::Notification;
-use notify::Watcher;
-use pane_role::PaneRole;
-use std::collections::BTreeMap;
-use workflow_phase::WorkflowPhase;
-use zellij_service::ZellijServiceImpl;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use zellij_tile::prelude::*;
+use zzz::assistant::AssistantConfig;
+use zzz::communication::envelope::COORDINATOR_SENDER;
+use zzz::communication::{
+    Communication, CommunicationError, HealthEvent, HealthTracker, MessageEnvelope, MessageRouter,
+    PaneSelectors, PaneSpawner, ParsedMessage, Recipient, Recovery, RetryPolicy,
+};
+use zzz::control::{
+    self, ControlCommand, ControlError, ControlReply, WorkflowStatus, CONTROL_PIPE,
+};
+use zzz::coordination_message::CoordinationMessage;
+use zzz::dashboard::{Dashboard, DashboardData, PaneStatus, MIN_DASHBOARD_ROWS};
+use zzz::file_system::{FileSystem, FileSystemError};
+use zzz::file_watcher::monitor::DEFAULT_DEBOUNCE;
+use zzz::file_watcher::polling::DEFAULT_POLL_INTERVAL_SECS;
+use zzz::file_watcher::{PollingWatcher, TaskFileMonitor};
+use zzz::litellm_config::LiteLLMConfig;
+use zzz::logging::{LogComponent, LogRecord, Logger};
+use zzz::pane_role::PaneRole;
+use zzz::persistence::{CoordinatorSnapshot, MAX_RECEIVED_MESSAGES};
+use zzz::review::{Review, DEFAULT_MAX_REVIEW_ROUNDS};
+use zzz::secrets::{
+    self, ApiKeySource, Redactor, ENV_FILE_ROLE_CONTEXT_KEY, ENV_FILE_TASK_CONTEXT_KEY,
+    PRIVATE_CONTENT_VARIABLE,
+};
+use zzz::status_bar::{LastMessage, StatusBar};
+use zzz::task_id::{TaskId, TaskIdError};
+use zzz::task_registry::TaskRegistry;
+use zzz::todo_list::TodoList;
+use zzz::workflow::{
+    commands, ActionExecutor, DispatchReport, FailedWork, GuardContext, PromptKind, TaskContext,
+    TransitionError, WorkflowAction, WorkflowEvent, WorkflowMachine,
+};
+use zzz::workflow_phase::WorkflowPhase;
+use zzz::zellij_service::ZellijServiceImpl;
 
 struct State {
-    task_id: u32,
+    task_id: TaskId,
+    /// Why the configured `task_id` was rejected; no task is opened and no
+    /// workflow event is fired until a valid one is set
+    task_id_error: Option<TaskIdError>,
+    /// Task files under the configured `base_directory`
+    file_system: FileSystem,
+    /// Level filter and rotation for the coordinator log
+    logger: Logger,
     task_description: String,
-    current_phase: WorkflowPhase,
-    file_watcher: Option<Box<dyn Watcher>>,
-    pending_notifications: Vec<Notification>,
-    received_messages: Vec<CoordinationMessage>,
-    last_message: Option<String>,
+    workflow: WorkflowMachine,
+    file_monitor: TaskFileMonitor<PollingWatcher>,
+    todo_list: Option<TodoList>,
+    /// Last review.md read, for the dashboard
+    review: Option<Review>,
+    /// Newest messages received from other panes, for the snapshot
+    received_messages: VecDeque<CoordinationMessage>,
+    /// Messages received from other panes since the task was opened
+    message_count: usize,
+    last_message: Option<LastMessage>,
     message_router: MessageRouter<ZellijServiceImpl>,
     communication: Communication<ZellijServiceImpl>,
     permissions_granted: bool,
     pane_manifest: Option<PaneManifest>,
     litellm_config: LiteLLMConfig,
+    max_review_rounds: u32,
+    assistants: AssistantConfig,
+    redactor: Redactor,
+    /// Roles whose API key env file could not be written; the workflow
+    /// does not start without them
+    missing_env_files: Vec<PaneRole>,
+    failed_work: Vec<FailedWork>,
+    /// Assistant commands to send again once their re-run pane is back
+    pending_relaunches: HashMap<PaneRole, PromptKind>,
+    /// Automatic transitions are held back until a `resume` command
+    paused: bool,
+    /// The workflow was abandoned with an `abort` command
+    aborted: bool,
+    /// The task description was replaced with `set-description`
+    description_from_cli: bool,
+    dashboard: Dashboard,
 }
 
 impl Default for State {
     fn default() -> Self {
         Self {
-            task_id: 0,
+            task_id: TaskId::default(),
+            task_id_error: None,
+            file_system: FileSystem::default(),
+            logger: Logger::default(),
             task_description: "Default task description".to_string(),
-            current_phase: WorkflowPhase::Initializing,
-            file_watcher: None,
-            pending_notifications: Vec::new(),
-            received_messages: Vec::new(),
+            workflow: WorkflowMachine::new(),
+            file_monitor: TaskFileMonitor::new(PollingWatcher::new(), DEFAULT_DEBOUNCE),
+            todo_list: None,
+            review: None,
+            received_messages: VecDeque::new(),
+            message_count: 0,
             last_message: None,
             message_router: MessageRouter::new(ZellijServiceImpl),
             communication: Communication::new(ZellijServiceImpl),
             permissions_granted: false,
             pane_manifest: None,
             litellm_config: LiteLLMConfig::default(),
+            max_review_rounds: DEFAULT_MAX_REVIEW_ROUNDS,
+            assistants: AssistantConfig::default(),
+            redactor: Redactor::default(),
+            missing_env_files: Vec::new(),
+            failed_work: Vec::new(),
+            pending_relaunches: HashMap::new(),
+            paused: false,
+            aborted: false,
+            description_from_cli: false,
+            dashboard: Dashboard::new(),
         }
     }
 }
@@ -58,48 +117,37 @@ impl Default for State {
 impl State {
     /// Sets up the complete directory structure for the current task
     fn setup_task_directories(&self) -> Result<std::path::PathBuf, std::io::Error> {
-        FileSystem::setup_task_directories(self.task_id)
+        self.file_system.setup_task_directories(&self.task_id)
     }
 
     /// Gets the path to the todo-list.md file for the current task
     fn get_todo_list_path(&self) -> std::path::PathBuf {
-        FileSystem::get_todo_list_path(self.task_id)
+        self.file_system.get_todo_list_path(&self.task_id)
     }
 
     /// Gets the path to the review.md file for the current task
     fn get_review_path(&self) -> std::path::PathBuf {
-        FileSystem::get_review_path(self.task_id)
-    }
-
-    /// Gets the path to the plan.md file for the current task
-    fn get_plan_path(&self) -> std::path::PathBuf {
-        FileSystem::get_plan_path(self.task_id)
-    }
-
-    /// Gets the path to the logs directory for the current task
-    fn get_logs_dir_path(&self) -> std::path::PathBuf {
-        FileSystem::get_logs_dir_path(self.task_id)
+        self.file_system.get_review_path(&self.task_id)
     }
 
     /// Gets the path to the overseer.log file for the current task
     fn get_overseer_log_path(&self) -> std::path::PathBuf {
-        FileSystem::get_overseer_log_path(self.task_id)
+        self.file_system.get_overseer_log_path(&self.task_id)
     }
 
     /// Gets the path to the commander.log file for the current task
     fn get_commander_log_path(&self) -> std::path::PathBuf {
-        FileSystem::get_commander_log_path(self.task_id)
+        self.file_system.get_commander_log_path(&self.task_id)
     }
 
     /// Gets the path to the coordinator.log file for the current task
     fn get_coordinator_log_path(&self) -> std::path::PathBuf {
-        FileSystem::get_coordinator_log_path(self.task_id)
+        self.file_system.get_coordinator_log_path(&self.task_id)
     }
 
-    /// Atomically writes content to the todo-list.md file
-    fn write_todo_list(&self, content: &str) -> Result<(), FileSystemError> {
-        let path = self.get_todo_list_path();
-        FileSystem::write_file_atomic(path, content)
+    /// Gets the path to the persisted state.json file for the current task
+    fn get_state_path(&self) -> std::path::PathBuf {
+        self.file_system.get_state_path(&self.task_id)
     }
 
     /// Safely reads the todo-list.md file content
@@ -108,39 +156,31 @@ impl State {
         FileSystem::read_file_safe(path)
     }
 
-    /// Atomically writes content to the review.md file
-    fn write_review(&self, content: &str) -> Result<(), FileSystemError> {
-        let path = self.get_review_path();
-        FileSystem::write_file_atomic(path, content)
-    }
-
     /// Safely reads the review.md file content
     fn read_review(&self) -> Result<String, FileSystemError> {
         let path = self.get_review_path();
         FileSystem::read_file_safe(path)
     }
 
-    /// Atomically writes content to the plan.md file
-    fn write_plan(&self, content: &str) -> Result<(), FileSystemError> {
-        let path = self.get_plan_path();
-        FileSystem::write_file_atomic(path, content)
-    }
-
-    /// Safely reads the plan.md file content
-    fn read_plan(&self) -> Result<String, FileSystemError> {
-        let path = self.get_plan_path();
-        FileSystem::read_file_safe(path)
+    /// Write a record to the coordinator log, tagged with the task and the
+    /// current phase and with secrets masked
+    fn log(&self, record: LogRecord) {
+        // Without a valid task ID there is no task directory to log into
+        if self.task_id_error.is_some() {
+            return;
+        }
+        let phase = record.phase.unwrap_or_else(|| self.workflow.phase());
+        let record = record.phase(phase).redacted(&self.redactor);
+        let _ = self
+            .file_system
+            .log_coordinator(&self.task_id, &self.logger, record);
     }
 
-    /// Appends a log entry to the coordinator log
-    fn log_coordinator(&self, message: &str) -> Result<(), FileSystemError> {
-        let path = self.get_coordinator_log_path();
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        let log_entry = format!("[{}] {}\n", timestamp, message);
-        FileSystem::append_to_file(path, &log_entry)
+    /// Show a message in the status bar and the dashboard's log, with secrets masked
+    fn show_message(&mut self, display: String, summary: LastMessage) {
+        self.dashboard
+            .record(now_secs(), &self.redactor.redact(&display));
+        self.last_message = Some(summary);
     }
 
     /// Checks if the todo-list.md file exists
@@ -169,48 +209,383 @@ impl State {
         Ok(())
     }
 
-    // === Communication Methods ===
-
-    /// Send a coordination message to a specific pane by title
+    /// Write the env files the assistants source their API key from
     ///
-    /// # Arguments
-    /// * `message` - The coordination message to send
-    /// * `target_pane_title` - Title of the target pane (e.g., "Overseer", "Commander")
+    /// An env file holding the key itself must be private. Where the plugin
+    /// cannot set file modes, as under WASI, the host writes it instead and
+    /// the role counts as missing until the host reports success. An env file
+    /// that reads the key file holds no secret and is written directly.
+    fn write_secret_env_files(&mut self) {
+        let holds_key = matches!(
+            ApiKeySource::from_config(&self.litellm_config),
+            Some(ApiKeySource::Inline(_))
+        );
+        let task = self.task_context();
+
+        let mut missing = Vec::new();
+        let mut pending = Vec::new();
+        for role in [PaneRole::Overseer, PaneRole::Commander].iter() {
+            let content = match commands::build_secret_env(&task, *role) {
+                Some(content) => content,
+                None => continue,
+            };
+
+            let path = self
+                .file_system
+                .get_secret_env_path(&self.task_id, role.config_key());
+            let result = match FileSystem::write_private_file_atomic(&path, &content) {
+                Err(FileSystemError::PrivateFilesUnsupported) if holds_key => {
+                    let pane_path = commands::relative_secret_env_path(&task, *role);
+                    self.write_env_file_on_host(*role, &pane_path, &content);
+                    pending.push(*role);
+                    continue;
+                }
+                Err(FileSystemError::PrivateFilesUnsupported) => {
+                    FileSystem::write_file_atomic(&path, &content)
+                }
+                result => result,
+            };
+            match result {
+                Ok(()) => self.log(
+                    LogRecord::info(LogComponent::Files, "Wrote env file")
+                        .field("role", role)
+                        .field("path", path.display().to_string()),
+                ),
+                Err(e) => {
+                    self.log(
+                        LogRecord::error(LogComponent::Files, "Failed to write env file")
+                            .field("role", role)
+                            .field("path", path.display().to_string())
+                            .field("error", format!("{:?}", e)),
+                    );
+                    missing.push(*role);
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            self.report_missing_env_files(&missing);
+        }
+        missing.extend(pending);
+        self.missing_env_files = missing;
+    }
+
+    /// Have the host write a role's env file, readable by the owner only
     ///
-    /// # Returns
-    /// * `Ok(())` if message was sent successfully
-    /// * `Err(CommunicationError)` if sending failed
-    fn send_coordination_message(
-        &self,
-        message: CoordinationMessage,
-        target_pane_title: &str,
-    ) -> Result<(), CommunicationError> {
-        // Create envelope with target pane
-        let envelope =
-            MessageEnvelope::new_targeted(message.clone(), target_pane_title, "zzz-coordinator");
+    /// The key is passed in the command's environment rather than its
+    /// arguments. The outcome arrives as a `RunCommandResult` event, handled
+    /// by `env_file_written`; until permissions are granted nothing is run and
+    /// the files are written once they are.
+    fn write_env_file_on_host(&self, role: PaneRole, pane_path: &str, content: &str) {
+        if !self.permissions_granted {
+            return;
+        }
 
-        // Log the outgoing message
-        let log_msg = format!("Sending message to '{}': {:?}", target_pane_title, message);
-        let _ = self.log_coordinator(&log_msg);
+        let command = secrets::private_file_command(pane_path);
+        let command: Vec<&str> = command.iter().map(String::as_str).collect();
+        let env = BTreeMap::from([(PRIVATE_CONTENT_VARIABLE.to_string(), content.to_string())]);
+        let context = BTreeMap::from([
+            (
+                ENV_FILE_ROLE_CONTEXT_KEY.to_string(),
+                role.config_key().to_string(),
+            ),
+            (
+                ENV_FILE_TASK_CONTEXT_KEY.to_string(),
+                self.task_id.directory_name(),
+            ),
+        ]);
+        run_command_with_env_variables_and_cwd(&command, env, PathBuf::from("."), context);
+        self.log(
+            LogRecord::info(LogComponent::Files, "Writing env file on the host")
+                .field("role", role)
+                .field("path", pane_path),
+        );
+    }
 
-        // Send the message
-        match self.communication.send_pipe_message(&envelope) {
-            Ok(()) => {
-                let success_msg = format!(
-                    "Successfully sent message to '{}': {:?}",
-                    target_pane_title, message
+    /// Record the outcome of an env file written by `write_env_file_on_host`
+    fn env_file_written(
+        &mut self,
+        context: &BTreeMap<String, String>,
+        exit_code: Option<i32>,
+        stderr: &[u8],
+    ) {
+        let role = match context
+            .get(ENV_FILE_ROLE_CONTEXT_KEY)
+            .and_then(|key| PaneRole::from_config_key(key))
+        {
+            Some(role) => role,
+            None => return,
+        };
+        // Results for another task, or for a file already written, are stale
+        if context.get(ENV_FILE_TASK_CONTEXT_KEY) != Some(&self.task_id.directory_name())
+            || !self.missing_env_files.contains(&role)
+        {
+            return;
+        }
+
+        if exit_code == Some(0) {
+            self.missing_env_files.retain(|missing| *missing != role);
+            self.log(LogRecord::info(LogComponent::Files, "Wrote env file").field("role", role));
+        } else {
+            self.log(
+                LogRecord::error(LogComponent::Files, "Failed to write env file")
+                    .field("role", role)
+                    .field("exit_code", exit_code)
+                    .field("stderr", String::from_utf8_lossy(stderr).trim()),
+            );
+            self.report_missing_env_files(&[role]);
+        }
+    }
+
+    /// Tell the operator the workflow will not start without these env files
+    fn report_missing_env_files(&mut self, roles: &[PaneRole]) {
+        let warning = format!(
+            "Not starting: could not write a private env file for {:?}; set api_key_file or check the coordinator log",
+            roles
+        );
+        self.show_message(
+            warning,
+            LastMessage::Notice("API key not written".to_string()),
+        );
+    }
+
+    // === Task Methods ===
+
+    /// Rename the task's directory if it was created when task IDs were numbers
+    fn migrate_task_directory(&self) {
+        match self
+            .file_system
+            .migrate_numeric_task_directory(&self.task_id)
+        {
+            Ok(true) => self.log(
+                LogRecord::info(LogComponent::Files, "Migrated numeric task directory")
+                    .field("directory", self.task_id.directory_name()),
+            ),
+            Ok(false) => {}
+            Err(e) => self.log(
+                LogRecord::warn(
+                    LogComponent::Files,
+                    "Failed to migrate numeric task directory",
+                )
+                .field("error", e.to_string()),
+            ),
+        }
+    }
+
+    /// Set up the active task's files and pick up where it was left off
+    fn open_task(&mut self) {
+        // Initialize task directories
+        match self.ensure_task_files_exist() {
+            Ok(()) => self.log(LogRecord::info(
+                LogComponent::Files,
+                "Created task directories",
+            )),
+            Err(e) => {
+                self.log(
+                    LogRecord::error(LogComponent::Files, "Failed to create task directories")
+                        .field("error", format!("{:?}", e)),
                 );
-                let _ = self.log_coordinator(&success_msg);
-                Ok(())
+                // Note: Plugin continues to run even if directory creation fails
+                // This allows the UI to show the error state
             }
-            Err(e) => {
-                let error_msg = format!("Failed to send message to '{}': {}", target_pane_title, e);
-                let _ = self.log_coordinator(&error_msg);
-                Err(e)
+        }
+
+        // Give each assistant its API key through a private env file
+        self.write_secret_env_files();
+
+        // Resume where a previous instance of the plugin left off
+        self.restore_snapshot();
+        self.update_task_index();
+
+        // The dashboard shows the task files from the start
+        self.load_task_file_caches();
+
+        // Poll todo-list.md and review.md; notify is unavailable under WASI
+        self.watch_task_files();
+
+        // Files already on disk are the watcher's baseline, so check them directly
+        self.resume_from_task_files();
+    }
+
+    /// Every task recorded under the task root, logging entries that cannot be read
+    fn task_index(&self) -> Result<TaskRegistry, ControlError> {
+        let (registry, skipped) = TaskRegistry::load_all(&self.file_system)
+            .map_err(|e| ControlError::Registry(e.to_string()))?;
+        for (path, e) in skipped {
+            self.log(
+                LogRecord::warn(LogComponent::Files, "Skipped unreadable task entry")
+                    .field("path", path.display().to_string())
+                    .field("error", e.to_string()),
+            );
+        }
+        Ok(registry)
+    }
+
+    /// Record the active task's description and phase in its registry entry
+    fn update_task_index(&self) {
+        if let Err(e) = TaskRegistry::update(
+            &self.file_system,
+            &self.task_id,
+            &self.task_description,
+            self.workflow.phase(),
+            now_secs(),
+        ) {
+            self.log(
+                LogRecord::error(LogComponent::Files, "Failed to update the task index")
+                    .field("error", e.to_string()),
+            );
+        }
+    }
+
+    /// Make another task the active one, leaving the current one resumable
+    ///
+    /// A task missing from the index needs a description to start from; a
+    /// known task keeps its own.
+    fn switch_task(
+        &mut self,
+        task_id: &TaskId,
+        description: Option<&str>,
+    ) -> Result<Option<String>, ControlError> {
+        if self.task_id_error.is_none() && task_id.is_same_task(&self.task_id) {
+            return Ok(Some(format!("Task {} is already active", self.task_id)));
+        }
+        if let Some((role, _)) = self.workflow.in_flight() {
+            if !self.paused && !self.aborted {
+                return Err(ControlError::TaskBusy(role));
             }
         }
+
+        let known_description = self
+            .task_index()?
+            .get(task_id)
+            .map(|entry| entry.description.clone());
+        let new_task = known_description.is_none();
+        let task_description = known_description
+            .or_else(|| description.map(str::to_string))
+            .ok_or_else(|| ControlError::UnknownTask(task_id.clone()))?;
+
+        self.save_snapshot();
+        for path in [self.get_todo_list_path(), self.get_review_path()].iter() {
+            let _ = self.file_monitor.unwatch(path);
+        }
+
+        let previous = std::mem::replace(&mut self.task_id, task_id.clone());
+        self.task_id_error = None;
+        self.task_description = task_description;
+        self.description_from_cli = new_task;
+        self.workflow = WorkflowMachine::new();
+        self.received_messages.clear();
+        self.message_count = 0;
+        self.failed_work.clear();
+        self.pending_relaunches.clear();
+        self.message_router.reset_restarts();
+        self.paused = false;
+        self.aborted = false;
+        self.todo_list = None;
+        self.review = None;
+
+        self.migrate_task_directory();
+        self.log(
+            LogRecord::info(LogComponent::Control, "Switched task")
+                .field("previous_task_id", &previous),
+        );
+        self.open_task();
+
+        if self.permissions_granted && !self.get_registered_roles().is_empty() {
+            self.resume_workflow();
+        }
+
+        Ok(Some(format!(
+            "Switched from task {} to task {} in {:?}",
+            previous,
+            self.task_id,
+            self.workflow.phase()
+        )))
     }
 
+    // === Persistence Methods ===
+
+    /// Capture the current coordinator state as a versioned snapshot
+    fn snapshot(&self) -> CoordinatorSnapshot {
+        let pane_registry = self
+            .get_registered_roles()
+            .into_iter()
+            .filter_map(|role| {
+                self.message_router
+                    .get_pane_id(&role)
+                    .map(|pane_id| (role, pane_id))
+            })
+            .collect();
+
+        CoordinatorSnapshot {
+            failed_work: self.failed_work.clone(),
+            paused: self.paused,
+            aborted: self.aborted,
+            task_description: if self.description_from_cli {
+                Some(self.task_description.clone())
+            } else {
+                None
+            },
+            ..CoordinatorSnapshot::new(
+                self.task_id.clone(),
+                self.workflow.phase(),
+                self.workflow.history().to_vec(),
+                self.received_messages.iter().cloned().collect(),
+                pane_registry,
+            )
+        }
+    }
+
+    /// Persist the coordinator state so a plugin reload can resume it
+    fn save_snapshot(&self) {
+        if self.task_id_error.is_some() {
+            return;
+        }
+        if let Err(e) = self.snapshot().save(self.get_state_path()) {
+            self.log(
+                LogRecord::error(LogComponent::Files, "Failed to persist coordinator state")
+                    .field("error", e.to_string()),
+            );
+        }
+        self.update_task_index();
+    }
+
+    /// Restore coordinator state persisted by a previous plugin instance
+    fn restore_snapshot(&mut self) {
+        match CoordinatorSnapshot::load(self.get_state_path(), &self.task_id) {
+            Ok(Some(snapshot)) => {
+                self.workflow = WorkflowMachine::restore(snapshot.phase, snapshot.history);
+                self.message_count = snapshot.received_messages.len();
+                self.received_messages = snapshot.received_messages.into();
+                self.failed_work = snapshot.failed_work;
+                self.paused = snapshot.paused;
+                self.aborted = snapshot.aborted;
+                if let Some(task_description) = snapshot.task_description {
+                    self.task_description = task_description;
+                    self.description_from_cli = true;
+                }
+                for (role, pane_id) in snapshot.pane_registry {
+                    self.message_router.register_pane(role, pane_id);
+                }
+
+                self.log(
+                    LogRecord::info(LogComponent::Files, "Restored coordinator state")
+                        .field("saved_at", snapshot.saved_at),
+                );
+            }
+            Ok(None) => self.log(LogRecord::info(
+                LogComponent::Files,
+                "No persisted coordinator state, starting fresh",
+            )),
+            Err(e) => self.log(
+                LogRecord::warn(LogComponent::Files, "Ignoring persisted coordinator state")
+                    .field("error", e.to_string()),
+            ),
+        }
+    }
+
+    // === Communication Methods ===
+
     /// Broadcast a coordination message to all listening panes
     ///
     /// # Arguments
@@ -224,22 +599,23 @@ impl State {
         message: CoordinationMessage,
     ) -> Result<(), CommunicationError> {
         // Create envelope for broadcasting
-        let envelope = MessageEnvelope::new_broadcast(message.clone(), "zzz-coordinator");
-
-        // Log the outgoing message
-        let log_msg = format!("Broadcasting message: {:?}", message);
-        let _ = self.log_coordinator(&log_msg);
+        let envelope = MessageEnvelope::new_broadcast(message.clone(), COORDINATOR_SENDER);
 
         // Send the message
         match self.communication.send_pipe_message(&envelope) {
             Ok(()) => {
-                let success_msg = format!("Successfully broadcast message: {:?}", message);
-                let _ = self.log_coordinator(&success_msg);
+                self.log(
+                    LogRecord::info(LogComponent::Messaging, "Broadcast message")
+                        .field("message", &message),
+                );
                 Ok(())
             }
             Err(e) => {
-                let error_msg = format!("Failed to broadcast message: {}", e);
-                let _ = self.log_coordinator(&error_msg);
+                self.log(
+                    LogRecord::error(LogComponent::Messaging, "Failed to broadcast message")
+                        .field("message", &message)
+                        .field("error", e.to_string()),
+                );
                 Err(e)
             }
         }
@@ -271,13 +647,35 @@ impl State {
 
     /// Handle a message in the modern envelope format
     fn handle_envelope_message(&mut self, envelope: MessageEnvelope, source: &str) -> bool {
-        let message = &envelope.coordination_message;
+        // Our own envelopes echo back on the coordination pipe and must not be
+        // acknowledged, or settle our own outstanding messages
+        let from_peer = envelope.sender != COORDINATOR_SENDER;
+        if from_peer {
+            self.communication
+                .record_peer_version(&envelope.sender, envelope.protocol_version);
+        }
+
+        if let CoordinationMessage::Ack { message_id } = envelope.coordination_message {
+            if from_peer {
+                self.handle_ack(message_id, source);
+            }
+            return false;
+        }
+
+        // Only the addressee acknowledges; the coordinator merely observes
+        // envelopes meant for other panes
+        let for_coordinator = envelope.is_targeted_to(&self.coordinator_recipient());
+        if let Some(message_id) = envelope.ack_id().filter(|_| from_peer && for_coordinator) {
+            self.send_ack(message_id, &envelope.sender);
+        }
 
-        // Store the coordination message
-        self.received_messages.push(message.clone());
+        let message = &envelope.coordination_message;
+        if from_peer {
+            self.record_received_message(message.clone());
+        }
 
         // Create display message with envelope info
-        let display = if let Some(ref target) = envelope.target_pane {
+        let display = if let Some(ref target) = envelope.target {
             format!(
                 "Envelope from {} → {}: {:?} (sent by {} at {})",
                 source, target, message, envelope.sender, envelope.timestamp
@@ -289,159 +687,351 @@ impl State {
             )
         };
 
-        self.last_message = Some(display.clone());
-
-        // Log the received envelope
-        let log_msg = format!(
-            "Received envelope from {}: target={:?}, sender={}, message={:?}",
-            source, envelope.target_pane, envelope.sender, message
+        let summary = LastMessage::Coordination {
+            name: message.name().to_string(),
+            target: envelope.target.as_ref().map(|target| target.to_string()),
+        };
+        self.show_message(display, summary);
+
+        self.log(
+            LogRecord::info(LogComponent::Messaging, "Received envelope")
+                .message_id(envelope.message_id)
+                .field("source", source)
+                .field("sender", &envelope.sender)
+                .field("target", envelope.target.as_ref().map(|t| t.to_string()))
+                .field("message", message),
         );
-        let _ = self.log_coordinator(&log_msg);
+
+        // Only other senders drive the workflow
+        if from_peer {
+            self.apply_message_to_workflow(&envelope.coordination_message);
+        }
 
         true // trigger re-render
     }
 
+    /// Remember a message from another pane, dropping the oldest beyond `MAX_RECEIVED_MESSAGES`
+    fn record_received_message(&mut self, message: CoordinationMessage) {
+        self.message_count += 1;
+        self.received_messages.push_back(message);
+        while self.received_messages.len() > MAX_RECEIVED_MESSAGES {
+            self.received_messages.pop_front();
+        }
+    }
+
+    /// Settle an outstanding message acknowledged by its receiver
+    fn handle_ack(&mut self, message_id: u64, source: &str) {
+        let record = if self.communication.acknowledge(message_id) {
+            LogRecord::info(LogComponent::Messaging, "Message acknowledged")
+        } else {
+            LogRecord::warn(
+                LogComponent::Messaging,
+                "Ignoring acknowledgement for unknown message",
+            )
+        };
+        self.log(record.message_id(Some(message_id)).field("source", source));
+    }
+
+    /// How the coordinator can be addressed by envelopes
+    fn coordinator_recipient(&self) -> Recipient {
+        Recipient {
+            name: COORDINATOR_SENDER.to_string(),
+            pane_id: self.message_router.own_plugin_id().map(PaneId::Plugin),
+            role: None,
+            title: None,
+        }
+    }
+
+    /// Acknowledge a received envelope to its sender
+    fn send_ack(&self, message_id: u64, sender: &str) {
+        let ack = MessageEnvelope::new_ack(message_id, sender, COORDINATOR_SENDER);
+        let result = self
+            .message_router
+            .resolve_target(ack.target.as_ref())
+            .and_then(|transport| self.communication.send(&ack, transport));
+        if let Err(e) = result {
+            self.log(
+                LogRecord::error(LogComponent::Messaging, "Failed to acknowledge message")
+                    .message_id(Some(message_id))
+                    .field("sender", sender)
+                    .field("error", e.to_string()),
+            );
+        }
+    }
+
+    /// Rotate the assistant transcripts that grew past the log size limit
+    ///
+    /// The capture pipeline reopens the log for every line, so it carries on
+    /// in a fresh file.
+    fn rotate_transcripts(&self) {
+        for path in [
+            self.file_system.get_overseer_log_path(&self.task_id),
+            self.file_system.get_commander_log_path(&self.task_id),
+        ]
+        .iter()
+        {
+            if let Err(e) = self.logger.rotate_if_full(path) {
+                self.log(
+                    LogRecord::error(LogComponent::Files, "Failed to rotate transcript")
+                        .field("path", path.display().to_string())
+                        .field("error", e.to_string()),
+                );
+            }
+        }
+    }
+
+    /// Resend unacknowledged messages whose timeout expired and dead-letter exhausted ones
+    fn process_delivery_timeouts(&mut self) {
+        let tick = self.communication.process_timeouts(Instant::now());
+
+        for (envelope, _) in &tick.retries {
+            self.log(
+                LogRecord::warn(LogComponent::Messaging, "No acknowledgement, resending")
+                    .message_id(envelope.message_id)
+                    .field("target", envelope.target.as_ref().map(|t| t.to_string())),
+            );
+        }
+
+        for dead_letter in &tick.dead_letters {
+            self.log(
+                LogRecord::error(LogComponent::Messaging, &dead_letter.error().to_string())
+                    .message_id(dead_letter.envelope.message_id)
+                    .field("attempts", dead_letter.attempts),
+            );
+
+            if let Err(e) = self.file_system.log_dead_letter(
+                &self.task_id,
+                &self.logger,
+                dead_letter.log_record().redacted(&self.redactor),
+            ) {
+                self.log(
+                    LogRecord::error(LogComponent::Files, "Failed to write dead letter")
+                        .field("error", format!("{:?}", e)),
+                );
+            }
+        }
+    }
+
     /// Handle a message in the legacy direct CoordinationMessage format
     fn handle_legacy_message(&mut self, message: CoordinationMessage, source: &str) -> bool {
-        self.received_messages.push(message.clone());
-        self.last_message = Some(format!("Legacy from {}: {:?}", source, message));
+        self.record_received_message(message.clone());
+        self.show_message(
+            format!("Legacy from {}: {:?}", source, message),
+            LastMessage::Coordination {
+                name: message.name().to_string(),
+                target: None,
+            },
+        );
+
+        self.log(
+            LogRecord::info(LogComponent::Messaging, "Received legacy message")
+                .field("source", source)
+                .field("message", &message),
+        );
 
-        // Log the legacy message
-        let log_msg = format!("Received legacy message from {}: {:?}", source, message);
-        let _ = self.log_coordinator(&log_msg);
+        self.apply_message_to_workflow(&message);
 
         true // trigger re-render
     }
 
     /// Handle a raw text message that couldn't be parsed as JSON
     fn handle_raw_message(&mut self, payload: &str, source: &str) -> bool {
-        self.last_message = Some(format!("Raw from {}: {}", source, payload));
+        self.show_message(
+            format!("Raw from {}: {}", source, payload),
+            LastMessage::Raw {
+                source: source.to_string(),
+            },
+        );
 
-        // Log the raw message
-        let log_msg = format!("Received raw message from {}: {}", source, payload);
-        let _ = self.log_coordinator(&log_msg);
+        self.log(
+            LogRecord::info(LogComponent::Messaging, "Received raw message")
+                .field("source", source)
+                .field("payload", payload),
+        );
 
         true // trigger re-render
     }
 
     // === Message Routing Methods ===
 
-    /// Send a coordination message to a specific pane role using the router
-    fn route_message_to_role(
-        &self,
-        message: CoordinationMessage,
-        target_role: PaneRole,
-    ) -> Result<(), CommunicationError> {
-        match self
-            .message_router
-            .route_message_to_role(&message, target_role)
-        {
-            Ok(()) => {
-                let log_msg = format!(
-                    "Successfully routed message to {:?}: {:?}",
-                    target_role, message
-                );
-                let _ = self.log_coordinator(&log_msg);
-                Ok(())
-            }
-            Err(CommunicationError::PaneNotFound(role)) => {
-                let error_msg = format!(
-                    "Pane not found for role {:?} when trying to send message: {:?}",
-                    role, message
-                );
-                let _ = self.log_coordinator(&error_msg);
-                Err(CommunicationError::PaneNotFound(role))
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to route message to {:?}: {}", target_role, e);
-                let _ = self.log_coordinator(&error_msg);
-                Err(e)
-            }
-        }
-    }
-
-    /// Send a coordination message to multiple pane roles
-    fn route_message_to_roles(
-        &self,
-        message: CoordinationMessage,
-        target_roles: &[PaneRole],
-    ) -> Vec<(PaneRole, Result<(), CommunicationError>)> {
-        let results = self
-            .message_router
-            .route_message_to_roles(&message, target_roles);
+    /// Discover and register panes based on their names/titles using current manifest
+    fn discover_and_register_panes(&mut self) {
+        if let Some(ref manifest) = self.pane_manifest {
+            match self.message_router.discover_panes_with_manifest(manifest) {
+                Ok(matches) => {
+                    let discovered_roles = self.message_router.get_registered_roles();
+                    self.log(
+                        LogRecord::debug(LogComponent::Panes, "Discovered panes")
+                            .field("tab", matches.tab)
+                            .field("roles", discovered_roles),
+                    );
 
-        // Log results
-        for (role, result) in &results {
-            match result {
-                Ok(()) => {
-                    let log_msg =
-                        format!("Successfully routed message to {:?}: {:?}", role, message);
-                    let _ = self.log_coordinator(&log_msg);
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to route message to {:?}: {}", role, e);
-                    let _ = self.log_coordinator(&error_msg);
+                    for ambiguous in &matches.ambiguous {
+                        self.log(
+                            LogRecord::warn(LogComponent::Panes, "Ambiguous pane match")
+                                .field("match", ambiguous.to_string()),
+                        );
+                    }
                 }
+                Err(e) => self.log(
+                    LogRecord::error(LogComponent::Panes, "Pane discovery failed")
+                        .field("error", e.to_string()),
+                ),
             }
+        } else {
+            self.log(LogRecord::warn(
+                LogComponent::Panes,
+                "No pane manifest available for discovery",
+            ));
         }
+    }
 
-        results
+    /// Open panes for the Overseer or Commander if the layout has none
+    fn spawn_missing_panes(&mut self) {
+        for role in self.message_router.spawn_missing_panes() {
+            let record =
+                LogRecord::info(LogComponent::Panes, "Opening missing pane").field("role", role);
+            let record = match self.message_router.spawner().spec(role) {
+                Some(spec) => record
+                    .field("name", &spec.name)
+                    .field("program", &spec.program)
+                    .field(
+                        "cwd",
+                        spec.cwd.as_ref().map(|cwd| cwd.display().to_string()),
+                    ),
+                None => record,
+            };
+            self.log(record);
+        }
     }
 
-    /// Broadcast a coordination message to all registered panes
-    fn broadcast_to_all_roles(
-        &self,
-        message: CoordinationMessage,
-    ) -> Vec<(PaneRole, Result<(), CommunicationError>)> {
-        let results = self.message_router.broadcast_to_all(&message);
+    /// Track pane exits and recoveries from the current manifest
+    ///
+    /// An exit fails the work the role had in flight and applies the role's
+    /// restart policy; a recovered pane gets its assistant command again if
+    /// the policy asked for a relaunch.
+    fn handle_pane_health(&mut self) {
+        let events = match self.pane_manifest {
+            Some(ref manifest) => self.message_router.update_health(manifest),
+            None => return,
+        };
 
-        let log_msg = format!("Broadcasting message to all roles: {:?}", message);
-        let _ = self.log_coordinator(&log_msg);
+        for event in events {
+            match event {
+                HealthEvent::Exited {
+                    role,
+                    pane_id,
+                    exit_status,
+                    held,
+                } => self.handle_pane_exit(role, pane_id, exit_status, held),
+                HealthEvent::Recovered { role, pane_id } => {
+                    self.log(
+                        LogRecord::info(LogComponent::Panes, "Pane is running again")
+                            .field("role", role)
+                            .field("pane_id", format!("{:?}", pane_id)),
+                    );
 
-        // Log individual results
-        for (role, result) in &results {
-            match result {
-                Ok(()) => {
-                    let log_msg = format!("Broadcast successful to {:?}", role);
-                    let _ = self.log_coordinator(&log_msg);
-                }
-                Err(e) => {
-                    let error_msg = format!("Broadcast failed to {:?}: {}", role, e);
-                    let _ = self.log_coordinator(&error_msg);
+                    if let Some(prompt) = self.pending_relaunches.remove(&role) {
+                        self.relaunch_assistant(role, prompt);
+                    }
                 }
             }
         }
-
-        results
     }
 
-    /// Discover and register panes based on their names/titles using current manifest
-    fn discover_and_register_panes(&mut self) {
-        let log_msg = "Attempting to discover panes...".to_string();
-        let _ = self.log_coordinator(&log_msg);
+    /// Fail the exited role's in-flight work and apply its restart policy
+    fn handle_pane_exit(
+        &mut self,
+        role: PaneRole,
+        pane_id: PaneId,
+        exit_status: Option<i32>,
+        held: bool,
+    ) {
+        self.log(
+            LogRecord::warn(LogComponent::Panes, "Pane exited")
+                .field("role", role)
+                .field("pane_id", format!("{:?}", pane_id))
+                .field("exit_status", exit_status),
+        );
 
-        if let Some(ref manifest) = self.pane_manifest {
-            match self.message_router.discover_panes_with_manifest(manifest) {
-                Ok(()) => {
-                    let discovered_roles = self.message_router.get_registered_roles();
-                    let log_msg = format!(
-                        "Pane discovery completed successfully. Found {} panes: {:?}",
-                        discovered_roles.len(),
-                        discovered_roles
-                    );
-                    let _ = self.log_coordinator(&log_msg);
+        let failure = self.workflow.fail_in_flight(role, exit_status);
+        if let Some(ref failure) = failure {
+            self.log(
+                LogRecord::error(
+                    LogComponent::Workflow,
+                    "Assistant work failed: the assistant's pane exited",
+                )
+                .phase(failure.phase)
+                .field("role", role)
+                .field("prompt", failure.prompt),
+            );
+            let _ = self.broadcast_coordination_message(CoordinationMessage::AssistantFailed {
+                role,
+                phase: failure.phase,
+                exit_status,
+            });
+            self.failed_work.push(failure.clone());
+            self.save_snapshot();
+        }
+
+        match self.message_router.recover_pane(role, pane_id, held) {
+            Recovery::RelaunchAssistant(terminal_id) => {
+                if let Some(failure) = failure {
+                    self.pending_relaunches.insert(role, failure.prompt);
                 }
-                Err(e) => {
-                    let error_msg = format!("Pane discovery failed: {}", e);
-                    let _ = self.log_coordinator(&error_msg);
+                self.log(
+                    LogRecord::info(
+                        LogComponent::Panes,
+                        "Re-running terminal pane; the assistant is relaunched once it is back",
+                    )
+                    .field("role", role)
+                    .field("terminal_id", terminal_id),
+                );
+            }
+            Recovery::RetypeAssistant(terminal_id) => {
+                self.log(
+                    LogRecord::info(
+                        LogComponent::Panes,
+                        "Pane is still at its shell; relaunching the assistant",
+                    )
+                    .field("role", role)
+                    .field("terminal_id", terminal_id),
+                );
+                if let Some(failure) = failure {
+                    self.relaunch_assistant(role, failure.prompt);
                 }
             }
-        } else {
-            let error_msg = "No pane manifest available for discovery".to_string();
-            let _ = self.log_coordinator(&error_msg);
+            Recovery::ReopenPane(terminal_id) => self.log(
+                LogRecord::info(LogComponent::Panes, "Re-running terminal pane")
+                    .field("role", role)
+                    .field("terminal_id", terminal_id),
+            ),
+            Recovery::GaveUp { restarts } => self.log(
+                LogRecord::error(LogComponent::Panes, "Not restarting pane")
+                    .field("role", role)
+                    .field("restarts", restarts),
+            ),
+            Recovery::Nothing => {}
         }
     }
 
+    /// Send the in-flight assistant command to a role's pane again
+    fn relaunch_assistant(&mut self, role: PaneRole, prompt: PromptKind) {
+        // The workflow may have moved on while the pane was down
+        if self.workflow.in_flight() != Some((role, prompt)) {
+            return;
+        }
+
+        let executor = self.executor();
+
+        let record = match executor.execute(&WorkflowAction::LaunchAssistant { role, prompt }) {
+            Ok(()) => LogRecord::info(LogComponent::Workflow, "Relaunched assistant"),
+            Err(e) => LogRecord::error(LogComponent::Workflow, "Failed to relaunch assistant")
+                .field("error", e.to_string()),
+        };
+        self.log(record.field("role", role).field("prompt", prompt));
+    }
+
     /// Get the list of registered pane roles
     fn get_registered_roles(&self) -> Vec<PaneRole> {
         self.message_router.get_registered_roles()
@@ -452,133 +1042,582 @@ impl State {
         self.message_router.is_role_registered(role)
     }
 
-    /// Transition workflow phase to PlanningInProgress with validation
-    fn transition_to_planning_in_progress(&mut self) -> Result<(), String> {
-        // Validate current state
-        if self.current_phase != WorkflowPhase::Initializing {
-            return Err(format!(
-                "Invalid state transition: can only transition to PlanningInProgress from Initializing, currently in {:?}",
-                self.current_phase
-            ));
+    /// Borrow the task's identity, configuration and files for building commands
+    fn task_context(&self) -> TaskContext<'_> {
+        TaskContext {
+            task_id: &self.task_id,
+            task_description: &self.task_description,
+            litellm_config: &self.litellm_config,
+            assistants: &self.assistants,
+            file_system: &self.file_system,
         }
+    }
 
-        // Validate prerequisites
-        if !self.permissions_granted {
-            return Err("Cannot transition to planning: permissions not granted".to_string());
+    /// Create an executor for running workflow actions of the current task
+    fn executor(&self) -> ActionExecutor<'_, ZellijServiceImpl> {
+        ActionExecutor::new(
+            &self.message_router,
+            &self.communication,
+            self.task_context(),
+        )
+    }
+
+    /// Run `f` with an executor and the workflow machine it may transition
+    ///
+    /// The executor borrows the whole state, so the machine is moved out while
+    /// `f` runs and put back afterwards.
+    fn with_executor<R>(
+        &mut self,
+        f: impl FnOnce(&ActionExecutor<'_, ZellijServiceImpl>, &mut WorkflowMachine) -> R,
+    ) -> R {
+        let mut workflow = std::mem::take(&mut self.workflow);
+        let result = f(&self.executor(), &mut workflow);
+        self.workflow = workflow;
+        result
+    }
+
+    /// Collect the facts that workflow transition guards are evaluated against
+    fn guard_context(&self) -> GuardContext {
+        let todo_list_exists = self.todo_list_exists();
+        let todo_list = if todo_list_exists {
+            self.load_valid_todo_list()
+        } else {
+            None
+        };
+
+        GuardContext {
+            permissions_granted: self.permissions_granted,
+            registered_roles: self.get_registered_roles(),
+            litellm_configured: self.litellm_config.is_configured()
+                && self.missing_env_files.is_empty(),
+            todo_list_exists,
+            todo_list_valid: todo_list.is_some(),
+            todo_list_complete: todo_list.is_some_and(|list| list.is_complete()),
+            review_exists: self.review_exists(),
+            review_has_blocking_findings: self
+                .read_review()
+                .map(|content| Review::parse(&content).has_blocking_findings())
+                .unwrap_or(false),
+            fix_rounds: self.workflow.fix_rounds(),
+            max_review_rounds: self.max_review_rounds,
         }
+    }
+
+    /// Parse and validate the todo list, logging why an existing list is rejected
+    fn load_valid_todo_list(&self) -> Option<TodoList> {
+        let content = self.read_todo_list().ok()?;
+        let todo_list = TodoList::parse(&content);
 
-        if self.get_registered_roles().is_empty() {
-            return Err("Cannot transition to planning: no panes discovered".to_string());
+        match todo_list.validate() {
+            Ok(()) => Some(todo_list),
+            Err(e) => {
+                self.log(
+                    LogRecord::debug(LogComponent::Workflow, "Todo list is not usable yet")
+                        .field("reason", e.to_string()),
+                );
+                None
+            }
         }
+    }
 
-        if self.litellm_config.api_key.is_empty() || self.litellm_config.url.is_empty() {
-            return Err(
-                "Cannot transition to planning: LiteLLM configuration incomplete".to_string(),
+    /// Fire a workflow event and run the resulting entry/exit actions
+    ///
+    /// While the workflow is paused the event is logged and dropped.
+    fn dispatch_workflow_event(&mut self, event: WorkflowEvent) {
+        if self.task_id_error.is_some() {
+            return;
+        }
+        if self.paused {
+            self.log(
+                LogRecord::info(
+                    LogComponent::Workflow,
+                    "Workflow paused, holding back event",
+                )
+                .field("event", event),
             );
+            return;
         }
 
-        // Perform the transition
-        let old_phase = self.current_phase.clone();
-        self.current_phase = WorkflowPhase::PlanningInProgress;
+        if let Err(e) = self.run_workflow_event(event) {
+            self.log(
+                LogRecord::warn(LogComponent::Workflow, "Workflow event rejected")
+                    .field("event", event)
+                    .field("error", e.to_string()),
+            );
+        }
+    }
 
-        // Log the transition
-        let log_msg = format!(
-            "State transition: {:?} → {:?} at timestamp {}",
-            old_phase,
-            self.current_phase,
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        );
-        let _ = self.log_coordinator(&log_msg);
+    /// Fire a workflow event regardless of pausing, logging and persisting the result
+    fn run_workflow_event(
+        &mut self,
+        event: WorkflowEvent,
+    ) -> Result<DispatchReport, TransitionError> {
+        let guards = self.guard_context();
+        let report =
+            self.with_executor(|executor, workflow| executor.dispatch(workflow, event, &guards))?;
+        // The workflow moved on, so panes that crashed earlier get their restarts back
+        self.message_router.reset_restarts();
+        self.record_dispatch(&report);
+        Ok(report)
+    }
 
-        Ok(())
+    /// Force the workflow into a phase, logging and persisting the result
+    fn force_workflow_phase(
+        &mut self,
+        phase: WorkflowPhase,
+        event: WorkflowEvent,
+    ) -> Result<DispatchReport, TransitionError> {
+        let guards = self.guard_context();
+        let report = self
+            .with_executor(|executor, workflow| executor.force(workflow, phase, event, &guards))?;
+        self.message_router.reset_restarts();
+        self.record_dispatch(&report);
+        Ok(report)
     }
 
-    /// Execute a command in a specific pane by role
-    fn execute_command_in_pane(
-        &self,
-        command: &str,
-        target_role: PaneRole,
-    ) -> Result<(), CommunicationError> {
-        // Log the command execution attempt
-        let log_msg = format!("Executing command in {:?} pane: {}", target_role, command);
-        let _ = self.log_coordinator(&log_msg);
+    /// Log the outcome of a dispatch and persist the new state
+    fn record_dispatch(&self, report: &DispatchReport) {
+        for change in &report.changes {
+            self.log(
+                LogRecord::info(LogComponent::Workflow, "State transition")
+                    .phase(change.to)
+                    .field("from", change.from)
+                    .field("event", change.event)
+                    .field("transitioned_at", change.timestamp),
+            );
+        }
+        for (action, e) in &report.action_failures {
+            self.log(
+                LogRecord::error(LogComponent::Workflow, "Workflow action failed")
+                    .field("action", format!("{:?}", action))
+                    .field("error", e.to_string()),
+            );
+        }
+        for e in &report.advance_errors {
+            self.log(
+                LogRecord::warn(
+                    LogComponent::Workflow,
+                    "Automatic workflow advance rejected",
+                )
+                .field("error", e.to_string()),
+            );
+        }
+
+        self.save_snapshot();
+    }
 
-        // Write the command to the target pane (with newline to execute)
-        let command_with_newline = format!("{}\n", command);
-        self.message_router
-            .execute_command_in_role(&command_with_newline, target_role)?;
+    /// Fire the event the current phase is waiting on once its panes are available
+    ///
+    /// Called on every pane update, so it only acts in phases that are blocked
+    /// on the layout rather than on an assistant.
+    fn resume_workflow(&mut self) {
+        match self.workflow.phase() {
+            WorkflowPhase::Initializing => {
+                self.dispatch_workflow_event(WorkflowEvent::StartPlanning)
+            }
+            WorkflowPhase::PlanReady if self.is_role_registered(&PaneRole::Commander) => {
+                self.dispatch_workflow_event(WorkflowEvent::StartImplementation)
+            }
+            WorkflowPhase::ImplementationComplete
+                if self.is_role_registered(&PaneRole::Overseer) =>
+            {
+                self.dispatch_workflow_event(WorkflowEvent::StartReview)
+            }
+            WorkflowPhase::ReviewComplete if self.is_role_registered(&PaneRole::Commander) => {
+                self.dispatch_workflow_event(WorkflowEvent::RequestFixes)
+            }
+            _ => {}
+        }
+    }
 
-        // Log successful execution
-        let success_msg = format!("Successfully sent command to {:?} pane", target_role);
-        let _ = self.log_coordinator(&success_msg);
+    /// Pick up task files written while no coordinator was watching
+    fn resume_from_task_files(&mut self) {
+        let event = match self.workflow.phase() {
+            WorkflowPhase::PlanningInProgress => WorkflowEvent::PlanReady,
+            WorkflowPhase::ImplementationInProgress => WorkflowEvent::AllTasksComplete,
+            WorkflowPhase::ReviewInProgress => WorkflowEvent::ReviewComplete,
+            _ => return,
+        };
 
-        Ok(())
+        self.dispatch_file_event(event);
     }
 
-    /// Build the codex command with environment variables and enhanced prompt
-    fn build_codex_command(&self) -> String {
-        let todo_list_path = format!(".zzz/task-{}/todo-list.md", self.task_id);
-
-        let prompt = format!(
-            "Create a detailed step-by-step todo list for implementing the following feature: {}\n\n\
-            Requirements:\n\
-            - Save the todo list as a markdown file at {}\n\
-            - Use checkbox format: - [ ] Task description\n\
-            - Break down complex tasks into smaller, implementable steps\n\
-            - Include testing requirements for each major feature\n\
-            - Focus on practical implementation steps\n\
-            - Each task should be specific and actionable\n\n\
-            Generate a comprehensive plan that a developer can follow step by step.",
-            self.task_description,
-            todo_list_path
-        );
+    /// Dispatch an event signalled by a task file once its guards hold
+    ///
+    /// Files are rewritten many times while an assistant works on them, so a
+    /// change that does not satisfy the guards yet is not treated as an error.
+    fn dispatch_file_event(&mut self, event: WorkflowEvent) {
+        if self.workflow.check(event, &self.guard_context()).is_ok() {
+            self.dispatch_workflow_event(event);
+        }
+    }
 
-        format!(
-            "OPENAI_BASE_URL=\"{}\" OPENAI_API_KEY=\"{}\" codex --quiet \"{}\"",
-            self.litellm_config.url,
-            self.litellm_config.api_key,
-            prompt.replace('"', "\\\"")
-        )
+    /// Feed a coordination message received from another pane into the workflow
+    fn apply_message_to_workflow(&mut self, message: &CoordinationMessage) {
+        if let Some(event) = WorkflowEvent::from_message(message) {
+            self.dispatch_workflow_event(event);
+        }
     }
 
-    /// Start the planning workflow by executing codex command in Overseer pane
-    fn start_planning_workflow(&mut self) {
-        // Generate the codex command
-        let codex_command = self.build_codex_command();
+    // === Control Methods ===
 
-        let log_msg = format!("Generated codex command: {}", codex_command);
-        let _ = self.log_coordinator(&log_msg);
+    /// Parse and run a control command, replying on the CLI pipe if it came from one
+    fn handle_control_command(&mut self, payload: &str, cli_pipe: Option<&str>) {
+        self.run_parsed_control_command(ControlCommand::parse(payload), payload, cli_pipe);
+    }
 
-        // Execute the command in the Overseer pane
-        match self.execute_command_in_pane(&codex_command, PaneRole::Overseer) {
-            Ok(()) => {
-                let success_msg =
-                    "Successfully executed codex command in Overseer pane".to_string();
-                let _ = self.log_coordinator(&success_msg);
-
-                // Transition to PlanningInProgress phase
-                match self.transition_to_planning_in_progress() {
-                    Ok(()) => {
-                        let transition_msg =
-                            "Successfully transitioned to PlanningInProgress phase".to_string();
-                        let _ = self.log_coordinator(&transition_msg);
-                    }
-                    Err(e) => {
-                        let error_msg =
-                            format!("Failed to transition to PlanningInProgress: {}", e);
-                        let _ = self.log_coordinator(&error_msg);
+    /// Run a parsed control command and reply on the CLI pipe if it came from one
+    fn run_parsed_control_command(
+        &mut self,
+        parsed: Result<ControlCommand, ControlError>,
+        payload: &str,
+        cli_pipe: Option<&str>,
+    ) {
+        let reply = match parsed {
+            Ok(command) => match self.run_control_command(&command) {
+                Ok(message) => {
+                    let reply =
+                        ControlReply::success(command.name(), message, self.workflow_status());
+                    match command {
+                        ControlCommand::Tasks => reply.with_tasks(
+                            self.task_index()
+                                .map(|registry| registry.tasks)
+                                .unwrap_or_default(),
+                        ),
+                        _ => reply,
                     }
                 }
-            }
+                Err(e) => ControlReply::failure(command.name(), &e, self.workflow_status()),
+            },
             Err(e) => {
-                let error_msg = format!("Failed to execute codex command in Overseer pane: {}", e);
-                let _ = self.log_coordinator(&error_msg);
+                ControlReply::failure(control::command_word(payload), &e, self.workflow_status())
+            }
+        };
+
+        let record = match &reply.error {
+            Some(error) => LogRecord::error(LogComponent::Control, "Control command failed")
+                .field("error", error.to_string()),
+            None => LogRecord::info(LogComponent::Control, "Control command"),
+        };
+        self.log(
+            record
+                .field("command", &reply.command)
+                .field("payload", payload.trim()),
+        );
+        self.show_message(
+            format!("Control: {}", reply.command),
+            LastMessage::Control {
+                command: reply.command.clone(),
+                ok: reply.ok,
+            },
+        );
+
+        if let Some(pipe_id) = cli_pipe {
+            cli_pipe_output(pipe_id, &format!("{}\n", reply.to_json()));
+            unblock_cli_pipe_input(pipe_id);
+        }
+    }
+
+    /// Carry out a control command, returning a note for the reply
+    fn run_control_command(
+        &mut self,
+        command: &ControlCommand,
+    ) -> Result<Option<String>, ControlError> {
+        // Only a task switch can open a task while the configured ID is invalid
+        if let Some(ref e) = self.task_id_error {
+            if !matches!(
+                command,
+                ControlCommand::Status | ControlCommand::Tasks | ControlCommand::SwitchTask { .. }
+            ) {
+                return Err(ControlError::NoTask(e.clone()));
+            }
+        }
+
+        match command {
+            ControlCommand::Status => Ok(None),
+            ControlCommand::Advance => {
+                let event = control::advance_event(&self.workflow, &self.guard_context())?;
+                let report = self.run_workflow_event(event)?;
+                Ok(Some(self.describe_dispatch(&report)))
+            }
+            ControlCommand::RetryPhase => {
+                let (role, prompt) = self
+                    .workflow
+                    .in_flight()
+                    .ok_or(ControlError::NothingToRetry(self.workflow.phase()))?;
+                let executor = self.executor();
+                executor
+                    .execute(&WorkflowAction::LaunchAssistant { role, prompt })
+                    .map_err(|e| ControlError::Action(e.to_string()))?;
+                Ok(Some(format!(
+                    "Sent the {:?} prompt to the {:?} assistant again",
+                    prompt, role
+                )))
+            }
+            ControlCommand::SkipTo(phase) => {
+                let report = self.force_workflow_phase(*phase, WorkflowEvent::Skip)?;
+                self.aborted = false;
+                self.save_snapshot();
+                Ok(Some(self.describe_dispatch(&report)))
+            }
+            ControlCommand::Pause => {
+                if self.paused {
+                    return Ok(Some("Already paused".to_string()));
+                }
+                self.paused = true;
+                self.save_snapshot();
+                Ok(Some("Automatic transitions paused".to_string()))
+            }
+            ControlCommand::Resume => {
+                if !self.paused {
+                    return Ok(Some("Not paused".to_string()));
+                }
+                self.paused = false;
+                self.save_snapshot();
+                // Catch up on whatever happened while paused
+                self.resume_from_task_files();
+                self.resume_workflow();
+                Ok(Some(format!(
+                    "Automatic transitions resumed in {:?}",
+                    self.workflow.phase()
+                )))
+            }
+            ControlCommand::Abort => {
+                self.force_workflow_phase(WorkflowPhase::Finished, WorkflowEvent::Abort)?;
+                self.aborted = true;
+                self.pending_relaunches.clear();
+                self.save_snapshot();
+                Ok(Some("Workflow aborted".to_string()))
+            }
+            ControlCommand::SetDescription(description) => {
+                self.task_description = description.clone();
+                self.description_from_cli = true;
+                self.save_snapshot();
+                Ok(Some("Task description updated".to_string()))
+            }
+            ControlCommand::ApprovePlan => {
+                let report = self.run_workflow_event(WorkflowEvent::ApprovePlan)?;
+                Ok(Some(self.describe_dispatch(&report)))
+            }
+            ControlCommand::ForceReview => {
+                let report = self.run_workflow_event(WorkflowEvent::ForceReview)?;
+                Ok(Some(self.describe_dispatch(&report)))
+            }
+            ControlCommand::RejectPlan(feedback) => {
+                self.workflow
+                    .check(WorkflowEvent::RejectPlan, &self.guard_context())?;
+                let feedback = feedback
+                    .as_deref()
+                    .unwrap_or("The plan was rejected without further feedback.");
+                FileSystem::write_file_atomic(
+                    self.file_system.get_plan_feedback_path(&self.task_id),
+                    &format!("{}\n", feedback),
+                )
+                .map_err(|e| ControlError::Action(format!("{:?}", e)))?;
+
+                let report = self.run_workflow_event(WorkflowEvent::RejectPlan)?;
+                self.todo_list = None;
+                Ok(Some(self.describe_dispatch(&report)))
+            }
+            ControlCommand::ResetTask => {
+                let report = self.run_workflow_event(WorkflowEvent::Reset)?;
+                self.aborted = false;
+                self.todo_list = None;
+                self.review = None;
+                self.failed_work.clear();
+                self.pending_relaunches.clear();
+                self.save_snapshot();
+                Ok(Some(self.describe_dispatch(&report)))
+            }
+            ControlCommand::Resend(role) => {
+                let role = role
+                    .or_else(|| self.workflow.in_flight().map(|(role, _)| role))
+                    .ok_or(ControlError::NothingToRetry(self.workflow.phase()))?;
+                let command = self
+                    .message_router
+                    .last_command(role)
+                    .ok_or(ControlError::NothingToResend(role))?;
+                self.message_router
+                    .execute_command_in_role(&command, role)
+                    .map_err(|e| ControlError::Action(e.to_string()))?;
+                Ok(Some(format!(
+                    "Sent the last command to the {:?} pane again",
+                    role
+                )))
+            }
+            ControlCommand::Tasks => {
+                let count = self.task_index()?.tasks.len();
+                Ok(Some(format!("{} task(s) in the task index", count)))
+            }
+            ControlCommand::SwitchTask {
+                task_id,
+                description,
+            } => self.switch_task(task_id, description.as_deref()),
+        }
+    }
+
+    /// Summarize a dispatch for a control reply
+    fn describe_dispatch(&self, report: &DispatchReport) -> String {
+        let mut message = format!("Now in {:?}", self.workflow.phase());
+        if !report.action_failures.is_empty() {
+            message.push_str(&format!(
+                "; {} workflow action(s) failed, see the coordinator log",
+                report.action_failures.len()
+            ));
+        }
+        message
+    }
+
+    /// Current workflow state as reported to the CLI
+    fn workflow_status(&self) -> WorkflowStatus {
+        WorkflowStatus {
+            paused: self.paused,
+            aborted: self.aborted,
+            todo: self
+                .todo_list
+                .as_ref()
+                .map(|todo_list| todo_list.progress().into()),
+            failed_work: self.failed_work.len(),
+            pending_acks: self.communication.pending_acks(),
+            ..WorkflowStatus::new(&self.task_id, &self.task_description, &self.workflow)
+        }
+    }
+
+    // === File Watching Methods ===
+
+    /// Start watching the task files that drive phase transitions
+    fn watch_task_files(&mut self) {
+        for path in [self.get_todo_list_path(), self.get_review_path()].iter() {
+            if let Err(e) = self.file_monitor.watch(path) {
+                self.log(
+                    LogRecord::error(LogComponent::Files, "Failed to watch task file")
+                        .field("path", path.display().to_string())
+                        .field("error", e.to_string()),
+                );
+            }
+        }
+    }
+
+    /// Poll watched task files, announce settled changes and feed them to the workflow
+    ///
+    /// Returns whether any change was observed.
+    fn poll_task_files(&mut self) -> bool {
+        let messages = self.file_monitor.tick(Instant::now());
+
+        for message in &messages {
+            // Delivery failures are logged by the broadcast itself
+            let _ = self.broadcast_coordination_message(message.clone());
+
+            if let CoordinationMessage::FileChanged { file_path, .. } = message {
+                if file_path.ends_with(FileSystem::TODO_LIST_FILE) {
+                    self.track_todo_progress();
+                } else if file_path.ends_with(FileSystem::REVIEW_FILE) {
+                    self.review = self
+                        .read_review()
+                        .ok()
+                        .map(|content| Review::parse(&content));
+                }
+            }
+
+            // Only act on changes the current phase is waiting for
+            if let Some(event) = WorkflowEvent::from_file_change(message, self.workflow.phase()) {
+                self.dispatch_file_event(event);
             }
         }
+
+        !messages.is_empty()
+    }
+
+    /// Announce todo items ticked since the list was last read
+    fn track_todo_progress(&mut self) {
+        let todo_list = match self.read_todo_list() {
+            Ok(content) => TodoList::parse(&content),
+            Err(_) => return,
+        };
+
+        let previous = self.todo_list.take().unwrap_or_default();
+        let completed: Vec<String> = todo_list
+            .newly_completed(&previous)
+            .iter()
+            .map(|item| item.id.clone())
+            .collect();
+
+        for task_id in completed {
+            let _ =
+                self.broadcast_coordination_message(CoordinationMessage::TaskCompleted { task_id });
+        }
+
+        let progress = todo_list.progress();
+        self.log(
+            LogRecord::info(LogComponent::Workflow, "Todo progress")
+                .field("completed", progress.completed)
+                .field("total", progress.total)
+                .field("percentage", progress.percentage()),
+        );
+
+        self.todo_list = Some(todo_list);
+    }
+
+    /// Read the task files already on disk, without announcing anything
+    fn load_task_file_caches(&mut self) {
+        self.todo_list = self
+            .read_todo_list()
+            .ok()
+            .map(|content| TodoList::parse(&content));
+        self.review = self
+            .read_review()
+            .ok()
+            .map(|content| Review::parse(&content));
+    }
+
+    /// Structured state for the one-line status bar
+    fn status_bar(&self) -> StatusBar {
+        StatusBar {
+            phase: self.workflow.phase(),
+            permissions_granted: self.permissions_granted,
+            registered_roles: self.get_registered_roles(),
+            progress: self
+                .todo_list
+                .as_ref()
+                .map(|todo_list| todo_list.progress()),
+            last_message: self.last_message.clone(),
+            message_count: self.message_count,
+            paused: self.paused,
+            aborted: self.aborted,
+            failed_work: self.failed_work.len(),
+        }
     }
+
+    /// Draw the full-screen dashboard
+    fn render_dashboard(&mut self, rows: usize, cols: usize) {
+        let panes = PaneRole::ALL
+            .iter()
+            .map(|role| PaneStatus {
+                role: *role,
+                pane_id: self.message_router.get_pane_id(role),
+                health: self.message_router.pane_health(role),
+            })
+            .collect();
+        let data = DashboardData {
+            task_id: &self.task_id,
+            phase: self.workflow.phase(),
+            paused: self.paused,
+            aborted: self.aborted,
+            history: self.workflow.history(),
+            todo_list: self.todo_list.as_ref(),
+            review: self.review.as_ref(),
+            panes,
+        };
+        self.dashboard.render(&data, rows, cols);
+    }
+}
+
+/// Current Unix time in seconds
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
 }
 
 register_plugin!(State);
@@ -587,48 +1626,204 @@ register_plugin!(State);
 
 impl ZellijPlugin for State {
     fn load(&mut self, configuration: BTreeMap<String, String>) {
+        // Task files live under base_directory, relative to the plugin's cwd
+        self.file_system =
+            FileSystem::from_configuration(&configuration, Path::new(FileSystem::HOST_DIRECTORY));
+
+        // Configure logging before anything is logged; a bad value is reported
+        // once the task's log is known
+        let logger = Logger::from_configuration(&configuration);
+        if let Ok(logger) = logger {
+            self.logger = logger;
+        }
+
         // Read configuration parameters
-        if let Some(task_id_str) = configuration.get("task_id") {
-            if let Ok(parsed_id) = task_id_str.parse::<u32>() {
-                self.task_id = parsed_id;
-                let _ = self.log_coordinator(&format!(
-                    "Loaded task_id from configuration: {}",
-                    self.task_id
-                ));
-            } else {
-                let _ = self.log_coordinator(&format!(
-                    "Warning: Invalid task_id in configuration: {}",
-                    task_id_str
-                ));
-            }
+        let configured_task_id = configuration
+            .get("task_id")
+            .map(|task_id_str| (task_id_str, TaskId::parse(task_id_str)));
+        match &configured_task_id {
+            Some((_, Ok(task_id))) => self.task_id = task_id.clone(),
+            Some((_, Err(e))) => self.task_id_error = Some(e.clone()),
+            None => {}
+        }
+
+        // Move a numeric task directory before anything is logged into the new one
+        if self.task_id_error.is_none() {
+            self.migrate_task_directory();
+        }
+
+        if let Err(e) = logger {
+            self.log(
+                LogRecord::warn(
+                    LogComponent::Coordinator,
+                    "Invalid logging configuration, using defaults",
+                )
+                .field("error", e.to_string()),
+            );
+        }
+
+        match configured_task_id {
+            Some((_, Ok(_))) => self.log(LogRecord::info(
+                LogComponent::Coordinator,
+                "Loaded task_id from configuration",
+            )),
+            Some((task_id_str, Err(e))) => self.show_message(
+                format!(
+                    "Invalid task_id {:?} in configuration: {}; no task is opened until a valid \
+                     one is configured or chosen with switch-task",
+                    task_id_str, e
+                ),
+                LastMessage::Notice("Invalid task_id".to_string()),
+            ),
+            None => {}
         }
 
         if let Some(task_desc) = configuration.get("task_description") {
             self.task_description = task_desc.clone();
-            let _ = self.log_coordinator(&format!(
-                "Loaded task_description from configuration: {}",
-                self.task_description
-            ));
+            self.log(
+                LogRecord::info(
+                    LogComponent::Coordinator,
+                    "Loaded task_description from configuration",
+                )
+                .field("task_description", &self.task_description),
+            );
         }
 
         // Load LiteLLM configuration
         if let Some(api_key) = configuration.get("api_key") {
             self.litellm_config.api_key = api_key.clone();
-            let _ = self.log_coordinator("Loaded API key from configuration");
+            self.redactor = Redactor::from_config(&self.litellm_config);
+            self.log(LogRecord::info(
+                LogComponent::Coordinator,
+                "Loaded API key from configuration",
+            ));
+        }
+
+        if let Some(key_file) = configuration.get("api_key_file") {
+            self.litellm_config.api_key_file = Some(key_file.clone());
+            self.log(
+                LogRecord::info(
+                    LogComponent::Coordinator,
+                    "Assistants will read the API key from a file",
+                )
+                .field("api_key_file", key_file),
+            );
         }
 
         if let Some(url) = configuration.get("litellm_url") {
             self.litellm_config.url = url.clone();
-            let _ = self.log_coordinator(&format!(
-                "Loaded LiteLLM URL from configuration: {}",
-                self.litellm_config.url
-            ));
+            self.log(
+                LogRecord::info(
+                    LogComponent::Coordinator,
+                    "Loaded LiteLLM URL from configuration",
+                )
+                .field("litellm_url", &self.litellm_config.url),
+            );
+        }
+
+        if let Some(rounds) = configuration.get("max_review_rounds") {
+            match rounds.parse::<u32>() {
+                Ok(parsed) if parsed > 0 => {
+                    self.max_review_rounds = parsed;
+                    self.log(
+                        LogRecord::info(
+                            LogComponent::Coordinator,
+                            "Loaded max_review_rounds from configuration",
+                        )
+                        .field("max_review_rounds", parsed),
+                    );
+                }
+                _ => self.log(
+                    LogRecord::warn(
+                        LogComponent::Coordinator,
+                        "Invalid max_review_rounds in configuration",
+                    )
+                    .field("max_review_rounds", rounds),
+                ),
+            }
+        }
+
+        match PaneSelectors::from_configuration(&configuration) {
+            Ok(selectors) => self.message_router.set_selectors(selectors),
+            Err(e) => self.log(
+                LogRecord::warn(
+                    LogComponent::Coordinator,
+                    "Invalid pane selector configuration, matching panes by title",
+                )
+                .field("error", e.to_string()),
+            ),
+        }
+        let plugin_ids = get_plugin_ids();
+        self.message_router.set_own_plugin_id(plugin_ids.plugin_id);
+
+        match AssistantConfig::from_configuration(&configuration) {
+            Ok(assistants) => {
+                for role in [PaneRole::Overseer, PaneRole::Commander].iter() {
+                    let assistant = assistants.for_role(*role);
+                    self.log(
+                        LogRecord::info(LogComponent::Coordinator, "Configured assistant")
+                            .field("role", role)
+                            .field("backend", format!("{:?}", assistant.backend))
+                            .field("model", assistant.model.as_deref().unwrap_or("default")),
+                    );
+                }
+                self.assistants = assistants;
+            }
+            Err(e) => self.log(
+                LogRecord::warn(
+                    LogComponent::Coordinator,
+                    "Invalid assistant configuration, using codex for every role",
+                )
+                .field("error", e.to_string()),
+            ),
+        }
+
+        match RetryPolicy::from_configuration(&configuration) {
+            Ok(policy) => self.communication.set_retry_policy(policy),
+            Err(e) => self.log(
+                LogRecord::warn(
+                    LogComponent::Coordinator,
+                    "Invalid message retry configuration, using defaults",
+                )
+                .field("error", e.to_string()),
+            ),
+        }
+
+        match HealthTracker::from_configuration(&configuration) {
+            Ok(health) => self.message_router.set_health_tracker(health),
+            Err(e) => self.log(
+                LogRecord::warn(
+                    LogComponent::Coordinator,
+                    "Invalid restart configuration, exited panes are not restarted",
+                )
+                .field("error", e.to_string()),
+            ),
+        }
+
+        match PaneSpawner::from_configuration(
+            &configuration,
+            &self.assistants,
+            Some(plugin_ids.initial_cwd),
+        ) {
+            Ok(spawner) => self.message_router.set_spawner(spawner),
+            Err(e) => self.log(
+                LogRecord::warn(
+                    LogComponent::Coordinator,
+                    "Invalid pane spawning configuration, using defaults",
+                )
+                .field("error", e.to_string()),
+            ),
         }
 
-        // Request permissions needed for pane discovery and writing to panes
+        // Request permissions needed for pane discovery, writing to panes
+        // and opening (and naming) panes for roles missing from the layout, and
+        // running the host command that writes private env files
         request_permission(&[
             PermissionType::ReadApplicationState,
             PermissionType::WriteToStdin,
+            PermissionType::OpenTerminalsOrPlugins,
+            PermissionType::ChangeApplicationState,
+            PermissionType::RunCommands,
         ]);
 
         // Subscribe to permission results and layout events
@@ -636,41 +1831,40 @@ impl ZellijPlugin for State {
             EventType::PermissionRequestResult,
             EventType::PaneUpdate,
             EventType::TabUpdate,
+            EventType::Timer,
+            EventType::CommandPaneOpened,
+            EventType::RunCommandResult,
+            EventType::Key,
         ]);
 
-        // Initialize task directories
-        match self.ensure_task_files_exist() {
-            Ok(()) => {
-                let success_msg = format!(
-                    "Successfully created task directories for task {}",
-                    self.task_id
-                );
-                let _ = self.log_coordinator(&success_msg);
-            }
-            Err(e) => {
-                let error_msg = format!(
-                    "CRITICAL: Failed to create task directories for task {}: {:?}",
-                    self.task_id, e
-                );
-                let _ = self.log_coordinator(&error_msg);
-                // Note: Plugin continues to run even if directory creation fails
-                // This allows the UI to show the error state
-            }
+        if self.task_id_error.is_none() {
+            self.open_task();
         }
+
+        // Start polling the watched files; each timer event re-arms it
+        set_timeout(DEFAULT_POLL_INTERVAL_SECS);
     }
     fn update(&mut self, event: Event) -> bool {
         match event {
             Event::PermissionRequestResult(_permission_status) => {
                 // Log that we received a permission result
-                let log_msg = format!("Permission result received: {:?}", _permission_status);
-                let _ = self.log_coordinator(&log_msg);
+                self.log(
+                    LogRecord::info(LogComponent::Coordinator, "Permission result received")
+                        .field("status", format!("{:?}", _permission_status)),
+                );
 
                 // Check if all required permissions are granted
                 // For now, we'll assume they are if we get here
                 if !self.permissions_granted {
                     self.permissions_granted = true;
-                    let _ = self
-                        .log_coordinator("All permissions granted, waiting for pane manifest...");
+                    self.log(LogRecord::info(
+                        LogComponent::Coordinator,
+                        "All permissions granted, waiting for pane manifest",
+                    ));
+                    // Env files the host writes needed the RunCommands permission
+                    if self.task_id_error.is_none() && !self.missing_env_files.is_empty() {
+                        self.write_secret_env_files();
+                    }
                 }
 
                 true // trigger re-render to show permission status
@@ -679,33 +1873,83 @@ impl ZellijPlugin for State {
                 // Store the updated pane manifest
                 self.pane_manifest = Some(pane_manifest);
 
-                let log_msg =
-                    "Received pane manifest update, attempting pane discovery...".to_string();
-                let _ = self.log_coordinator(&log_msg);
-
                 // Rediscover panes with the new manifest
                 self.discover_and_register_panes();
+                self.handle_pane_health();
 
-                // If we have permissions and found panes, start planning workflow
+                // Bootstrap required panes the layout does not provide
+                if self.permissions_granted && self.task_id_error.is_none() {
+                    self.spawn_missing_panes();
+                }
+
+                // If we have permissions and found panes, start or continue the workflow
                 if self.permissions_granted && !self.get_registered_roles().is_empty() {
-                    self.start_planning_workflow();
+                    self.resume_workflow();
                 }
 
                 true // trigger re-render to show updated pane information
             }
             Event::TabUpdate(_tab_info) => {
                 // Tab structure changed, request updated pane information
-                let log_msg = "Tab update received, pane manifest may be outdated".to_string();
-                let _ = self.log_coordinator(&log_msg);
+                self.log(LogRecord::debug(
+                    LogComponent::Panes,
+                    "Tab update received, pane manifest may be outdated",
+                ));
 
                 // Note: Zellij will send a PaneUpdate event after TabUpdate,
                 // so we don't need to do anything special here
                 true // trigger re-render
             }
+            Event::CommandPaneOpened(terminal_pane_id, context) => {
+                if let Some(role) = self
+                    .message_router
+                    .command_pane_opened(terminal_pane_id, &context)
+                {
+                    self.log(
+                        LogRecord::info(
+                            LogComponent::Panes,
+                            "Opened terminal pane, waiting for it to appear in the layout",
+                        )
+                        .field("role", role)
+                        .field("terminal_id", terminal_pane_id),
+                    );
+                }
+                false
+            }
+            Event::RunCommandResult(exit_code, _stdout, stderr, context) => {
+                self.env_file_written(&context, exit_code, &stderr);
+                true
+            }
+            Event::Timer(_elapsed) => {
+                // Acknowledgement deadlines are checked at the poll interval
+                self.process_delivery_timeouts();
+                let changed = if self.task_id_error.is_none() {
+                    self.rotate_transcripts();
+                    self.poll_task_files()
+                } else {
+                    false
+                };
+
+                // Re-arm the timer for the next poll
+                set_timeout(DEFAULT_POLL_INTERVAL_SECS);
+                changed
+            }
+            // Keys reach the plugin only while its pane is focused
+            Event::Key(key) => self.dashboard.handle_key(&key),
             _ => false,
         }
     }
     fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
+        if pipe_message.name == CONTROL_PIPE {
+            let payload = pipe_message.payload.unwrap_or_default();
+            let cli_pipe = match &pipe_message.source {
+                PipeSource::Cli(input_id) => Some(input_id.as_str()),
+                _ => None,
+            };
+            self.handle_control_command(&payload, cli_pipe);
+            return true;
+        }
+
         match pipe_message.source {
             PipeSource::Cli(input_id) => {
                 if let Some(payload) = pipe_message.payload {
@@ -720,107 +1964,49 @@ impl ZellijPlugin for State {
                         None,
                     );
                 } else {
-                    self.last_message = Some("Received empty message from plugin".to_string());
+                    self.show_message(
+                        "Received empty message from plugin".to_string(),
+                        LastMessage::Notice("Empty".to_string()),
+                    );
                     return true;
                 }
             }
             PipeSource::Keybind => {
+                // Keys bound to a control command name carry its argument as payload
+                let parsed = ControlCommand::parse_keybind(
+                    &pipe_message.name,
+                    pipe_message.payload.as_deref(),
+                );
+                if !matches!(parsed, Err(ControlError::UnknownCommand(_))) {
+                    let line = format!(
+                        "{} {}",
+                        pipe_message.name,
+                        pipe_message.payload.unwrap_or_default()
+                    );
+                    self.run_parsed_control_command(parsed, &line, None);
+                    return true;
+                }
+
                 if let Some(payload) = pipe_message.payload {
                     return self.handle_incoming_message(&payload, "Keybind", None);
                 } else {
-                    self.last_message = Some("Received keybind trigger".to_string());
+                    self.show_message(
+                        "Received keybind trigger".to_string(),
+                        LastMessage::Notice("Keybind".to_string()),
+                    );
                     return true;
                 }
             }
         }
         false
     }
-    fn render(&mut self, _rows: usize, _cols: usize) {
-        // Create condensed status bar format
-        // ZZZ | Phase: Init | Perms: ✓ | Panes: O,C,T,R,E (5/5) | Last: StartPlanning→Overseer | Msgs: 3
-
-        // Format phase
-        let phase = match self.current_phase {
-            WorkflowPhase::Initializing => "Init",
-            WorkflowPhase::PlanningInProgress => "Plan",
-            WorkflowPhase::PlanReady => "Plan",
-            WorkflowPhase::ImplementationInProgress => "Impl",
-            WorkflowPhase::ImplementationComplete => "Impl",
-            WorkflowPhase::ReviewInProgress => "Rev",
-            WorkflowPhase::ReviewComplete => "Rev",
-            WorkflowPhase::Finished => "Done",
-        };
-
-        // Format permissions
-        let perms = if self.permissions_granted {
-            "✓"
-        } else {
-            "✗"
-        };
-
-        // Format pane roles
-        let registered_roles = self.get_registered_roles();
-        let pane_icons: Vec<String> = registered_roles
-            .iter()
-            .map(|role| match role {
-                PaneRole::Overseer => "O".to_string(),
-                PaneRole::Commander => "C".to_string(),
-                PaneRole::TaskList => "T".to_string(),
-                PaneRole::Review => "R".to_string(),
-                PaneRole::Editor => "E".to_string(),
-            })
-            .collect();
-        let panes_display = if pane_icons.is_empty() {
-            "None (0/5)".to_string()
-        } else {
-            format!("{} ({}/5)", pane_icons.join(","), pane_icons.len())
-        };
-
-        // Format last message
-        let last_msg = if let Some(ref msg) = self.last_message {
-            // Extract key info from complex message strings
-            if msg.contains("StartPlanning") && msg.contains("→") {
-                "StartPlanning→Overseer".to_string()
-            } else if msg.contains("Envelope from") && msg.contains("→") {
-                // Extract "from source → target: MessageType"
-                if let Some(arrow_pos) = msg.find(" → ") {
-                    if let Some(colon_pos) = msg[arrow_pos..].find(": ") {
-                        let start = arrow_pos + 3;
-                        let end = arrow_pos + colon_pos;
-                        let target = &msg[start..end];
-                        if let Some(msg_start) = msg.find(": ") {
-                            if let Some(msg_type) = msg[msg_start + 2..].split('(').next() {
-                                format!("{}→{}", msg_type, target)
-                            } else {
-                                "Message→Target".to_string()
-                            }
-                        } else {
-                            format!("Msg→{}", target)
-                        }
-                    } else {
-                        "Message→Unknown".to_string()
-                    }
-                } else {
-                    "Recent".to_string()
-                }
-            } else if msg.contains("Broadcast") {
-                "Broadcast*All".to_string()
-            } else if msg.contains("Raw from") {
-                "Raw→Plugin".to_string()
-            } else {
-                "Recent".to_string()
-            }
-        } else {
-            "None".to_string()
-        };
-
-        // Format message count
-        let msg_count = self.received_messages.len();
+    fn render(&mut self, rows: usize, cols: usize) {
+        // An enlarged or floating plugin pane has room for the dashboard
+        if rows >= MIN_DASHBOARD_ROWS {
+            self.render_dashboard(rows, cols);
+            return;
+        }
 
-        // Render single-line status bar
-        print!(
-            "ZZZ | Phase: {} | Perms: {} | Panes: {} | Last: {} | Msgs: {}",
-            phase, perms, panes_display, last_msg, msg_count
-        );
+        self.status_bar().render(cols);
     }
 }
@@ -8,3 +8,77 @@ pub enum PaneRole {
     Review,
     Editor,
 }
+
+impl PaneRole {
+    /// Every role, in display order
+    pub const ALL: [PaneRole; 5] = [
+        PaneRole::Overseer,
+        PaneRole::Commander,
+        PaneRole::TaskList,
+        PaneRole::Review,
+        PaneRole::Editor,
+    ];
+
+    /// Prefix used for this role's keys in the plugin configuration
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            PaneRole::Overseer => "overseer",
+            PaneRole::Commander => "commander",
+            PaneRole::TaskList => "task_list",
+            PaneRole::Review => "review",
+            PaneRole::Editor => "editor",
+        }
+    }
+
+    /// Look up a role by its configuration key
+    pub fn from_config_key(key: &str) -> Option<PaneRole> {
+        PaneRole::ALL
+            .iter()
+            .copied()
+            .find(|role| role.config_key() == key)
+    }
+
+    /// Pane title used for this role in the standard layout
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PaneRole::Overseer => "Overseer",
+            PaneRole::Commander => "Commander",
+            PaneRole::TaskList => "Task List",
+            PaneRole::Review => "Review",
+            PaneRole::Editor => "Editor",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_keys_are_unique() {
+        let mut keys: Vec<&str> = PaneRole::ALL.iter().map(|r| r.config_key()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), PaneRole::ALL.len());
+    }
+
+    #[test]
+    fn test_config_key() {
+        assert_eq!(PaneRole::Overseer.config_key(), "overseer");
+        assert_eq!(PaneRole::TaskList.config_key(), "task_list");
+    }
+
+    #[test]
+    fn test_from_config_key_roundtrip() {
+        for role in PaneRole::ALL.iter() {
+            assert_eq!(PaneRole::from_config_key(role.config_key()), Some(*role));
+        }
+        assert_eq!(PaneRole::from_config_key("Overseer"), None);
+    }
+
+    #[test]
+    fn test_display_name() {
+        assert_eq!(PaneRole::Commander.display_name(), "Commander");
+        assert_eq!(PaneRole::TaskList.display_name(), "Task List");
+    }
+}
@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zellij_tile::prelude::PaneId;
+
+use crate::communication::protocol;
+use crate::coordination_message::CoordinationMessage;
+use crate::file_system::{FileSystem, FileSystemError};
+use crate::pane_role::PaneRole;
+use crate::task_id::TaskId;
+use crate::workflow::{FailedWork, PhaseChange};
+use crate::workflow_phase::WorkflowPhase;
+
+/// Current on-disk snapshot format version
+///
+/// Version 2 stores received messages in the tagged protocol encoding.
+/// Version 3 stores the task ID as a string.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+/// Received coordination messages kept in memory and in `state.json`
+///
+/// Only the newest are kept; older ones have already driven the workflow.
+pub const MAX_RECEIVED_MESSAGES: usize = 100;
+
+/// Errors that can occur while saving or restoring coordinator state
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Reading or writing the snapshot file failed
+    FileSystem(FileSystemError),
+    /// The snapshot could not be (de)serialized
+    Serialization(serde_json::Error),
+    /// The snapshot was written by a newer, incompatible version
+    UnsupportedVersion(u32),
+    /// The snapshot belongs to a different task
+    TaskMismatch { expected: TaskId, found: TaskId },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::FileSystem(e) => write!(f, "Snapshot file error: {:?}", e),
+            SnapshotError::Serialization(e) => write!(f, "Snapshot serialization failed: {}", e),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported snapshot version {} (expected at most {})",
+                version, SNAPSHOT_VERSION
+            ),
+            SnapshotError::TaskMismatch { expected, found } => write!(
+                f,
+                "Snapshot belongs to task {} but task {} is configured",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl From<FileSystemError> for SnapshotError {
+    fn from(error: FileSystemError) -> Self {
+        SnapshotError::FileSystem(error)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(error: serde_json::Error) -> Self {
+        SnapshotError::Serialization(error)
+    }
+}
+
+/// Versioned snapshot of the coordinator state, persisted to `state.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoordinatorSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+    pub task_id: TaskId,
+    pub phase: WorkflowPhase,
+    pub history: Vec<PhaseChange>,
+    /// The newest `MAX_RECEIVED_MESSAGES` messages received from other panes
+    pub received_messages: Vec<CoordinationMessage>,
+    pub pane_registry: HashMap<PaneRole, PaneId>,
+    /// Assistant work lost to exited panes
+    #[serde(default)]
+    pub failed_work: Vec<FailedWork>,
+    /// Automatic transitions are held back until the operator resumes
+    #[serde(default)]
+    pub paused: bool,
+    /// The operator abandoned the workflow
+    #[serde(default)]
+    pub aborted: bool,
+    /// Task description set from the CLI, overriding the configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_description: Option<String>,
+    /// Unix timestamp when the snapshot was taken
+    pub saved_at: u64,
+}
+
+impl CoordinatorSnapshot {
+    /// Create a snapshot stamped with the current version and time
+    ///
+    /// Only the newest `MAX_RECEIVED_MESSAGES` received messages are kept.
+    pub fn new(
+        task_id: TaskId,
+        phase: WorkflowPhase,
+        history: Vec<PhaseChange>,
+        mut received_messages: Vec<CoordinationMessage>,
+        pane_registry: HashMap<PaneRole, PaneId>,
+    ) -> Self {
+        let dropped = received_messages
+            .len()
+            .saturating_sub(MAX_RECEIVED_MESSAGES);
+        received_messages.drain(..dropped);
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            task_id,
+            phase,
+            history,
+            received_messages,
+            pane_registry,
+            failed_work: Vec::new(),
+            paused: false,
+            aborted: false,
+            task_description: None,
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+
+    /// Atomically write the snapshot to `path`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        let content = serde_json::to_string_pretty(self)?;
+        FileSystem::write_file_atomic(path, &content)?;
+        Ok(())
+    }
+
+    /// Load a snapshot for `task_id` from `path`
+    ///
+    /// Returns `Ok(None)` when no snapshot has been written yet. Numeric IDs
+    /// from older snapshots match regardless of leading zeros.
+    pub fn load<P: AsRef<Path>>(path: P, task_id: &TaskId) -> Result<Option<Self>, SnapshotError> {
+        let path = path.as_ref();
+        if !FileSystem::file_exists(path) {
+            return Ok(None);
+        }
+
+        let content = FileSystem::read_file_safe(path)?;
+        let snapshot = Self::parse(&content)?;
+
+        if !snapshot.task_id.is_same_task(task_id) {
+            return Err(SnapshotError::TaskMismatch {
+                expected: task_id.clone(),
+                found: snapshot.task_id,
+            });
+        }
+
+        Ok(Some(snapshot))
+    }
+
+    /// Parse snapshot JSON, rejecting versions newer than this build understands
+    pub fn parse(content: &str) -> Result<Self, SnapshotError> {
+        // Check the version before decoding the full structure so that a newer
+        // layout is reported as such rather than as a confusing field error
+        let header: SnapshotHeader = serde_json::from_str(content)?;
+        if header.version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(header.version));
+        }
+
+        if header.version >= SNAPSHOT_VERSION {
+            return Ok(serde_json::from_str(content)?);
+        }
+
+        // Version 1 stored messages externally tagged; version 2 task IDs
+        // were numbers, which `TaskId` still deserializes
+        let mut value: serde_json::Value = serde_json::from_str(content)?;
+        if header.version < 2 {
+            if let Some(serde_json::Value::Array(messages)) = value.get_mut("received_messages") {
+                for message in messages.iter_mut() {
+                    *message = protocol::upgrade_message(message.take());
+                }
+            }
+        }
+        value["version"] = serde_json::json!(SNAPSHOT_VERSION);
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Minimal view of a snapshot used to check its version
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    version: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::WorkflowEvent;
+    use tempfile::tempdir;
+
+    fn create_test_snapshot() -> CoordinatorSnapshot {
+        let mut pane_registry = HashMap::new();
+        pane_registry.insert(PaneRole::Overseer, PaneId::Terminal(1));
+        pane_registry.insert(PaneRole::Commander, PaneId::Plugin(2));
+
+        CoordinatorSnapshot::new(
+            TaskId::from(42),
+            WorkflowPhase::PlanningInProgress,
+            vec![PhaseChange {
+                from: WorkflowPhase::Initializing,
+                to: WorkflowPhase::PlanningInProgress,
+                event: WorkflowEvent::StartPlanning,
+                timestamp: 1_700_000_000,
+            }],
+            vec![CoordinationMessage::StartImplementation],
+            pane_registry,
+        )
+    }
+
+    #[test]
+    fn test_new_snapshot_uses_current_version() {
+        let snapshot = create_test_snapshot();
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert!(snapshot.saved_at > 0);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let snapshot = create_test_snapshot();
+
+        snapshot.save(&path).unwrap();
+        let loaded = CoordinatorSnapshot::load(&path, &TaskId::from(42))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(loaded, snapshot);
+        assert_eq!(
+            loaded.pane_registry.get(&PaneRole::Commander),
+            Some(&PaneId::Plugin(2))
+        );
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_none() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+
+        let result = CoordinatorSnapshot::load(&path, &TaskId::from(1)).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_other_task() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+        create_test_snapshot().save(&path).unwrap();
+
+        let result = CoordinatorSnapshot::load(&path, &TaskId::from(7));
+        match result {
+            Err(SnapshotError::TaskMismatch { expected, found }) => {
+                assert_eq!(expected, TaskId::from(7));
+                assert_eq!(found, TaskId::from(42));
+            }
+            other => panic!("expected a task mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_newer_version() {
+        let mut value = serde_json::to_value(create_test_snapshot()).unwrap();
+        value["version"] = serde_json::json!(SNAPSHOT_VERSION + 1);
+
+        let result = CoordinatorSnapshot::parse(&value.to_string());
+        assert!(matches!(
+            result,
+            Err(SnapshotError::UnsupportedVersion(v)) if v == SNAPSHOT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_parse_migrates_version_1_messages() {
+        let mut value = serde_json::to_value(create_test_snapshot()).unwrap();
+        value["version"] = serde_json::json!(1);
+        value["received_messages"] = serde_json::json!([
+            "StartImplementation",
+            { "PlanReady": { "todo_file_path": "todo.md" } }
+        ]);
+
+        let parsed = CoordinatorSnapshot::parse(&value.to_string()).unwrap();
+        assert_eq!(parsed.version, SNAPSHOT_VERSION);
+        assert_eq!(
+            parsed.received_messages,
+            vec![
+                CoordinationMessage::StartImplementation,
+                CoordinationMessage::PlanReady {
+                    todo_file_path: "todo.md".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failed_work_roundtrip_and_default() {
+        let mut snapshot = create_test_snapshot();
+        snapshot.failed_work.push(FailedWork {
+            role: PaneRole::Overseer,
+            phase: WorkflowPhase::PlanningInProgress,
+            prompt: crate::workflow::PromptKind::Planning,
+            exit_status: Some(1),
+            timestamp: 1_700_000_100,
+        });
+
+        let parsed =
+            CoordinatorSnapshot::parse(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(parsed.failed_work, snapshot.failed_work);
+
+        // Snapshots written before failures were tracked still load
+        let mut value = serde_json::to_value(create_test_snapshot()).unwrap();
+        value.as_object_mut().unwrap().remove("failed_work");
+        let parsed = CoordinatorSnapshot::parse(&value.to_string()).unwrap();
+        assert!(parsed.failed_work.is_empty());
+    }
+
+    #[test]
+    fn test_control_state_roundtrip_and_default() {
+        let snapshot = CoordinatorSnapshot {
+            paused: true,
+            aborted: true,
+            task_description: Some("Add logout too".to_string()),
+            ..create_test_snapshot()
+        };
+
+        let parsed =
+            CoordinatorSnapshot::parse(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(parsed, snapshot);
+
+        // Older snapshots are neither paused nor aborted
+        let mut value = serde_json::to_value(create_test_snapshot()).unwrap();
+        assert!(value.get("task_description").is_none());
+        let object = value.as_object_mut().unwrap();
+        object.remove("paused");
+        object.remove("aborted");
+        let parsed = CoordinatorSnapshot::parse(&value.to_string()).unwrap();
+        assert!(!parsed.paused);
+        assert!(!parsed.aborted);
+        assert_eq!(parsed.task_description, None);
+    }
+
+    #[test]
+    fn test_parse_invalid_json() {
+        let result = CoordinatorSnapshot::parse("{ not json");
+        assert!(matches!(result, Err(SnapshotError::Serialization(_))));
+    }
+
+    #[test]
+    fn test_save_overwrites_previous_snapshot() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+
+        let mut snapshot = create_test_snapshot();
+        snapshot.save(&path).unwrap();
+
+        snapshot.phase = WorkflowPhase::PlanReady;
+        snapshot.save(&path).unwrap();
+
+        let loaded = CoordinatorSnapshot::load(&path, &TaskId::from(42))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.phase, WorkflowPhase::PlanReady);
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            format!("{}", SnapshotError::UnsupportedVersion(9)),
+            format!(
+                "Unsupported snapshot version 9 (expected at most {})",
+                SNAPSHOT_VERSION
+            )
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                SnapshotError::TaskMismatch {
+                    expected: TaskId::from(1),
+                    found: TaskId::parse("PROJ-2").unwrap()
+                }
+            ),
+            "Snapshot belongs to task PROJ-2 but task 1 is configured"
+        );
+    }
+
+    #[test]
+    fn test_new_keeps_newest_received_messages() {
+        let messages = (0..MAX_RECEIVED_MESSAGES + 20)
+            .map(|i| CoordinationMessage::TaskCompleted {
+                task_id: i.to_string(),
+            })
+            .collect();
+        let snapshot = CoordinatorSnapshot::new(
+            TaskId::from(1),
+            WorkflowPhase::ImplementationInProgress,
+            Vec::new(),
+            messages,
+            HashMap::new(),
+        );
+
+        assert_eq!(snapshot.received_messages.len(), MAX_RECEIVED_MESSAGES);
+        assert_eq!(
+            snapshot.received_messages[0],
+            CoordinationMessage::TaskCompleted {
+                task_id: "20".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_migrates_version_2_numeric_task_id() {
+        let mut value = serde_json::to_value(create_test_snapshot()).unwrap();
+        value["version"] = serde_json::json!(2);
+        value["task_id"] = serde_json::json!(42);
+
+        let parsed = CoordinatorSnapshot::parse(&value.to_string()).unwrap();
+        assert_eq!(parsed.version, SNAPSHOT_VERSION);
+        assert_eq!(parsed.task_id, TaskId::from(42));
+        assert_eq!(
+            parsed.received_messages,
+            create_test_snapshot().received_messages
+        );
+    }
+
+    #[test]
+    fn test_load_string_task_id_and_padded_numbers() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+
+        let key = TaskId::parse("PROJ-1234").unwrap();
+        let snapshot = CoordinatorSnapshot {
+            task_id: key.clone(),
+            ..create_test_snapshot()
+        };
+        snapshot.save(&path).unwrap();
+        assert!(CoordinatorSnapshot::load(&path, &key).unwrap().is_some());
+        assert!(CoordinatorSnapshot::load(&path, &TaskId::parse("PROJ-1235").unwrap()).is_err());
+
+        create_test_snapshot().save(&path).unwrap();
+        let padded = TaskId::parse("042").unwrap();
+        assert!(CoordinatorSnapshot::load(&path, &padded).unwrap().is_some());
+    }
+}
@@ -0,0 +1,530 @@
+use crate::todo_list::{TodoList, TodoSection};
+
+/// Maximum number of reviews per task when not configured
+pub const DEFAULT_MAX_REVIEW_ROUNDS: u32 = 3;
+
+/// Heading prefix of the todo list sections that hold review fixes
+pub const FIX_ROUND_HEADING: &str = "Review round";
+
+/// How serious a review finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Critical,
+    Major,
+    Minor,
+    Info,
+}
+
+impl Severity {
+    /// Parse a severity label such as `critical`, `High` or `nit`
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().trim_end_matches(':').to_lowercase().as_str() {
+            "critical" | "blocker" => Some(Severity::Critical),
+            "major" | "high" | "error" => Some(Severity::Major),
+            "minor" | "medium" | "low" | "warning" => Some(Severity::Minor),
+            "info" | "nit" | "nitpick" | "suggestion" | "note" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+
+    /// Check whether findings of this severity must be fixed before finishing
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, Severity::Critical | Severity::Major)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::Major => "major",
+            Severity::Minor => "minor",
+            Severity::Info => "info",
+        }
+    }
+}
+
+/// Whether a finding has been accepted for fixing or dismissed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingStatus {
+    /// No decision recorded
+    Open,
+    Accepted,
+    Rejected,
+}
+
+/// A `path:line` or `path:start-end` reference in a finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReference {
+    pub path: String,
+    pub line: Option<u32>,
+    pub end_line: Option<u32>,
+}
+
+impl FileReference {
+    /// Parse `src/main.rs:42`, `src/main.rs:42-48`, `src/main.rs#L42` or a bare path
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().trim_end_matches(&[',', ';', ')', '.'][..]);
+        let (path, lines) = match text.find("#L") {
+            Some(pos) => (&text[..pos], Some(&text[pos + 2..])),
+            None => match text.rfind(':') {
+                Some(pos) => (&text[..pos], Some(&text[pos + 1..])),
+                None => (text, None),
+            },
+        };
+
+        // Require something path-like so ordinary words are not mistaken for files
+        let path_like = path.contains('/') || path.rsplit('/').next()?.contains('.');
+        if path.is_empty() || !path_like || path.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let (line, end_line) = match lines {
+            Some(lines) => {
+                let mut parts = lines.splitn(2, '-');
+                let line = parts.next()?.trim_start_matches('L').parse().ok()?;
+                let end_line = match parts.next() {
+                    Some(end) => Some(end.trim_start_matches('L').parse().ok()?),
+                    None => None,
+                };
+                (Some(line), end_line)
+            }
+            None => (None, None),
+        };
+
+        Some(Self {
+            path: path.to_string(),
+            line,
+            end_line,
+        })
+    }
+}
+
+impl std::fmt::Display for FileReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(end_line) = self.end_line {
+            write!(f, "-{}", end_line)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single finding from review.md
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewFinding {
+    pub severity: Severity,
+    pub status: FindingStatus,
+    pub location: Option<FileReference>,
+    /// Finding text without severity and status markers
+    pub description: String,
+    /// Indented lines that continue the finding
+    pub details: Vec<String>,
+    /// 1-based line number in review.md
+    pub line: usize,
+}
+
+impl ReviewFinding {
+    /// Check whether the finding must be fixed before the workflow can finish
+    pub fn is_blocking(&self) -> bool {
+        self.severity.is_blocking() && self.status != FindingStatus::Rejected
+    }
+
+    /// Render the finding as an unchecked todo item
+    pub fn to_todo_item(&self) -> String {
+        let description = self.description.replace('\n', " ");
+        match &self.location {
+            Some(location) if !description.contains(&location.to_string()) => format!(
+                "- [ ] **{}** `{}` {}",
+                self.severity.as_str(),
+                location,
+                description
+            ),
+            _ => format!("- [ ] **{}** {}", self.severity.as_str(), description),
+        }
+    }
+}
+
+/// Parsed review.md
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Review {
+    pub findings: Vec<ReviewFinding>,
+}
+
+impl Review {
+    /// Parse review markdown into findings
+    ///
+    /// A finding is a list item that starts with a severity label, written as
+    /// `**major**`, `[major]` or `major:`, or any list item under a heading
+    /// named after a severity (e.g. `## Critical`). A `path:line` reference in
+    /// the item becomes its location, and `(accepted)`, `(rejected)` or
+    /// `status: rejected` set its status.
+    pub fn parse(content: &str) -> Self {
+        let mut findings: Vec<ReviewFinding> = Vec::new();
+        let mut heading_severity: Option<Severity> = None;
+        let mut current: Option<(usize, usize)> = None;
+        let mut in_code_block = false;
+
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            if trimmed.starts_with('#') {
+                let title = trimmed.trim_start_matches('#');
+                heading_severity = Severity::from_label(title.trim());
+                current = None;
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if let Some(item) = strip_list_marker(trimmed) {
+                let nested =
+                    matches!(current, Some((finding_indent, _)) if indent > finding_indent);
+
+                if !nested {
+                    // An unlabelled item takes the severity of its heading, if any
+                    let labelled = split_severity(item)
+                        .or_else(|| heading_severity.map(|severity| (severity, item)));
+                    current = None;
+                    if let Some((severity, rest)) = labelled {
+                        findings.push(build_finding(severity, rest, index + 1));
+                        current = Some((indent, findings.len() - 1));
+                    }
+                    continue;
+                }
+            }
+
+            // Continuation of the current finding
+            match current {
+                Some((finding_indent, finding)) if indent > finding_indent => {
+                    let detail = strip_list_marker(trimmed).unwrap_or(trimmed);
+                    findings[finding].details.push(detail.to_string());
+                }
+                _ => current = None,
+            }
+        }
+
+        Review { findings }
+    }
+
+    /// Findings that must be fixed before the workflow can finish
+    pub fn blocking_findings(&self) -> Vec<&ReviewFinding> {
+        self.findings.iter().filter(|f| f.is_blocking()).collect()
+    }
+
+    /// Check whether any blocking finding remains
+    pub fn has_blocking_findings(&self) -> bool {
+        self.findings.iter().any(|f| f.is_blocking())
+    }
+}
+
+/// Number of the next fix round, based on the fix sections already in the todo list
+pub fn next_fix_round(todo_list: &TodoList) -> u32 {
+    fn count(sections: &[TodoSection]) -> u32 {
+        sections
+            .iter()
+            .map(|s| u32::from(s.title.starts_with(FIX_ROUND_HEADING)) + count(&s.subsections))
+            .sum()
+    }
+
+    count(&todo_list.sections) + 1
+}
+
+/// Append the blocking findings of `review` to the todo list as a new fix round
+///
+/// Returns the updated todo list content and the round number used.
+pub fn append_fix_round(todo_content: &str, review: &Review) -> (String, u32) {
+    let round = next_fix_round(&TodoList::parse(todo_content));
+
+    let mut content = todo_content.trim_end().to_string();
+    if !content.is_empty() {
+        content.push_str("\n\n");
+    }
+    content.push_str(&format!("## {} {}\n\n", FIX_ROUND_HEADING, round));
+    for finding in review.blocking_findings() {
+        content.push_str(&finding.to_todo_item());
+        content.push('\n');
+    }
+
+    (content, round)
+}
+
+/// Strip a `-`, `*`, `+` or `1.` list marker and an optional checkbox
+fn strip_list_marker(trimmed: &str) -> Option<&str> {
+    let rest = if let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        rest
+    } else {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        trimmed[digits..].strip_prefix(". ")?
+    };
+
+    let rest = rest.trim_start();
+    let rest = ["[ ]", "[x]", "[X]"]
+        .iter()
+        .find_map(|checkbox| rest.strip_prefix(checkbox))
+        .unwrap_or(rest);
+    Some(rest.trim_start())
+}
+
+/// Split a leading severity label off a list item
+fn split_severity(item: &str) -> Option<(Severity, &str)> {
+    let (label, rest) = if let Some(rest) = item.strip_prefix("**") {
+        let end = rest.find("**")?;
+        (&rest[..end], &rest[end + 2..])
+    } else if let Some(rest) = item.strip_prefix('[') {
+        let end = rest.find(']')?;
+        (&rest[..end], &rest[end + 1..])
+    } else {
+        let end = item.find(':')?;
+        (&item[..end], &item[end + 1..])
+    };
+
+    let severity = Severity::from_label(label)?;
+    let rest =
+        rest.trim_start_matches(|c: char| c == ':' || c == '-' || c == '—' || c.is_whitespace());
+    Some((severity, rest))
+}
+
+/// Status markers recognised in finding text, with the status they set
+const STATUS_MARKERS: &[(&str, FindingStatus)] = &[
+    ("(accepted)", FindingStatus::Accepted),
+    ("[accepted]", FindingStatus::Accepted),
+    ("status: accepted", FindingStatus::Accepted),
+    ("(rejected)", FindingStatus::Rejected),
+    ("[rejected]", FindingStatus::Rejected),
+    ("status: rejected", FindingStatus::Rejected),
+];
+
+fn build_finding(severity: Severity, text: &str, line: usize) -> ReviewFinding {
+    let mut description = text.to_string();
+    let mut status = FindingStatus::Open;
+
+    for (marker, marker_status) in STATUS_MARKERS {
+        if let Some(pos) = description.to_lowercase().find(marker) {
+            status = *marker_status;
+            description.replace_range(pos..pos + marker.len(), "");
+        }
+    }
+
+    let location = description
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .find_map(FileReference::parse)
+        .or_else(|| {
+            description
+                .split_whitespace()
+                .find_map(FileReference::parse)
+        });
+
+    ReviewFinding {
+        severity,
+        status,
+        location,
+        description: description.split_whitespace().collect::<Vec<_>>().join(" "),
+        details: Vec::new(),
+        line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Review
+
+Overall the change looks good.
+
+## Findings
+
+- **critical** `src/auth.rs:42` Password compared with `==` instead of constant time
+  - Use `subtle::ConstantTimeEq`
+- [major] src/api.rs:10-12 Missing error handling (rejected)
+- minor: Rename `tmp` to something clearer
+- **info** Consider adding docs (accepted)
+- Not a finding, just a remark
+
+```
+- **critical** inside a code block
+```
+";
+
+    #[test]
+    fn test_parse_findings() {
+        let review = Review::parse(SAMPLE);
+        assert_eq!(review.findings.len(), 4);
+
+        let severities: Vec<Severity> = review.findings.iter().map(|f| f.severity).collect();
+        assert_eq!(
+            severities,
+            vec![
+                Severity::Critical,
+                Severity::Major,
+                Severity::Minor,
+                Severity::Info
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_locations() {
+        let review = Review::parse(SAMPLE);
+
+        assert_eq!(
+            review.findings[0].location,
+            Some(FileReference {
+                path: "src/auth.rs".to_string(),
+                line: Some(42),
+                end_line: None,
+            })
+        );
+        assert_eq!(
+            review.findings[1].location,
+            Some(FileReference {
+                path: "src/api.rs".to_string(),
+                line: Some(10),
+                end_line: Some(12),
+            })
+        );
+        assert_eq!(review.findings[2].location, None);
+    }
+
+    #[test]
+    fn test_parse_status_markers() {
+        let review = Review::parse(SAMPLE);
+
+        assert_eq!(review.findings[0].status, FindingStatus::Open);
+        assert_eq!(review.findings[1].status, FindingStatus::Rejected);
+        assert_eq!(
+            review.findings[1].description,
+            "src/api.rs:10-12 Missing error handling"
+        );
+        assert_eq!(review.findings[3].status, FindingStatus::Accepted);
+    }
+
+    #[test]
+    fn test_parse_details_and_line_numbers() {
+        let review = Review::parse(SAMPLE);
+        assert_eq!(
+            review.findings[0].details,
+            vec!["Use `subtle::ConstantTimeEq`"]
+        );
+        assert_eq!(review.findings[0].line, 7);
+        assert_eq!(review.findings[1].line, 9);
+    }
+
+    #[test]
+    fn test_blocking_findings_exclude_rejected_and_minor() {
+        let review = Review::parse(SAMPLE);
+        let blocking = review.blocking_findings();
+
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].severity, Severity::Critical);
+        assert!(review.has_blocking_findings());
+    }
+
+    #[test]
+    fn test_heading_sets_severity() {
+        let review = Review::parse(
+            "## Major\n\n- `src/lib.rs:3` Leaks a file handle\n\n## Notes\n\n- Nice work\n",
+        );
+
+        assert_eq!(review.findings.len(), 1);
+        assert_eq!(review.findings[0].severity, Severity::Major);
+        assert_eq!(review.findings[0].location.as_ref().unwrap().line, Some(3));
+    }
+
+    #[test]
+    fn test_clean_review_has_no_blocking_findings() {
+        let review = Review::parse("# Review\n\nNo issues found. LGTM.\n");
+        assert!(review.findings.is_empty());
+        assert!(!review.has_blocking_findings());
+    }
+
+    #[test]
+    fn test_severity_labels() {
+        assert_eq!(Severity::from_label("Blocker"), Some(Severity::Critical));
+        assert_eq!(Severity::from_label("HIGH:"), Some(Severity::Major));
+        assert_eq!(Severity::from_label("nit"), Some(Severity::Info));
+        assert_eq!(Severity::from_label("Findings"), None);
+        assert!(Severity::Major.is_blocking());
+        assert!(!Severity::Minor.is_blocking());
+    }
+
+    #[test]
+    fn test_file_reference_parse() {
+        assert_eq!(
+            FileReference::parse("src/main.rs#L7"),
+            Some(FileReference {
+                path: "src/main.rs".to_string(),
+                line: Some(7),
+                end_line: None,
+            })
+        );
+        assert_eq!(
+            FileReference::parse("Cargo.toml"),
+            Some(FileReference {
+                path: "Cargo.toml".to_string(),
+                line: None,
+                end_line: None,
+            })
+        );
+        assert_eq!(FileReference::parse("instead"), None);
+        assert_eq!(FileReference::parse("note:"), None);
+        assert_eq!(
+            format!("{}", FileReference::parse("src/a.rs:1-2,").unwrap()),
+            "src/a.rs:1-2"
+        );
+    }
+
+    #[test]
+    fn test_to_todo_item() {
+        let review = Review::parse(SAMPLE);
+        assert_eq!(
+            review.findings[0].to_todo_item(),
+            "- [ ] **critical** `src/auth.rs:42` Password compared with `==` instead of constant time"
+        );
+        assert_eq!(
+            review.findings[2].to_todo_item(),
+            "- [ ] **minor** Rename `tmp` to something clearer"
+        );
+    }
+
+    #[test]
+    fn test_append_fix_round() {
+        let review = Review::parse(SAMPLE);
+        let todo = "# Todo\n\n- [x] Build it\n";
+
+        let (first, round) = append_fix_round(todo, &review);
+        assert_eq!(round, 1);
+        assert!(first.contains("## Review round 1\n\n- [ ] **critical**"));
+
+        let parsed = TodoList::parse(&first);
+        assert_eq!(parsed.progress().total, 2);
+        assert!(!parsed.is_complete());
+
+        let (second, round) = append_fix_round(&first, &review);
+        assert_eq!(round, 2);
+        assert!(second.contains("## Review round 2"));
+        assert_eq!(next_fix_round(&TodoList::parse(&second)), 3);
+    }
+}
@@ -0,0 +1,229 @@
+use std::path::Path;
+
+use crate::litellm_config::LiteLLMConfig;
+use crate::shell::ShellDialect;
+
+/// Text substituted for a secret in log output
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Where the LiteLLM API key handed to assistants comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeySource<'a> {
+    /// The key itself, from the `api_key` setting
+    Inline(&'a str),
+    /// A file holding the key, read by the pane's shell so the plugin never sees it
+    KeyFile(&'a Path),
+}
+
+impl<'a> ApiKeySource<'a> {
+    /// Pick the key source from the LiteLLM configuration, preferring a key file
+    pub fn from_config(config: &'a LiteLLMConfig) -> Option<Self> {
+        match &config.api_key_file {
+            Some(path) if !path.is_empty() => Some(ApiKeySource::KeyFile(Path::new(path))),
+            _ if !config.api_key.is_empty() => Some(ApiKeySource::Inline(&config.api_key)),
+            _ => None,
+        }
+    }
+
+    /// Shell expression that yields the key
+    fn shell_value(&self) -> String {
+        match self {
+            ApiKeySource::Inline(key) => ShellDialect::Posix.quote(key),
+            ApiKeySource::KeyFile(path) => format!(
+                "\"$(cat {})\"",
+                ShellDialect::Posix.quote(&path.to_string_lossy())
+            ),
+        }
+    }
+}
+
+/// Render an env file that exports the API key under each of `names`
+///
+/// The file is meant to be sourced by a POSIX shell right before the
+/// assistant starts, so the key never appears on the command line.
+pub fn env_file_content(names: &[String], source: &ApiKeySource) -> String {
+    let value = source.shell_value();
+    names
+        .iter()
+        .map(|name| format!("export {}={}\n", name, value))
+        .collect()
+}
+
+/// Context key naming the role whose env file a host command writes
+pub const ENV_FILE_ROLE_CONTEXT_KEY: &str = "zzz_env_file";
+
+/// Context key naming the task directory of that env file
+pub const ENV_FILE_TASK_CONTEXT_KEY: &str = "zzz_env_task";
+
+/// Environment variable the host-side writer reads the file content from
+pub const PRIVATE_CONTENT_VARIABLE: &str = "ZZZ_PRIVATE_CONTENT";
+
+/// Host command that writes `$ZZZ_PRIVATE_CONTENT` to `path`, readable by the owner only
+///
+/// Under WASI the plugin cannot set file modes, so the host's shell creates the
+/// file under `umask 077` and renames it into place. The content travels in the
+/// command's environment, never in its arguments, so the process list does not
+/// show it.
+pub fn private_file_command(path: &str) -> Vec<String> {
+    let script = format!(
+        "umask 077 && rm -f \"$1.tmp\" && printf '%s' \"${}\" > \"$1.tmp\" && mv -f \"$1.tmp\" \"$1\"",
+        PRIVATE_CONTENT_VARIABLE
+    );
+    ["sh", "-c", &script, "sh", path]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// Masks configured secrets in text before it is written to a log
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    /// Create a redactor for the secrets in the LiteLLM configuration
+    pub fn from_config(config: &LiteLLMConfig) -> Self {
+        let mut redactor = Self::default();
+        redactor.add(&config.api_key);
+        redactor
+    }
+
+    /// Register a secret to mask; empty strings are ignored
+    pub fn add(&mut self, secret: &str) {
+        let secret = secret.trim();
+        if secret.is_empty() {
+            return;
+        }
+
+        // Also catch the forms a secret takes once quoted for a shell or JSON
+        for form in [
+            secret.to_string(),
+            secret.replace('"', "\\\""),
+            secret.replace('\'', "'\\''"),
+        ]
+        .iter()
+        {
+            if !self.secrets.contains(form) {
+                self.secrets.push(form.clone());
+            }
+        }
+
+        // Replace longer forms first so a shorter one never splits them
+        self.secrets
+            .sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+    }
+
+    /// Replace every occurrence of a registered secret with `[REDACTED]`
+    pub fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), REDACTED)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(api_key: &str, api_key_file: Option<&str>) -> LiteLLMConfig {
+        LiteLLMConfig {
+            api_key: api_key.to_string(),
+            api_key_file: api_key_file.map(str::to_string),
+            url: "https://litellm.test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_key_source_prefers_key_file() {
+        let with_file = config("sk-inline", Some("/home/me/.litellm-key"));
+        assert_eq!(
+            ApiKeySource::from_config(&with_file),
+            Some(ApiKeySource::KeyFile(Path::new("/home/me/.litellm-key")))
+        );
+
+        let inline = config("sk-inline", None);
+        assert_eq!(
+            ApiKeySource::from_config(&inline),
+            Some(ApiKeySource::Inline("sk-inline"))
+        );
+
+        assert_eq!(ApiKeySource::from_config(&config("", Some(""))), None);
+    }
+
+    #[test]
+    fn test_env_file_content_inline_key() {
+        let names = vec!["OPENAI_API_KEY".to_string()];
+        assert_eq!(
+            env_file_content(&names, &ApiKeySource::Inline("sk-it's")),
+            "export OPENAI_API_KEY='sk-it'\\''s'\n"
+        );
+    }
+
+    #[test]
+    fn test_env_file_content_key_file() {
+        let names = vec![
+            "ANTHROPIC_AUTH_TOKEN".to_string(),
+            "OPENAI_API_KEY".to_string(),
+        ];
+        assert_eq!(
+            env_file_content(&names, &ApiKeySource::KeyFile(Path::new("/keys/litellm"))),
+            "export ANTHROPIC_AUTH_TOKEN=\"$(cat /keys/litellm)\"\n\
+             export OPENAI_API_KEY=\"$(cat /keys/litellm)\"\n"
+        );
+    }
+
+    #[test]
+    fn test_redact_masks_api_key() {
+        let redactor = Redactor::from_config(&config("sk-secret-123", None));
+        assert_eq!(
+            redactor.redact("OPENAI_API_KEY=\"sk-secret-123\" codex --quiet"),
+            "OPENAI_API_KEY=\"[REDACTED]\" codex --quiet"
+        );
+        assert_eq!(redactor.redact("nothing to hide"), "nothing to hide");
+    }
+
+    #[test]
+    fn test_redact_masks_quoted_forms() {
+        let mut redactor = Redactor::default();
+        redactor.add("a\"b'c");
+        assert_eq!(redactor.redact("x=a\\\"b'c"), "x=[REDACTED]");
+        assert_eq!(redactor.redact("x='a\"b'\\''c'"), "x='[REDACTED]'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_file_command_writes_owner_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::process::Command;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("overseer.env");
+        let content = env_file_content(
+            &["OPENAI_API_KEY".to_string()],
+            &ApiKeySource::Inline("sk-'secret'"),
+        );
+        let command = private_file_command(&path.to_string_lossy());
+        assert!(command.iter().all(|arg| !arg.contains("secret")));
+
+        for _ in 0..2 {
+            let status = Command::new(&command[0])
+                .args(&command[1..])
+                .env(PRIVATE_CONTENT_VARIABLE, &content)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!path.with_extension("env.tmp").exists());
+    }
+
+    #[test]
+    fn test_empty_key_redacts_nothing() {
+        let redactor = Redactor::from_config(&config("", None));
+        assert_eq!(redactor.redact("plain text"), "plain text");
+    }
+}
@@ -0,0 +1,380 @@
+use std::fmt;
+
+/// Shell running in a pane, which decides how command words are quoted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ShellDialect {
+    #[default]
+    Posix,
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl fmt::Display for ShellDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl ShellDialect {
+    /// Parse a shell name from the plugin configuration
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "sh" | "posix" | "dash" => Some(ShellDialect::Posix),
+            "bash" => Some(ShellDialect::Bash),
+            "zsh" => Some(ShellDialect::Zsh),
+            "fish" => Some(ShellDialect::Fish),
+            _ => None,
+        }
+    }
+
+    /// Name of the shell's executable
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShellDialect::Posix => "sh",
+            ShellDialect::Bash => "bash",
+            ShellDialect::Zsh => "zsh",
+            ShellDialect::Fish => "fish",
+        }
+    }
+
+    /// Check whether the shell understands POSIX sh syntax
+    pub fn is_posix_compatible(&self) -> bool {
+        *self != ShellDialect::Fish
+    }
+
+    /// Quote `word` so the shell passes it through as a single literal argument
+    ///
+    /// Words made only of characters that no shell treats specially are left
+    /// bare. Everything else is single-quoted, which disables expansion of
+    /// `$`, backticks, `\` and `!` history references in every supported shell.
+    /// Control characters other than newline are spelled as escapes outside
+    /// the quotes, since a line editor acts on them (a tab completes) when the
+    /// command is typed into the pane.
+    pub fn quote(&self, word: &str) -> String {
+        if is_safe_word(word) {
+            return word.to_string();
+        }
+
+        let mut quoted = String::from("'");
+        for c in word.chars() {
+            match (self, c) {
+                (_, c) if is_line_editing_control(c) => quoted.push_str(&match self {
+                    ShellDialect::Fish => format!("'\\x{:02x}'", c as u32),
+                    _ => format!("'\"$(printf '\\{:03o}')\"'", c as u32),
+                }),
+                // Nothing is special inside single quotes, so a quote is
+                // closed, escaped and reopened
+                (ShellDialect::Posix | ShellDialect::Bash | ShellDialect::Zsh, '\'') => {
+                    quoted.push_str("'\\''")
+                }
+                // fish honours `\\` and `\'` inside single quotes
+                (ShellDialect::Fish, '\\' | '\'') => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                _ => quoted.push(c),
+            }
+        }
+        quoted.push('\'');
+        quoted
+    }
+
+    /// Run a POSIX sh `script` in a subshell of this shell
+    ///
+    /// The script's variables and options stay out of the pane's shell; fish
+    /// cannot parse sh syntax, so it hands the script to `sh -c`.
+    pub fn subshell(&self, script: &str) -> String {
+        if self.is_posix_compatible() {
+            format!("({})", script)
+        } else {
+            format!("sh -c {}", self.quote(script))
+        }
+    }
+
+    /// Quote and join words into a command line
+    pub fn join<S: AsRef<str>>(&self, words: &[S]) -> String {
+        words
+            .iter()
+            .map(|word| self.quote(word.as_ref()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Check whether a line editor acts on `c` instead of inserting it
+fn is_line_editing_control(c: char) -> bool {
+    c != '\n' && c.is_ascii_control()
+}
+
+/// Check whether a word can be typed unquoted in every supported shell
+fn is_safe_word(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c))
+        // A leading `=` triggers path expansion in zsh, `%` job lookup in fish
+        && !word.starts_with(&['=', '%'][..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Reference parser for a single POSIX-quoted word as produced by `quote`
+    fn posix_unquote(quoted: &str) -> String {
+        let mut out = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    for c in chars.by_ref() {
+                        if c == '\'' {
+                            break;
+                        }
+                        out.push(c);
+                    }
+                }
+                '\\' => out.extend(chars.next()),
+                // `"$(printf '\NNN')"`, the escape for a control character
+                '"' => {
+                    let escape: String = chars.by_ref().take_while(|c| *c != ')').collect();
+                    let octal = escape.trim_start_matches("$(printf '\\");
+                    let code = u32::from_str_radix(octal.trim_end_matches('\''), 8).unwrap();
+                    out.push(char::from_u32(code).unwrap());
+                    assert_eq!(chars.next(), Some('"'));
+                }
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// Reference parser for a single fish-quoted word as produced by `quote`
+    fn fish_unquote(quoted: &str) -> String {
+        let mut out = String::new();
+        let mut chars = quoted.chars();
+        let mut in_quotes = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => in_quotes = !in_quotes,
+                '\\' if in_quotes => match chars.next() {
+                    Some(next) if next == '\\' || next == '\'' => out.push(next),
+                    Some(next) => {
+                        out.push('\\');
+                        out.push(next);
+                    }
+                    None => out.push('\\'),
+                },
+                // `\xHH`, the escape for a control character
+                '\\' => {
+                    assert_eq!(chars.next(), Some('x'));
+                    let hex: String = chars.by_ref().take(2).collect();
+                    out.push(char::from(u8::from_str_radix(&hex, 16).unwrap()));
+                }
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// Flags that keep the shell from reading startup files
+    fn no_startup_files(shell: ShellDialect) -> &'static [&'static str] {
+        match shell {
+            ShellDialect::Posix => &[],
+            ShellDialect::Bash => &["--norc", "--noprofile"],
+            ShellDialect::Zsh => &["-f"],
+            ShellDialect::Fish => &["--no-config"],
+        }
+    }
+
+    /// Run `printf %s <quoted>` with `shell -c`
+    fn run_in_shell(shell: ShellDialect, quoted: &str) -> String {
+        let output = Command::new(shell.name())
+            .args(no_startup_files(shell))
+            .arg("-c")
+            .arg(format!("printf %s {}", quoted))
+            .output()
+            .unwrap_or_else(|e| panic!("{} is not installed: {}", shell, e));
+        assert!(output.status.success(), "{} failed on {}", shell, quoted);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Type `printf %s <quoted>` into an interactive shell on stdin, as a pane receives it
+    fn run_interactively(shell: ShellDialect, quoted: &str) -> String {
+        let mut child = Command::new(shell.name())
+            .args(no_startup_files(shell))
+            .arg("-i")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap_or_else(|e| panic!("{} is not installed: {}", shell, e));
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(format!("printf %s {}\n", quoted).as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Every nasty string survives both `-c` and the interactive parser
+    fn assert_nasty_strings_round_trip(shell: ShellDialect) {
+        for word in NASTY {
+            let quoted = shell.quote(word);
+            assert_eq!(
+                &run_in_shell(shell, &quoted),
+                word,
+                "{} -c mangled {:?}",
+                shell,
+                word
+            );
+            assert_eq!(
+                &run_interactively(shell, &quoted),
+                word,
+                "interactive {} mangled {:?}",
+                shell,
+                word
+            );
+        }
+    }
+
+    const NASTY: &[&str] = &[
+        "",
+        "plain",
+        "two words",
+        "it's",
+        "'",
+        "''",
+        "\\",
+        "\\'",
+        "$HOME",
+        "${HOME}",
+        "`id`",
+        "$(id)",
+        "!!",
+        "!$",
+        "line one\nline two",
+        "tab\there",
+        "cr\rlf",
+        "ctrl-a\u{1} del\u{7f}",
+        "\"double\"",
+        "*?[a]",
+        "~",
+        "=cmd",
+        "%1",
+        "a;b|c&d",
+        "{a,b}",
+        "#comment",
+        "ünïcödé ✓",
+    ];
+
+    #[test]
+    fn test_quote_leaves_safe_words_bare() {
+        for shell in [ShellDialect::Posix, ShellDialect::Fish].iter() {
+            assert_eq!(shell.quote("--quiet"), "--quiet");
+            assert_eq!(
+                shell.quote(".zzz/task-1/todo-list.md"),
+                ".zzz/task-1/todo-list.md"
+            );
+            assert_eq!(shell.quote("openai/gpt-4o"), "openai/gpt-4o");
+        }
+    }
+
+    #[test]
+    fn test_quote_posix() {
+        assert_eq!(ShellDialect::Bash.quote(""), "''");
+        assert_eq!(ShellDialect::Bash.quote("it's $HOME"), "'it'\\''s $HOME'");
+        assert_eq!(ShellDialect::Zsh.quote("=cmd"), "'=cmd'");
+        assert_eq!(ShellDialect::Posix.quote("a\\b"), "'a\\b'");
+        assert_eq!(
+            ShellDialect::Bash.quote("a\tb"),
+            "'a'\"$(printf '\\011')\"'b'"
+        );
+    }
+
+    #[test]
+    fn test_quote_fish() {
+        assert_eq!(ShellDialect::Fish.quote("it's"), "'it\\'s'");
+        assert_eq!(ShellDialect::Fish.quote("a\\b"), "'a\\\\b'");
+        assert_eq!(ShellDialect::Fish.quote("$HOME"), "'$HOME'");
+        assert_eq!(ShellDialect::Fish.quote("a\tb"), "'a'\\x09'b'");
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(
+            ShellDialect::Posix.join(&["codex", "--quiet", "Plan it"]),
+            "codex --quiet 'Plan it'"
+        );
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(ShellDialect::from_name("Bash"), Some(ShellDialect::Bash));
+        assert_eq!(ShellDialect::from_name("sh"), Some(ShellDialect::Posix));
+        assert_eq!(ShellDialect::from_name("fish"), Some(ShellDialect::Fish));
+        assert_eq!(ShellDialect::from_name("pwsh"), None);
+        assert!(!ShellDialect::Fish.is_posix_compatible());
+    }
+
+    #[test]
+    fn test_nasty_strings_round_trip_through_sh() {
+        assert_nasty_strings_round_trip(ShellDialect::Posix);
+    }
+
+    #[test]
+    fn test_nasty_strings_round_trip_through_bash() {
+        assert_nasty_strings_round_trip(ShellDialect::Bash);
+    }
+
+    #[test]
+    #[ignore = "needs zsh installed; run with --ignored"]
+    fn test_nasty_strings_round_trip_through_zsh() {
+        assert_nasty_strings_round_trip(ShellDialect::Zsh);
+    }
+
+    #[test]
+    #[ignore = "needs fish installed; run with --ignored"]
+    fn test_nasty_strings_round_trip_through_fish() {
+        assert_nasty_strings_round_trip(ShellDialect::Fish);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_posix_quote_round_trips(word in "\\PC*|[\\s\\S]*") {
+            for shell in [ShellDialect::Posix, ShellDialect::Bash, ShellDialect::Zsh].iter() {
+                prop_assert_eq!(posix_unquote(&shell.quote(&word)), word.clone());
+            }
+        }
+
+        #[test]
+        fn prop_fish_quote_round_trips(word in "\\PC*|[\\s\\S]*") {
+            prop_assert_eq!(fish_unquote(&ShellDialect::Fish.quote(&word)), word);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_quote_round_trips_through_sh_and_bash(word in "[^\\x00]*") {
+            for shell in [ShellDialect::Posix, ShellDialect::Bash].iter() {
+                prop_assert_eq!(run_in_shell(*shell, &shell.quote(&word)), word.clone());
+            }
+        }
+
+        #[test]
+        #[ignore = "needs zsh and fish installed; run with --ignored"]
+        fn prop_quote_round_trips_through_zsh_and_fish(word in "[^\\x00]*") {
+            for shell in [ShellDialect::Zsh, ShellDialect::Fish].iter() {
+                prop_assert_eq!(run_in_shell(*shell, &shell.quote(&word)), word.clone());
+            }
+        }
+    }
+}
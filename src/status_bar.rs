@@ -0,0 +1,544 @@
+use std::ops::Range;
+use zellij_tile::prelude::*;
+
+use crate::pane_role::PaneRole;
+use crate::todo_list::TodoProgress;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Separator drawn between status bar segments
+const SEPARATOR: &str = " | ";
+
+/// Cells in the todo progress bar
+const PROGRESS_CELLS: usize = 5;
+
+/// Emphasis level used for errors
+const ERROR_COLOR: usize = 3;
+
+/// What the coordinator last received, for the status bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LastMessage {
+    /// A coordination message and its receiver; `None` for broadcasts
+    Coordination {
+        name: String,
+        target: Option<String>,
+    },
+    /// A payload that was not a coordination message
+    Raw {
+        source: String,
+    },
+    Control {
+        command: String,
+        ok: bool,
+    },
+    /// A pipe trigger without a payload
+    Notice(String),
+}
+
+impl LastMessage {
+    fn full(&self) -> String {
+        match self {
+            LastMessage::Coordination {
+                name,
+                target: Some(target),
+            } => format!("{}→{}", name, target),
+            LastMessage::Coordination { name, target: None } => format!("{}→all", name),
+            LastMessage::Raw { source } => format!("Raw from {}", source),
+            LastMessage::Control { command, ok: true } => format!("zzz {}", command),
+            LastMessage::Control { command, ok: false } => format!("zzz {} failed", command),
+            LastMessage::Notice(text) => text.clone(),
+        }
+    }
+
+    fn short(&self) -> String {
+        match self {
+            LastMessage::Coordination { name, .. } => name.clone(),
+            LastMessage::Raw { .. } => "Raw".to_string(),
+            LastMessage::Control { command, .. } => command.clone(),
+            LastMessage::Notice(text) => text.clone(),
+        }
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, LastMessage::Control { ok: false, .. })
+    }
+}
+
+/// Coordinator state shown in the one-line status bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBar {
+    pub phase: WorkflowPhase,
+    pub permissions_granted: bool,
+    pub registered_roles: Vec<PaneRole>,
+    pub progress: Option<TodoProgress>,
+    pub last_message: Option<LastMessage>,
+    pub message_count: usize,
+    pub paused: bool,
+    pub aborted: bool,
+    /// Assistant work lost to exited panes
+    pub failed_work: usize,
+}
+
+/// A status bar line and the emphasis of its character ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledLine {
+    pub text: String,
+    pub colors: Vec<(usize, Range<usize>)>,
+}
+
+/// A piece of the status bar with progressively shorter forms
+#[derive(Debug, Clone)]
+struct Segment {
+    /// Longest first; an empty form drops the segment
+    forms: Vec<String>,
+    /// Segments with lower priority are shortened and dropped first
+    priority: u8,
+    color: Option<usize>,
+}
+
+impl Segment {
+    fn new(forms: &[&str], priority: u8, color: Option<usize>) -> Self {
+        Self {
+            forms: forms.iter().map(|form| form.to_string()).collect(),
+            priority,
+            color,
+        }
+    }
+}
+
+impl StatusBar {
+    /// Lay out the status bar to fit in `cols` characters
+    ///
+    /// Low-priority segments are shortened first, then dropped, and the
+    /// line is cut with an ellipsis when even the essentials do not fit.
+    pub fn layout(&self, cols: usize) -> StyledLine {
+        let segments = self.segments();
+        let mut levels = vec![0; segments.len()];
+
+        loop {
+            let line = compose(&segments, &levels);
+            if line.text.chars().count() <= cols {
+                return line;
+            }
+            match next_step(&segments, &levels) {
+                Some(index) => levels[index] += 1,
+                None => return truncate(line, cols),
+            }
+        }
+    }
+
+    /// Print the status bar on the first line of the pane
+    pub fn render(&self, cols: usize) {
+        let line = self.layout(cols);
+        let text = line
+            .colors
+            .into_iter()
+            .fold(Text::new(&line.text), |text, (level, range)| {
+                text.color_range(level, range)
+            });
+        print_text_with_coordinates(text, 0, 0, Some(cols), None);
+    }
+
+    fn segments(&self) -> Vec<Segment> {
+        let mut segments = vec![Segment::new(&["ZZZ", ""], 2, None)];
+
+        let phase = phase_label(self.phase);
+        segments.push(Segment::new(
+            &[&format!("Phase: {}", phase), phase],
+            9,
+            phase_color(self.phase),
+        ));
+
+        if self.aborted {
+            segments.push(Segment::new(&["ABORTED", "✗"], 8, Some(ERROR_COLOR)));
+        } else if self.paused {
+            segments.push(Segment::new(&["PAUSED", "‖"], 7, Some(0)));
+        }
+        if self.failed_work > 0 {
+            segments.push(Segment::new(
+                &[
+                    &format!("Failed: {}", self.failed_work),
+                    &format!("✗{}", self.failed_work),
+                ],
+                8,
+                Some(ERROR_COLOR),
+            ));
+        }
+
+        let (perms, perms_color) = if self.permissions_granted {
+            ("✓", None)
+        } else {
+            ("✗", Some(ERROR_COLOR))
+        };
+        segments.push(Segment::new(
+            &[&format!("Perms: {}", perms), perms, ""],
+            3,
+            perms_color,
+        ));
+
+        let count = format!("{}/{}", self.registered_roles.len(), PaneRole::ALL.len());
+        let icons: Vec<&str> = self.registered_roles.iter().map(role_initial).collect();
+        let panes = if icons.is_empty() {
+            format!("Panes: None ({})", count)
+        } else {
+            format!("Panes: {} ({})", icons.join(","), count)
+        };
+        segments.push(Segment::new(&[&panes, &count, ""], 4, None));
+
+        if let Some(progress) = &self.progress {
+            let percentage = format!("{}%", progress.percentage());
+            let full = format!(
+                "Todo: {} {}/{} ({})",
+                progress_bar(progress, PROGRESS_CELLS),
+                progress.completed,
+                progress.total,
+                percentage
+            );
+            let complete = progress.total > 0 && progress.completed == progress.total;
+            segments.push(Segment::new(
+                &[&full, &percentage, ""],
+                6,
+                if complete { Some(2) } else { None },
+            ));
+        }
+
+        let (last_full, last_short, last_color) = match &self.last_message {
+            Some(message) => (
+                message.full(),
+                message.short(),
+                if message.is_error() {
+                    Some(ERROR_COLOR)
+                } else {
+                    None
+                },
+            ),
+            None => ("None".to_string(), "None".to_string(), None),
+        };
+        segments.push(Segment::new(
+            &[&format!("Last: {}", last_full), &last_short, ""],
+            5,
+            last_color,
+        ));
+
+        segments.push(Segment::new(
+            &[&format!("Msgs: {}", self.message_count), ""],
+            1,
+            None,
+        ));
+
+        segments
+    }
+}
+
+/// Abbreviated phase name
+pub fn phase_label(phase: WorkflowPhase) -> &'static str {
+    match phase {
+        WorkflowPhase::Initializing => "Init",
+        WorkflowPhase::PlanningInProgress => "Plan",
+        WorkflowPhase::PlanReady => "Plan ✓",
+        WorkflowPhase::ImplementationInProgress => "Impl",
+        WorkflowPhase::ImplementationComplete => "Impl ✓",
+        WorkflowPhase::ReviewInProgress => "Rev",
+        WorkflowPhase::ReviewComplete => "Rev ✓",
+        WorkflowPhase::Finished => "Done",
+    }
+}
+
+/// Emphasis level for a phase, so each stage of the workflow stands out
+pub fn phase_color(phase: WorkflowPhase) -> Option<usize> {
+    match phase {
+        WorkflowPhase::Initializing => None,
+        WorkflowPhase::PlanningInProgress | WorkflowPhase::PlanReady => Some(1),
+        WorkflowPhase::ImplementationInProgress | WorkflowPhase::ImplementationComplete => Some(0),
+        WorkflowPhase::ReviewInProgress
+        | WorkflowPhase::ReviewComplete
+        | WorkflowPhase::Finished => Some(2),
+    }
+}
+
+/// A bar of `cells` characters filled in proportion to completed items
+pub fn progress_bar(progress: &TodoProgress, cells: usize) -> String {
+    let filled = (progress.completed * cells)
+        .checked_div(progress.total)
+        .unwrap_or(0)
+        .min(cells);
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(cells - filled))
+}
+
+fn role_initial(role: &PaneRole) -> &'static str {
+    match role {
+        PaneRole::Overseer => "O",
+        PaneRole::Commander => "C",
+        PaneRole::TaskList => "T",
+        PaneRole::Review => "R",
+        PaneRole::Editor => "E",
+    }
+}
+
+/// Join the current form of each segment, recording coloured ranges
+fn compose(segments: &[Segment], levels: &[usize]) -> StyledLine {
+    let mut text = String::new();
+    let mut colors = Vec::new();
+
+    for (segment, level) in segments.iter().zip(levels) {
+        let form = &segment.forms[*level];
+        if form.is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str(SEPARATOR);
+        }
+        let start = text.chars().count();
+        text.push_str(form);
+        if let Some(color) = segment.color {
+            colors.push((color, start..start + form.chars().count()));
+        }
+    }
+
+    StyledLine { text, colors }
+}
+
+/// The segment to shorten next: the lowest-priority one that can still be
+/// shortened, and only once nothing can be shortened, the lowest-priority one
+/// that can be dropped
+fn next_step(segments: &[Segment], levels: &[usize]) -> Option<usize> {
+    let candidates = |dropping: bool| {
+        segments
+            .iter()
+            .zip(levels)
+            .enumerate()
+            .filter(move |(_, (segment, level))| {
+                segment
+                    .forms
+                    .get(**level + 1)
+                    .is_some_and(|form| form.is_empty() == dropping)
+            })
+            .min_by_key(|(_, (segment, _))| segment.priority)
+            .map(|(index, _)| index)
+    };
+
+    candidates(false).or_else(|| candidates(true))
+}
+
+/// Cut a line to `cols` characters, ending it with an ellipsis
+fn truncate(line: StyledLine, cols: usize) -> StyledLine {
+    if cols == 0 {
+        return StyledLine {
+            text: String::new(),
+            colors: Vec::new(),
+        };
+    }
+
+    let keep = cols - 1;
+    let mut text: String = line.text.chars().take(keep).collect();
+    text.push('…');
+    let colors = line
+        .colors
+        .into_iter()
+        .filter(|(_, range)| range.start < keep)
+        .map(|(level, range)| (level, range.start..range.end.min(keep)))
+        .collect();
+
+    StyledLine { text, colors }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_bar() -> StatusBar {
+        StatusBar {
+            phase: WorkflowPhase::ImplementationInProgress,
+            permissions_granted: true,
+            registered_roles: vec![PaneRole::Overseer, PaneRole::Commander],
+            progress: Some(TodoProgress {
+                completed: 3,
+                total: 8,
+            }),
+            last_message: Some(LastMessage::Coordination {
+                name: "StartImplementation".to_string(),
+                target: Some("Commander".to_string()),
+            }),
+            message_count: 4,
+            paused: false,
+            aborted: false,
+            failed_work: 0,
+        }
+    }
+
+    #[test]
+    fn test_full_width_shows_every_segment() {
+        let line = status_bar().layout(200);
+
+        assert_eq!(
+            line.text,
+            "ZZZ | Phase: Impl | Perms: ✓ | Panes: O,C (2/5) | Todo: ▰▱▱▱▱ 3/8 (37%) | \
+             Last: StartImplementation→Commander | Msgs: 4"
+        );
+        assert_eq!(line.colors, vec![(0, 6..17)]);
+    }
+
+    #[test]
+    fn test_narrow_width_shortens_before_dropping() {
+        let full = status_bar().layout(200).text.chars().count();
+        let line = status_bar().layout(full - 1);
+
+        // The message count has no short form, so the permissions shrink first
+        assert_eq!(
+            line.text,
+            "ZZZ | Phase: Impl | ✓ | Panes: O,C (2/5) | Todo: ▰▱▱▱▱ 3/8 (37%) | \
+             Last: StartImplementation→Commander | Msgs: 4"
+        );
+    }
+
+    #[test]
+    fn test_layout_always_fits() {
+        let bar = status_bar();
+        for cols in 0..150 {
+            let line = bar.layout(cols);
+            assert!(line.text.chars().count() <= cols, "{} cols", cols);
+            for (_, range) in &line.colors {
+                assert!(range.end <= line.text.chars().count());
+            }
+        }
+    }
+
+    #[test]
+    fn test_very_narrow_keeps_phase_and_progress() {
+        let line = status_bar().layout(20);
+        assert_eq!(line.text, "Impl | 37%");
+
+        let line = status_bar().layout(12);
+        assert_eq!(line.text, "Impl | 37%");
+        assert_eq!(line.colors, vec![(0, 0..4)]);
+
+        let line = status_bar().layout(6);
+        assert_eq!(line.text, "Impl");
+    }
+
+    #[test]
+    fn test_truncates_with_ellipsis_when_nothing_fits() {
+        let line = status_bar().layout(3);
+
+        assert_eq!(line.text, "Im…");
+        assert_eq!(line.colors, vec![(0, 0..2)]);
+        assert_eq!(status_bar().layout(0).text, "");
+    }
+
+    #[test]
+    fn test_error_states_are_highlighted() {
+        let bar = StatusBar {
+            aborted: true,
+            failed_work: 2,
+            permissions_granted: false,
+            last_message: Some(LastMessage::Control {
+                command: "advance".to_string(),
+                ok: false,
+            }),
+            ..status_bar()
+        };
+        let line = bar.layout(200);
+
+        assert!(line.text.contains("ABORTED | Failed: 2 | Perms: ✗"));
+        assert!(line.text.contains("Last: zzz advance failed"));
+        let highlighted: Vec<String> = line
+            .colors
+            .iter()
+            .filter(|(level, _)| *level == ERROR_COLOR)
+            .map(|(_, range)| {
+                line.text
+                    .chars()
+                    .skip(range.start)
+                    .take(range.len())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            highlighted,
+            vec![
+                "ABORTED",
+                "Failed: 2",
+                "Perms: ✗",
+                "Last: zzz advance failed"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paused_and_error_segments_outlast_progress() {
+        let bar = StatusBar {
+            paused: true,
+            failed_work: 1,
+            ..status_bar()
+        };
+
+        assert_eq!(bar.layout(24).text, "Impl | ‖ | ✗1 | 37%");
+        assert_eq!(bar.layout(14).text, "Impl | ‖ | ✗1");
+    }
+
+    #[test]
+    fn test_phase_colors() {
+        assert_eq!(phase_color(WorkflowPhase::Initializing), None);
+        assert_eq!(phase_color(WorkflowPhase::PlanReady), Some(1));
+        assert_eq!(phase_color(WorkflowPhase::ImplementationComplete), Some(0));
+        assert_eq!(phase_color(WorkflowPhase::Finished), Some(2));
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        let progress = |completed, total| TodoProgress { completed, total };
+
+        assert_eq!(progress_bar(&progress(0, 0), 5), "▱▱▱▱▱");
+        assert_eq!(progress_bar(&progress(3, 8), 5), "▰▱▱▱▱");
+        assert_eq!(progress_bar(&progress(4, 8), 5), "▰▰▱▱▱");
+        assert_eq!(progress_bar(&progress(8, 8), 5), "▰▰▰▰▰");
+    }
+
+    #[test]
+    fn test_completed_todo_list_is_highlighted() {
+        let bar = StatusBar {
+            progress: Some(TodoProgress {
+                completed: 8,
+                total: 8,
+            }),
+            ..status_bar()
+        };
+        let line = bar.layout(200);
+
+        let (level, range) = line.colors[1].clone();
+        assert_eq!(level, 2);
+        let highlighted: String = line
+            .text
+            .chars()
+            .skip(range.start)
+            .take(range.len())
+            .collect();
+        assert_eq!(highlighted, "Todo: ▰▰▰▰▰ 8/8 (100%)");
+    }
+
+    #[test]
+    fn test_last_message_forms() {
+        let broadcast = LastMessage::Coordination {
+            name: "StartReview".to_string(),
+            target: None,
+        };
+        assert_eq!(broadcast.full(), "StartReview→all");
+        assert_eq!(broadcast.short(), "StartReview");
+
+        let raw = LastMessage::Raw {
+            source: "CLI".to_string(),
+        };
+        assert_eq!(raw.full(), "Raw from CLI");
+        assert_eq!(raw.short(), "Raw");
+
+        let bar = StatusBar {
+            last_message: None,
+            registered_roles: Vec::new(),
+            progress: None,
+            ..status_bar()
+        };
+        assert_eq!(
+            bar.layout(200).text,
+            "ZZZ | Phase: Impl | Perms: ✓ | Panes: None (0/5) | Last: None | Msgs: 4"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest accepted task ID
+pub const MAX_TASK_ID_LEN: usize = 64;
+
+/// Prefix of a task's directory name
+pub const TASK_DIRECTORY_PREFIX: &str = "task-";
+
+/// Reasons a string cannot be used as a task ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskIdError {
+    Empty,
+    TooLong(usize),
+    InvalidCharacter(char),
+    /// IDs must start with a letter or digit, which rules out `.`, `..` and
+    /// option-like `-x`
+    InvalidStart(char),
+}
+
+impl fmt::Display for TaskIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskIdError::Empty => write!(f, "Task ID is empty"),
+            TaskIdError::TooLong(len) => write!(
+                f,
+                "Task ID is {} characters long (at most {})",
+                len, MAX_TASK_ID_LEN
+            ),
+            TaskIdError::InvalidCharacter(c) => write!(
+                f,
+                "Task ID contains '{}' (only letters, digits, '-', '_' and '.' are allowed)",
+                c
+            ),
+            TaskIdError::InvalidStart(c) => {
+                write!(
+                    f,
+                    "Task ID starts with '{}' instead of a letter or digit",
+                    c
+                )
+            }
+        }
+    }
+}
+
+/// Identifier of a task, such as an issue tracker key like `PROJ-1234`
+///
+/// The ID names the task's `task-{id}` directory, so it is limited to ASCII
+/// letters, digits, `-`, `_` and `.`, and starts with a letter or digit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(String);
+
+impl Default for TaskId {
+    fn default() -> Self {
+        TaskId::from(0)
+    }
+}
+
+impl TaskId {
+    /// Validate a task ID, ignoring surrounding whitespace
+    pub fn parse(value: &str) -> Result<Self, TaskIdError> {
+        let value = value.trim();
+        let first = value.chars().next().ok_or(TaskIdError::Empty)?;
+
+        let len = value.chars().count();
+        if len > MAX_TASK_ID_LEN {
+            return Err(TaskIdError::TooLong(len));
+        }
+        if let Some(c) = value
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        {
+            return Err(TaskIdError::InvalidCharacter(c));
+        }
+        if !first.is_ascii_alphanumeric() {
+            return Err(TaskIdError::InvalidStart(first));
+        }
+
+        Ok(TaskId(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The ID as a number, for IDs from before task IDs were strings
+    pub fn as_number(&self) -> Option<u32> {
+        if self.0.bytes().all(|b| b.is_ascii_digit()) {
+            self.0.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Check whether two IDs name the same task, treating `007` and `7` alike
+    /// as numeric IDs used to be
+    pub fn is_same_task(&self, other: &TaskId) -> bool {
+        self == other
+            || self
+                .as_number()
+                .is_some_and(|n| other.as_number() == Some(n))
+    }
+
+    /// Name of the task's directory, `task-{id}`
+    pub fn directory_name(&self) -> String {
+        format!("{}{}", TASK_DIRECTORY_PREFIX, self.0)
+    }
+
+    /// Task ID of a `task-{id}` directory name
+    pub fn from_directory_name(name: &str) -> Option<Self> {
+        name.strip_prefix(TASK_DIRECTORY_PREFIX)
+            .and_then(|id| TaskId::parse(id).ok())
+    }
+}
+
+impl From<u32> for TaskId {
+    fn from(id: u32) -> Self {
+        TaskId(id.to_string())
+    }
+}
+
+impl FromStr for TaskId {
+    type Err = TaskIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        TaskId::parse(value)
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for TaskId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Task IDs were numbers in older snapshots and messages
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTaskId {
+    Number(u32),
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for TaskId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RawTaskId::deserialize(deserializer)? {
+            RawTaskId::Number(id) => Ok(TaskId::from(id)),
+            RawTaskId::Text(id) => TaskId::parse(&id).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_accepts_slugs() {
+        for id in ["PROJ-1234", "42", "fix_login.v2", "a"].iter() {
+            assert_eq!(TaskId::parse(id).unwrap().as_str(), *id);
+        }
+        assert_eq!(TaskId::parse("  PROJ-1 \n").unwrap().as_str(), "PROJ-1");
+    }
+
+    #[test]
+    fn test_parse_rejects_path_traversal() {
+        assert_eq!(
+            TaskId::parse("../etc"),
+            Err(TaskIdError::InvalidCharacter('/'))
+        );
+        assert_eq!(TaskId::parse(".."), Err(TaskIdError::InvalidStart('.')));
+        assert_eq!(
+            TaskId::parse("a\\b"),
+            Err(TaskIdError::InvalidCharacter('\\'))
+        );
+        assert_eq!(
+            TaskId::parse("PROJ 1"),
+            Err(TaskIdError::InvalidCharacter(' '))
+        );
+        assert_eq!(TaskId::parse("-rf"), Err(TaskIdError::InvalidStart('-')));
+        assert_eq!(TaskId::parse("   "), Err(TaskIdError::Empty));
+        assert_eq!(
+            TaskId::parse("tâche"),
+            Err(TaskIdError::InvalidCharacter('â'))
+        );
+    }
+
+    #[test]
+    fn test_parse_limits_length() {
+        let longest = "a".repeat(MAX_TASK_ID_LEN);
+        assert!(TaskId::parse(&longest).is_ok());
+        assert_eq!(
+            TaskId::parse(&format!("{}a", longest)),
+            Err(TaskIdError::TooLong(MAX_TASK_ID_LEN + 1))
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            TaskIdError::InvalidCharacter('/').to_string(),
+            "Task ID contains '/' (only letters, digits, '-', '_' and '.' are allowed)"
+        );
+        assert_eq!(
+            TaskIdError::InvalidStart('.').to_string(),
+            "Task ID starts with '.' instead of a letter or digit"
+        );
+    }
+
+    #[test]
+    fn test_directory_name_roundtrip() {
+        let id = TaskId::parse("PROJ-1234").unwrap();
+        assert_eq!(id.directory_name(), "task-PROJ-1234");
+        assert_eq!(TaskId::from_directory_name("task-PROJ-1234"), Some(id));
+        assert_eq!(TaskId::from_directory_name("logs"), None);
+        assert_eq!(TaskId::from_directory_name("task-"), None);
+    }
+
+    #[test]
+    fn test_numeric_ids() {
+        assert_eq!(TaskId::default().as_str(), "0");
+        assert_eq!(TaskId::from(42).as_number(), Some(42));
+        assert_eq!(TaskId::parse("007").unwrap().as_number(), Some(7));
+        assert_eq!(TaskId::parse("PROJ-7").unwrap().as_number(), None);
+
+        let padded = TaskId::parse("007").unwrap();
+        assert!(padded.is_same_task(&TaskId::from(7)));
+        assert!(!padded.is_same_task(&TaskId::from(8)));
+        assert!(!TaskId::parse("PROJ-7")
+            .unwrap()
+            .is_same_task(&TaskId::from(7)));
+    }
+
+    #[test]
+    fn test_serde_accepts_numbers_and_writes_strings() {
+        let id: TaskId = serde_json::from_str("123").unwrap();
+        assert_eq!(id, TaskId::from(123));
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"123\"");
+
+        let id: TaskId = serde_json::from_str("\"PROJ-1\"").unwrap();
+        assert_eq!(id.as_str(), "PROJ-1");
+        assert!(serde_json::from_str::<TaskId>("\"../x\"").is_err());
+        assert!(serde_json::from_str::<TaskId>("-1").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_valid_ids_stay_inside_their_directory(id in "\\PC{0,80}") {
+            if let Ok(task_id) = TaskId::parse(&id) {
+                let name = task_id.directory_name();
+                let mut components = std::path::Path::new(&name).components();
+                prop_assert!(matches!(components.next(), Some(std::path::Component::Normal(_))));
+                prop_assert!(components.next().is_none());
+                prop_assert!(!name.contains('\\'));
+                prop_assert_eq!(TaskId::from_directory_name(&name), Some(task_id));
+            }
+        }
+    }
+}
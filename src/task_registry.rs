@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::file_system::{FileSystem, FileSystemError};
+use crate::task_id::TaskId;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Current `index.json` format version
+pub const REGISTRY_VERSION: u32 = 1;
+
+/// Errors that can occur while reading or writing the task registry
+#[derive(Debug)]
+pub enum RegistryError {
+    /// Reading or writing an index or entry file failed
+    FileSystem(FileSystemError),
+    /// An index or entry could not be (de)serialized
+    Serialization(serde_json::Error),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::FileSystem(e) => write!(f, "Task index file error: {:?}", e),
+            RegistryError::Serialization(e) => {
+                write!(f, "Task index serialization failed: {}", e)
+            }
+        }
+    }
+}
+
+impl From<FileSystemError> for RegistryError {
+    fn from(error: FileSystemError) -> Self {
+        RegistryError::FileSystem(error)
+    }
+}
+
+impl From<serde_json::Error> for RegistryError {
+    fn from(error: serde_json::Error) -> Self {
+        RegistryError::Serialization(error)
+    }
+}
+
+/// One task, as kept in its `entry.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskEntry {
+    pub task_id: TaskId,
+    pub description: String,
+    pub phase: WorkflowPhase,
+    /// Unix timestamp when the task was first registered
+    pub created_at: u64,
+    /// Unix timestamp of the last recorded change
+    pub updated_at: u64,
+}
+
+/// Every task under the task root, shared by the coordinators of all tabs
+///
+/// Each coordinator writes only its own task's `entry.json`, so tabs never
+/// overwrite each other's entries. `index.json` is rebuilt from the entries
+/// after every update for tools outside the plugin; a rebuild racing another
+/// tab's may miss that tab's latest change until its next update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRegistry {
+    /// Index format version
+    pub version: u32,
+    /// Tasks in the order they were first registered
+    pub tasks: Vec<TaskEntry>,
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self {
+            version: REGISTRY_VERSION,
+            tasks: Vec::new(),
+        }
+    }
+}
+
+impl TaskRegistry {
+    /// Load every task entry under the root of `file_system`
+    ///
+    /// Entries that cannot be read are skipped and returned with their path,
+    /// so one broken task does not hide the others.
+    pub fn load_all(
+        file_system: &FileSystem,
+    ) -> Result<(Self, Vec<(PathBuf, RegistryError)>), RegistryError> {
+        let mut registry = Self::default();
+        let mut skipped = Vec::new();
+
+        let dirs = match fs::read_dir(file_system.root()) {
+            Ok(dirs) => dirs,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((registry, skipped)),
+            Err(e) => return Err(FileSystemError::from(e).into()),
+        };
+        for dir in dirs.flatten() {
+            let name = dir.file_name().to_string_lossy().into_owned();
+            let path = match TaskId::from_directory_name(&name) {
+                Some(task_id) => file_system.get_task_entry_path(&task_id),
+                None => continue,
+            };
+            match Self::load_entry(&path) {
+                Ok(entry) => registry.tasks.extend(entry),
+                Err(e) => skipped.push((path, e)),
+            }
+        }
+
+        registry
+            .tasks
+            .sort_by(|a, b| (a.created_at, &a.task_id).cmp(&(b.created_at, &b.task_id)));
+        Ok((registry, skipped))
+    }
+
+    /// Load a single task's entry file, if it has been written
+    pub fn load_entry<P: AsRef<Path>>(path: P) -> Result<Option<TaskEntry>, RegistryError> {
+        let path = path.as_ref();
+        if !FileSystem::file_exists(path) {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&FileSystem::read_file_safe(
+            path,
+        )?)?))
+    }
+
+    /// Entry for a task, matching numeric IDs regardless of leading zeros
+    pub fn get(&self, task_id: &TaskId) -> Option<&TaskEntry> {
+        self.tasks
+            .iter()
+            .find(|entry| entry.task_id.is_same_task(task_id))
+    }
+
+    /// Record a task's description and phase in its entry file and rebuild `index.json`
+    ///
+    /// The creation time is kept from the existing entry. The index is written
+    /// through a temporary file in the task's own directory, so coordinators
+    /// of other tabs never write to the same temporary file.
+    pub fn update(
+        file_system: &FileSystem,
+        task_id: &TaskId,
+        description: &str,
+        phase: WorkflowPhase,
+        now: u64,
+    ) -> Result<TaskEntry, RegistryError> {
+        let path = file_system.get_task_entry_path(task_id);
+        let created_at = Self::load_entry(&path)
+            .ok()
+            .flatten()
+            .map_or(now, |entry| entry.created_at);
+
+        let entry = TaskEntry {
+            task_id: task_id.clone(),
+            description: description.to_string(),
+            phase,
+            created_at,
+            updated_at: now,
+        };
+        FileSystem::write_file_atomic(&path, &serde_json::to_string_pretty(&entry)?)?;
+
+        let (registry, _) = Self::load_all(file_system)?;
+        FileSystem::write_file_atomic_via(
+            file_system.get_index_path(),
+            &file_system
+                .get_task_directory_path(task_id)
+                .join("index.json.tmp"),
+            &serde_json::to_string_pretty(&registry)?,
+        )?;
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn key(id: &str) -> TaskId {
+        TaskId::parse(id).unwrap()
+    }
+
+    /// A file system with the task directories of `ids` set up
+    fn file_system_with(root: &Path, ids: &[&str]) -> FileSystem {
+        let file_system = FileSystem::new(root);
+        for id in ids {
+            file_system.setup_task_directories(&key(id)).unwrap();
+        }
+        file_system
+    }
+
+    fn record(file_system: &FileSystem, id: &str, phase: WorkflowPhase, now: u64) -> TaskEntry {
+        TaskRegistry::update(file_system, &key(id), "Add login", phase, now).unwrap()
+    }
+
+    #[test]
+    fn test_load_all_without_root_is_empty() {
+        let temp_dir = tempdir().unwrap();
+        let file_system = FileSystem::new(temp_dir.path().join("missing"));
+
+        let (registry, skipped) = TaskRegistry::load_all(&file_system).unwrap();
+        assert_eq!(registry, TaskRegistry::default());
+        assert_eq!(registry.version, REGISTRY_VERSION);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_update_writes_only_own_entry() {
+        let temp_dir = tempdir().unwrap();
+        let file_system = file_system_with(temp_dir.path(), &["PROJ-1", "PROJ-2"]);
+
+        // Coordinators in two tabs, each recording its own task
+        record(&file_system, "PROJ-2", WorkflowPhase::Initializing, 1);
+        record(&file_system, "PROJ-1", WorkflowPhase::Initializing, 2);
+        let updated = record(&file_system, "PROJ-2", WorkflowPhase::PlanningInProgress, 3);
+
+        assert_eq!(updated.created_at, 1);
+        assert_eq!(updated.updated_at, 3);
+        assert_eq!(
+            TaskRegistry::load_entry(file_system.get_task_entry_path(&key("PROJ-2"))).unwrap(),
+            Some(updated)
+        );
+
+        let (registry, _) = TaskRegistry::load_all(&file_system).unwrap();
+        let ids: Vec<&str> = registry.tasks.iter().map(|t| t.task_id.as_str()).collect();
+        assert_eq!(ids, vec!["PROJ-2", "PROJ-1"]);
+        assert_eq!(
+            registry.get(&key("PROJ-2")).unwrap().phase,
+            WorkflowPhase::PlanningInProgress
+        );
+    }
+
+    #[test]
+    fn test_update_rebuilds_index_from_every_entry() {
+        let temp_dir = tempdir().unwrap();
+        let file_system = file_system_with(temp_dir.path(), &["PROJ-1", "PROJ-2"]);
+
+        record(&file_system, "PROJ-1", WorkflowPhase::PlanReady, 1);
+        // Another tab's entry, written while this one's index was current
+        record(&file_system, "PROJ-2", WorkflowPhase::Initializing, 2);
+
+        let index: TaskRegistry =
+            serde_json::from_str(&fs::read_to_string(file_system.get_index_path()).unwrap())
+                .unwrap();
+        assert_eq!(index, TaskRegistry::load_all(&file_system).unwrap().0);
+        assert_eq!(index.tasks.len(), 2);
+        assert!(!file_system
+            .get_task_directory_path(&key("PROJ-2"))
+            .join("index.json.tmp")
+            .exists());
+    }
+
+    #[test]
+    fn test_load_all_skips_broken_entries() {
+        let temp_dir = tempdir().unwrap();
+        let file_system = file_system_with(temp_dir.path(), &["PROJ-1", "PROJ-2", "PROJ-3"]);
+        record(&file_system, "PROJ-1", WorkflowPhase::PlanReady, 1);
+        fs::write(
+            file_system.get_task_entry_path(&key("PROJ-2")),
+            "{\"task_id\": ",
+        )
+        .unwrap();
+
+        let (registry, skipped) = TaskRegistry::load_all(&file_system).unwrap();
+
+        assert_eq!(registry.tasks.len(), 1);
+        assert_eq!(registry.tasks[0].task_id, key("PROJ-1"));
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            skipped[0].0,
+            file_system.get_task_entry_path(&key("PROJ-2"))
+        );
+        assert!(matches!(skipped[0].1, RegistryError::Serialization(_)));
+
+        // A broken entry does not stop other tasks from being recorded
+        record(&file_system, "PROJ-3", WorkflowPhase::Initializing, 2);
+    }
+
+    #[test]
+    fn test_numeric_ids_match_regardless_of_padding() {
+        let temp_dir = tempdir().unwrap();
+        let file_system = file_system_with(temp_dir.path(), &["007"]);
+        record(&file_system, "007", WorkflowPhase::Finished, 1);
+
+        let (registry, _) = TaskRegistry::load_all(&file_system).unwrap();
+        assert_eq!(registry.tasks[0].task_id, key("007"));
+        assert!(registry.get(&TaskId::from(7)).is_some());
+    }
+
+    #[test]
+    fn test_index_json_layout() {
+        let registry = TaskRegistry {
+            version: REGISTRY_VERSION,
+            tasks: vec![TaskEntry {
+                task_id: key("PROJ-1234"),
+                description: "Add login".to_string(),
+                phase: WorkflowPhase::PlanReady,
+                created_at: 5,
+                updated_at: 5,
+            }],
+        };
+
+        let value = serde_json::to_value(&registry).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "version": 1,
+                "tasks": [{
+                    "task_id": "PROJ-1234",
+                    "description": "Add login",
+                    "phase": "PlanReady",
+                    "created_at": 5,
+                    "updated_at": 5
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_entry_rejects_bad_ids() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("entry.json");
+        fs::write(
+            &path,
+            r#"{"task_id": "../x", "description": "", "phase": "Initializing",
+                "created_at": 0, "updated_at": 0}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            TaskRegistry::load_entry(&path),
+            Err(RegistryError::Serialization(_))
+        ));
+        assert_eq!(
+            TaskRegistry::load_entry(temp_dir.path().join("missing.json")).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,748 @@
+use std::collections::{HashMap, HashSet};
+
+/// Completion state of a single checkbox item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoStatus {
+    /// `- [ ]`
+    Pending,
+    /// `- [x]` or `- [X]`
+    Completed,
+}
+
+/// A checkbox item from the todo list, with any nested sub-items
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    /// Stable identifier (see [`TodoList::parse`] for how it is derived)
+    pub id: String,
+    /// Explicit numbering such as `1.2` taken from `**1.2**`, if present
+    pub number: Option<String>,
+    /// Item text without checkbox and numbering
+    pub text: String,
+    pub status: TodoStatus,
+    /// 1-based line number in the source file
+    pub line: usize,
+    /// Plain (non-checkbox) bullets nested under this item
+    pub notes: Vec<String>,
+    pub children: Vec<TodoItem>,
+}
+
+impl TodoItem {
+    /// Check whether this item is ticked
+    pub fn is_completed(&self) -> bool {
+        self.status == TodoStatus::Completed
+    }
+}
+
+/// A markdown heading and everything nested under it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoSection {
+    pub title: String,
+    /// Heading level (1 for `#`, 2 for `##`, ...)
+    pub level: usize,
+    pub items: Vec<TodoItem>,
+    pub subsections: Vec<TodoSection>,
+}
+
+/// Completed/total counts across every checkbox item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TodoProgress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+impl TodoProgress {
+    /// Completion percentage, 0 when the list has no items
+    pub fn percentage(&self) -> u8 {
+        (self.completed * 100).checked_div(self.total).unwrap_or(0) as u8
+    }
+}
+
+/// Reasons a todo list cannot be used as an implementation plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TodoValidationError {
+    /// The file contains no checkbox items
+    NoItems,
+    /// A checkbox item has no text
+    EmptyItem { line: usize },
+    /// Two items share the same explicit number
+    DuplicateNumber { number: String, line: usize },
+}
+
+impl std::fmt::Display for TodoValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TodoValidationError::NoItems => write!(f, "todo list contains no checkbox items"),
+            TodoValidationError::EmptyItem { line } => {
+                write!(f, "todo item on line {} has no description", line)
+            }
+            TodoValidationError::DuplicateNumber { number, line } => {
+                write!(f, "todo item {} on line {} is numbered twice", number, line)
+            }
+        }
+    }
+}
+
+/// Parsed todo-list.md as a tree of sections and checkbox items
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TodoList {
+    /// Items that appear before the first heading
+    pub items: Vec<TodoItem>,
+    pub sections: Vec<TodoSection>,
+}
+
+impl TodoList {
+    /// Parse todo list markdown into a tree of sections and items
+    ///
+    /// Item IDs are derived so that they survive ticking checkboxes, reordering
+    /// and edits to other items: an explicit number like `**1.2**` is used
+    /// as-is, otherwise the ID is a hash of the enclosing section titles and the
+    /// normalized item text. Duplicate IDs get a `-2`, `-3`, ... suffix in
+    /// document order.
+    pub fn parse(content: &str) -> Self {
+        let mut sections: Vec<RawSection> = Vec::new();
+        let mut items: Vec<RawItem> = Vec::new();
+        let mut section_stack: Vec<usize> = Vec::new();
+        let mut item_stack: Vec<(usize, usize)> = Vec::new();
+        let mut in_code_block = false;
+
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            if let Some((level, title)) = parse_heading(line) {
+                while let Some(&top) = section_stack.last() {
+                    if sections[top].level >= level {
+                        section_stack.pop();
+                    } else {
+                        break;
+                    }
+                }
+                sections.push(RawSection {
+                    level,
+                    title,
+                    parent: section_stack.last().copied(),
+                });
+                section_stack.push(sections.len() - 1);
+                item_stack.clear();
+                continue;
+            }
+
+            let indent = indentation(line);
+            let (status, text) = match parse_list_entry(trimmed) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            while let Some(&(top_indent, _)) = item_stack.last() {
+                if top_indent >= indent {
+                    item_stack.pop();
+                } else {
+                    break;
+                }
+            }
+
+            match status {
+                Some(status) => {
+                    let (number, text) = split_number(&text);
+                    items.push(RawItem {
+                        section: section_stack.last().copied(),
+                        parent: item_stack.last().map(|&(_, item)| item),
+                        number,
+                        text,
+                        status,
+                        line: index + 1,
+                        notes: Vec::new(),
+                    });
+                    item_stack.push((indent, items.len() - 1));
+                }
+                None => {
+                    if let Some(&(_, parent)) = item_stack.last() {
+                        items[parent].notes.push(text);
+                    }
+                }
+            }
+        }
+
+        let ids = assign_ids(&sections, &items);
+        build_tree(&sections, &items, &ids)
+    }
+
+    /// Every item in document order, depth first
+    pub fn all_items(&self) -> Vec<&TodoItem> {
+        let mut result = Vec::new();
+        collect_items(&self.items, &mut result);
+        for section in &self.sections {
+            collect_section_items(section, &mut result);
+        }
+        result
+    }
+
+    /// Find an item by its stable ID
+    pub fn find(&self, id: &str) -> Option<&TodoItem> {
+        self.all_items().into_iter().find(|item| item.id == id)
+    }
+
+    /// Count completed and total items
+    pub fn progress(&self) -> TodoProgress {
+        let items = self.all_items();
+        TodoProgress {
+            completed: items.iter().filter(|item| item.is_completed()).count(),
+            total: items.len(),
+        }
+    }
+
+    /// Check whether the list has items and every one of them is ticked
+    pub fn is_complete(&self) -> bool {
+        let progress = self.progress();
+        progress.total > 0 && progress.completed == progress.total
+    }
+
+    /// Check that the list is usable as an implementation plan
+    pub fn validate(&self) -> Result<(), TodoValidationError> {
+        let items = self.all_items();
+        if items.is_empty() {
+            return Err(TodoValidationError::NoItems);
+        }
+
+        let mut numbers = HashSet::new();
+        for item in items {
+            if item.text.is_empty() {
+                return Err(TodoValidationError::EmptyItem { line: item.line });
+            }
+            if let Some(number) = &item.number {
+                if !numbers.insert(number.as_str()) {
+                    return Err(TodoValidationError::DuplicateNumber {
+                        number: number.clone(),
+                        line: item.line,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Items that are completed here but were not completed in `previous`
+    pub fn newly_completed(&self, previous: &TodoList) -> Vec<&TodoItem> {
+        self.all_items()
+            .into_iter()
+            .filter(|item| item.is_completed())
+            .filter(|item| {
+                previous
+                    .find(&item.id)
+                    .map(|old| !old.is_completed())
+                    .unwrap_or(true)
+            })
+            .collect()
+    }
+}
+
+struct RawSection {
+    level: usize,
+    title: String,
+    parent: Option<usize>,
+}
+
+struct RawItem {
+    section: Option<usize>,
+    parent: Option<usize>,
+    number: Option<String>,
+    text: String,
+    status: TodoStatus,
+    line: usize,
+    notes: Vec<String>,
+}
+
+/// Parse an ATX heading (`## Title`), returning its level and title
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    // Headings may be indented by at most three spaces
+    if indentation(line) > 3 {
+        return None;
+    }
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim().to_string();
+    Some((level, title))
+}
+
+/// Parse a list entry, returning its checkbox state (if any) and text
+fn parse_list_entry(trimmed: &str) -> Option<(Option<TodoStatus>, String)> {
+    let after_marker = if let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        rest
+    } else {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        trimmed[digits..]
+            .strip_prefix(". ")
+            .or_else(|| trimmed[digits..].strip_prefix(") "))?
+    };
+
+    let after_marker = after_marker.trim_start();
+    let checkbox = match after_marker.strip_prefix("[ ]") {
+        Some(rest) => Some((TodoStatus::Pending, rest)),
+        None => after_marker
+            .strip_prefix("[x]")
+            .or_else(|| after_marker.strip_prefix("[X]"))
+            .map(|rest| (TodoStatus::Completed, rest)),
+    };
+
+    match checkbox {
+        Some((status, rest)) => Some((Some(status), rest.trim().to_string())),
+        None => Some((None, after_marker.trim().to_string())),
+    }
+}
+
+/// Split a leading `**1.2**` (or bare `1.2`) number off the item text
+fn split_number(text: &str) -> (Option<String>, String) {
+    let (bold, rest) = match text.strip_prefix("**") {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+
+    let number_len = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .count();
+    if number_len == 0 || !rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return (None, text.to_string());
+    }
+
+    let number = rest[..number_len].trim_end_matches('.');
+    let remainder = &rest[number_len..];
+    let remainder = if bold {
+        match remainder.strip_prefix("**") {
+            Some(remainder) => remainder,
+            None => return (None, text.to_string()),
+        }
+    } else {
+        // Without bold markers, only dotted numbers count ("3 apples" is text)
+        if !number.contains('.') {
+            return (None, text.to_string());
+        }
+        remainder
+    };
+
+    if !remainder.is_empty() && !remainder.starts_with(char::is_whitespace) {
+        return (None, text.to_string());
+    }
+
+    (Some(number.to_string()), remainder.trim().to_string())
+}
+
+/// Width of the leading whitespace, counting tabs as four spaces
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Lowercase, strip inline markup and collapse whitespace for hashing
+fn normalize(text: &str) -> String {
+    text.replace("**", "")
+        .replace("__", "")
+        .replace('`', "")
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// FNV-1a, used instead of `DefaultHasher` because IDs must not change between builds
+fn fnv1a(input: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in input.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+fn section_path(sections: &[RawSection], mut index: Option<usize>) -> Vec<&str> {
+    let mut path = Vec::new();
+    while let Some(i) = index {
+        path.push(sections[i].title.as_str());
+        index = sections[i].parent;
+    }
+    path.reverse();
+    path
+}
+
+fn assign_ids(sections: &[RawSection], items: &[RawItem]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    items
+        .iter()
+        .map(|item| {
+            let base = match &item.number {
+                Some(number) => number.clone(),
+                None => {
+                    let key = format!(
+                        "{}/{}",
+                        section_path(sections, item.section)
+                            .iter()
+                            .map(|title| normalize(title))
+                            .collect::<Vec<_>>()
+                            .join("/"),
+                        normalize(&item.text)
+                    );
+                    format!("t-{:08x}", fnv1a(&key) as u32)
+                }
+            };
+
+            let count = seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                base
+            } else {
+                format!("{}-{}", base, count)
+            }
+        })
+        .collect()
+}
+
+fn build_item(items: &[RawItem], ids: &[String], index: usize) -> TodoItem {
+    let raw = &items[index];
+    TodoItem {
+        id: ids[index].clone(),
+        number: raw.number.clone(),
+        text: raw.text.clone(),
+        status: raw.status,
+        line: raw.line,
+        notes: raw.notes.clone(),
+        children: (0..items.len())
+            .filter(|&i| items[i].parent == Some(index))
+            .map(|i| build_item(items, ids, i))
+            .collect(),
+    }
+}
+
+fn build_section(
+    sections: &[RawSection],
+    items: &[RawItem],
+    ids: &[String],
+    index: usize,
+) -> TodoSection {
+    TodoSection {
+        title: sections[index].title.clone(),
+        level: sections[index].level,
+        items: (0..items.len())
+            .filter(|&i| items[i].section == Some(index) && items[i].parent.is_none())
+            .map(|i| build_item(items, ids, i))
+            .collect(),
+        subsections: (0..sections.len())
+            .filter(|&i| sections[i].parent == Some(index))
+            .map(|i| build_section(sections, items, ids, i))
+            .collect(),
+    }
+}
+
+fn build_tree(sections: &[RawSection], items: &[RawItem], ids: &[String]) -> TodoList {
+    TodoList {
+        items: (0..items.len())
+            .filter(|&i| items[i].section.is_none() && items[i].parent.is_none())
+            .map(|i| build_item(items, ids, i))
+            .collect(),
+        sections: (0..sections.len())
+            .filter(|&i| sections[i].parent.is_none())
+            .map(|i| build_section(sections, items, ids, i))
+            .collect(),
+    }
+}
+
+fn collect_items<'a>(items: &'a [TodoItem], result: &mut Vec<&'a TodoItem>) {
+    for item in items {
+        result.push(item);
+        collect_items(&item.children, result);
+    }
+}
+
+fn collect_section_items<'a>(section: &'a TodoSection, result: &mut Vec<&'a TodoItem>) {
+    collect_items(&section.items, result);
+    for subsection in &section.subsections {
+        collect_section_items(subsection, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Todo List: Add login
+
+## 1. Setup
+
+- [x] **1.1** Create the user model
+- [ ] **1.2** Add password hashing
+  - [ ] Pick a hashing crate
+  - [x] Write hashing tests
+    - covers empty passwords
+
+## 2. API
+
+- [ ] Add `/login` endpoint
+- [ ] Add logout endpoint
+";
+
+    #[test]
+    fn test_parse_sections_and_items() {
+        let list = TodoList::parse(SAMPLE);
+
+        assert!(list.items.is_empty());
+        assert_eq!(list.sections.len(), 1);
+
+        let root = &list.sections[0];
+        assert_eq!(root.title, "Todo List: Add login");
+        assert_eq!(root.level, 1);
+        assert_eq!(root.subsections.len(), 2);
+
+        let setup = &root.subsections[0];
+        assert_eq!(setup.title, "1. Setup");
+        assert_eq!(setup.level, 2);
+        assert_eq!(setup.items.len(), 2);
+
+        let api = &root.subsections[1];
+        assert_eq!(api.items.len(), 2);
+        assert_eq!(api.items[0].text, "Add `/login` endpoint");
+    }
+
+    #[test]
+    fn test_parse_checkbox_status() {
+        let list = TodoList::parse(SAMPLE);
+        let setup = &list.sections[0].subsections[0];
+
+        assert_eq!(setup.items[0].status, TodoStatus::Completed);
+        assert_eq!(setup.items[1].status, TodoStatus::Pending);
+    }
+
+    #[test]
+    fn test_parse_nested_items_and_notes() {
+        let list = TodoList::parse(SAMPLE);
+        let hashing = &list.sections[0].subsections[0].items[1];
+
+        assert_eq!(hashing.children.len(), 2);
+        assert_eq!(hashing.children[0].text, "Pick a hashing crate");
+        assert!(hashing.children[1].is_completed());
+        assert_eq!(hashing.children[1].notes, vec!["covers empty passwords"]);
+    }
+
+    #[test]
+    fn test_parse_numbering() {
+        let list = TodoList::parse(SAMPLE);
+        let setup = &list.sections[0].subsections[0];
+
+        assert_eq!(setup.items[0].number, Some("1.1".to_string()));
+        assert_eq!(setup.items[0].id, "1.1");
+        assert_eq!(setup.items[0].text, "Create the user model");
+        assert_eq!(setup.items[1].id, "1.2");
+    }
+
+    #[test]
+    fn test_split_number_variants() {
+        assert_eq!(
+            split_number("**2.3** Write docs"),
+            (Some("2.3".to_string()), "Write docs".to_string())
+        );
+        assert_eq!(
+            split_number("**4** Ship it"),
+            (Some("4".to_string()), "Ship it".to_string())
+        );
+        assert_eq!(
+            split_number("1.2.3 Nested"),
+            (Some("1.2.3".to_string()), "Nested".to_string())
+        );
+        assert_eq!(split_number("3 apples"), (None, "3 apples".to_string()));
+        assert_eq!(
+            split_number("**bold** text"),
+            (None, "**bold** text".to_string())
+        );
+    }
+
+    #[test]
+    fn test_item_line_numbers() {
+        let list = TodoList::parse(SAMPLE);
+        let setup = &list.sections[0].subsections[0];
+        assert_eq!(setup.items[0].line, 5);
+        assert_eq!(setup.items[1].children[0].line, 7);
+    }
+
+    #[test]
+    fn test_ids_survive_ticking_and_reordering() {
+        let before = TodoList::parse("## API\n- [ ] Add login\n- [ ] Add logout\n");
+        let after = TodoList::parse("## API\n- [x] Add logout\n- [x] Add  **login**\n");
+
+        let login_before = &before.sections[0].items[0];
+        let login_after = after
+            .all_items()
+            .into_iter()
+            .find(|item| item.text.contains("login"))
+            .unwrap();
+        assert_eq!(login_before.id, login_after.id);
+        assert!(login_before.id.starts_with("t-"));
+    }
+
+    #[test]
+    fn test_ids_differ_by_section() {
+        let list = TodoList::parse("## Backend\n- [ ] Add tests\n## Frontend\n- [ ] Add tests\n");
+        let items = list.all_items();
+        assert_ne!(items[0].id, items[1].id);
+    }
+
+    #[test]
+    fn test_duplicate_ids_are_suffixed() {
+        let list = TodoList::parse("- [ ] Same task\n- [ ] Same task\n- [ ] Same task\n");
+        let ids: Vec<&str> = list.all_items().iter().map(|i| i.id.as_str()).collect();
+
+        assert_eq!(ids[1], format!("{}-2", ids[0]));
+        assert_eq!(ids[2], format!("{}-3", ids[0]));
+    }
+
+    #[test]
+    fn test_items_before_first_heading() {
+        let list = TodoList::parse("- [ ] Loose item\n\n# Section\n- [x] Inside\n");
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(list.items[0].text, "Loose item");
+        assert_eq!(list.sections[0].items.len(), 1);
+    }
+
+    #[test]
+    fn test_alternative_markers() {
+        let list = TodoList::parse("* [X] Star\n+ [ ] Plus\n1. [ ] Ordered\n2) [x] Paren\n");
+        let items = list.all_items();
+
+        assert_eq!(items.len(), 4);
+        assert!(items[0].is_completed());
+        assert!(!items[1].is_completed());
+        assert_eq!(items[2].text, "Ordered");
+        assert!(items[3].is_completed());
+    }
+
+    #[test]
+    fn test_code_blocks_are_ignored() {
+        let list = TodoList::parse("- [ ] Real\n```\n- [ ] Not real\n# Not a heading\n```\n");
+        assert_eq!(list.all_items().len(), 1);
+        assert!(list.sections.is_empty());
+    }
+
+    #[test]
+    fn test_tab_indentation_nests() {
+        let list = TodoList::parse("- [ ] Parent\n\t- [ ] Child\n");
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(list.items[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_progress_and_completion() {
+        let list = TodoList::parse(SAMPLE);
+        let progress = list.progress();
+
+        assert_eq!(progress.total, 6);
+        assert_eq!(progress.completed, 2);
+        assert_eq!(progress.percentage(), 33);
+        assert!(!list.is_complete());
+
+        let done = TodoList::parse("- [x] One\n- [X] Two\n");
+        assert!(done.is_complete());
+        assert_eq!(done.progress().percentage(), 100);
+    }
+
+    #[test]
+    fn test_empty_list_is_not_complete() {
+        let list = TodoList::parse("# Nothing here\n\nJust prose.\n");
+        assert!(!list.is_complete());
+        assert_eq!(list.progress(), TodoProgress::default());
+        assert_eq!(list.progress().percentage(), 0);
+    }
+
+    #[test]
+    fn test_find_by_id() {
+        let list = TodoList::parse(SAMPLE);
+        let item = list.find("1.2").unwrap();
+        assert_eq!(item.text, "Add password hashing");
+        assert!(list.find("9.9").is_none());
+    }
+
+    #[test]
+    fn test_newly_completed() {
+        let before = TodoList::parse(SAMPLE);
+        let after = TodoList::parse(&SAMPLE.replace("- [ ] **1.2**", "- [x] **1.2**"));
+
+        let completed = after.newly_completed(&before);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, "1.2");
+
+        assert!(after.newly_completed(&after).is_empty());
+    }
+
+    #[test]
+    fn test_validate_accepts_sample() {
+        assert_eq!(TodoList::parse(SAMPLE).validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_rejects_list_without_items() {
+        let list = TodoList::parse("# Plan\n\n- just a bullet\n");
+        assert_eq!(list.validate(), Err(TodoValidationError::NoItems));
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_item() {
+        let list = TodoList::parse("- [ ] First\n- [ ]\n");
+        assert_eq!(
+            list.validate(),
+            Err(TodoValidationError::EmptyItem { line: 2 })
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_numbers() {
+        let list = TodoList::parse("- [ ] **1.1** First\n- [ ] **1.1** Again\n");
+        assert_eq!(
+            list.validate(),
+            Err(TodoValidationError::DuplicateNumber {
+                number: "1.1".to_string(),
+                line: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validation_error_display() {
+        assert_eq!(
+            format!("{}", TodoValidationError::NoItems),
+            "todo list contains no checkbox items"
+        );
+        assert_eq!(
+            format!("{}", TodoValidationError::EmptyItem { line: 4 }),
+            "todo item on line 4 has no description"
+        );
+    }
+
+    #[test]
+    fn test_parse_heading_rules() {
+        assert_eq!(parse_heading("## Title ##"), Some((2, "Title".to_string())));
+        assert_eq!(parse_heading("#NoSpace"), None);
+        assert_eq!(parse_heading("    # Indented code"), None);
+        assert_eq!(parse_heading("####### Too deep"), None);
+    }
+}
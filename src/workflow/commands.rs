@@ -0,0 +1,712 @@
+use super::transition::PromptKind;
+use crate::assistant::{AssistantCommand, AssistantConfig, AssistantPrompt};
+use crate::file_system::FileSystem;
+use crate::litellm_config::LiteLLMConfig;
+use crate::pane_role::PaneRole;
+use crate::secrets::{self, ApiKeySource};
+use crate::shell::ShellDialect;
+use crate::task_id::TaskId;
+
+/// Task details needed to build assistant commands
+#[derive(Debug, Clone, Copy)]
+pub struct TaskContext<'a> {
+    pub task_id: &'a TaskId,
+    pub task_description: &'a str,
+    pub litellm_config: &'a LiteLLMConfig,
+    pub assistants: &'a AssistantConfig,
+    pub file_system: &'a FileSystem,
+}
+
+/// Path of a task file as the assistants see it
+fn pane_path(task: &TaskContext, file_name: &str) -> String {
+    task.file_system
+        .pane_task_directory_path(task.task_id)
+        .join(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Todo list path as the assistants see it
+fn relative_todo_list_path(task: &TaskContext) -> String {
+    pane_path(task, FileSystem::TODO_LIST_FILE)
+}
+
+/// Review path as the assistants see it
+fn relative_review_path(task: &TaskContext) -> String {
+    pane_path(task, FileSystem::REVIEW_FILE)
+}
+
+/// Feedback on a rejected plan, as the assistants see it
+fn relative_plan_feedback_path(task: &TaskContext) -> String {
+    pane_path(task, FileSystem::PLAN_FEEDBACK_FILE)
+}
+
+/// Env file holding a role's secrets, as the assistants see it
+pub fn relative_secret_env_path(task: &TaskContext, role: PaneRole) -> String {
+    pane_path(task, &format!("{}.env", role.config_key()))
+}
+
+/// Log a role's pane output is captured into, as the assistants see it
+pub fn relative_role_log_path(task: &TaskContext, role: PaneRole) -> String {
+    pane_path(task, &format!("logs/{}.log", role.config_key()))
+}
+
+/// Prompt file for a role's `n`th long prompt, as the assistants see it
+pub fn relative_prompt_path(task: &TaskContext, role: PaneRole, n: u32) -> String {
+    pane_path(task, &format!("prompt-{}-{}.md", role.config_key(), n))
+}
+
+/// Prompts longer than this are written to a file instead of typed into the pane
+pub const MAX_INLINE_PROMPT_BYTES: usize = 2048;
+
+/// Check whether a prompt should be passed as a file rather than typed inline
+///
+/// Besides long prompts, this covers control characters such as tabs, which an
+/// interactive shell would act on while the command is being typed.
+pub fn needs_prompt_file(prompt: &str) -> bool {
+    prompt.len() > MAX_INLINE_PROMPT_BYTES || prompt.chars().any(|c| c.is_control() && c != '\n')
+}
+
+/// Build the line typed into a role's pane to run a prompt
+///
+/// Words are quoted for the role's shell and the API key is read from the
+/// role's env file rather than put on the line. With `<role>_capture_output`
+/// on, the output is also captured into the role's log under the `run` label.
+pub fn build_launch_command(
+    task: &TaskContext,
+    role: PaneRole,
+    prompt: &AssistantPrompt,
+    run: &str,
+) -> String {
+    let assistant = task.assistants.for_role(role);
+    let command = build_assistant_command(task, role, prompt);
+    let env_file = relative_secret_env_path(task, role);
+    if !assistant.capture_output {
+        return command.render_with_env_file(&env_file, assistant.shell);
+    }
+
+    let script = capture_output(task, role, run, &command);
+    assistant
+        .shell
+        .subshell(&command.source_env_file(&env_file, &script))
+}
+
+/// Line the capture pipeline appends to the assistant's output to pass on its
+/// exit status
+const EXIT_STATUS_MARKER: &str = "__zzz_exit_status__=";
+
+/// Awk program that passes an assistant's output through to the pane and
+/// appends every line to the `out` file behind a Unix timestamp, between
+/// markers naming the `run`
+///
+/// Values of the environment variables listed in `secrets` are masked in the
+/// log. The exit status marker is taken off the end of the output, even when
+/// the assistant's last line has no newline, and becomes awk's own exit
+/// status. `srand()` returns the previous seed, so seeding twice reads the
+/// clock in any POSIX awk, without gawk's `systime()`.
+const TRANSCRIPT_AWK: &str = "function stamp() { srand(); return \"[\" srand() \"] \" } \
+    function mask(text,   k, i, masked) { \
+        for (k in keys) { \
+            masked = \"\"; \
+            while ((i = index(text, keys[k])) > 0) { \
+                masked = masked substr(text, 1, i - 1) \"[REDACTED]\"; \
+                text = substr(text, i + length(keys[k])) \
+            } \
+            text = masked text \
+        } \
+        return text \
+    } \
+    function record(text) { print stamp() mask(text) >> out; close(out) } \
+    BEGIN { \
+        n = split(secrets, names, \" \"); \
+        for (i = 1; i <= n; i++) if (ENVIRON[names[i]] != \"\") keys[i] = ENVIRON[names[i]]; \
+        record(\"--- \" run \" started ---\") \
+    } \
+    (i = index($0, marker)) > 0 && substr($0, i + length(marker)) ~ /^[0-9]+$/ { \
+        status = substr($0, i + length(marker)) + 0; \
+        if (i > 1) { printf \"%s\", substr($0, 1, i - 1); record(substr($0, 1, i - 1)) } \
+        next \
+    } \
+    { print; fflush(); record($0) } \
+    END { record(\"--- \" run \" finished with status \" (status + 0) \" ---\"); exit status }";
+
+/// POSIX sh pipeline that runs `command` with its output captured into the role's log
+///
+/// Standard error is captured with standard output, and the pipeline exits
+/// with the assistant's status rather than awk's. The assistant's output is a
+/// pipe rather than a terminal, which is why capturing is opt-in.
+fn capture_output(
+    task: &TaskContext,
+    role: PaneRole,
+    run: &str,
+    command: &AssistantCommand,
+) -> String {
+    let quote = |word: &str| ShellDialect::Posix.quote(word);
+    format!(
+        "{{ {} 2>&1; echo \"{}$?\"; }} | awk -v {} -v {} -v {} -v {} {}",
+        command.render(ShellDialect::Posix),
+        EXIT_STATUS_MARKER,
+        quote(&format!("run={}", run)),
+        quote(&format!("out={}", relative_role_log_path(task, role))),
+        quote(&format!("secrets={}", command.secrets.join(" "))),
+        quote(&format!("marker={}", EXIT_STATUS_MARKER)),
+        quote(TRANSCRIPT_AWK)
+    )
+}
+
+/// Content of the env file for a role, or `None` if its assistant needs no key
+pub fn build_secret_env(task: &TaskContext, role: PaneRole) -> Option<String> {
+    let names = build_assistant_command(task, role, &AssistantPrompt::Text(String::new())).secrets;
+    let source = ApiKeySource::from_config(task.litellm_config)?;
+    if names.is_empty() {
+        return None;
+    }
+    Some(secrets::env_file_content(&names, &source))
+}
+
+/// Build the command that runs a prompt with the assistant configured for `role`
+pub fn build_assistant_command(
+    task: &TaskContext,
+    role: PaneRole,
+    prompt: &AssistantPrompt,
+) -> AssistantCommand {
+    task.assistants
+        .for_role(role)
+        .backend()
+        .build_command(prompt, task.litellm_config)
+}
+
+/// Build the prompt text for a workflow step
+pub fn build_prompt(task: &TaskContext, prompt: PromptKind) -> String {
+    match prompt {
+        PromptKind::Planning => planning_prompt(task),
+        PromptKind::Implementation => implementation_prompt(task),
+        PromptKind::Review => review_prompt(task),
+        PromptKind::Fixes => fix_prompt(task),
+        PromptKind::Replan => replan_prompt(task),
+    }
+}
+
+/// Prompt asking the Overseer to write the todo list
+pub fn planning_prompt(task: &TaskContext) -> String {
+    let todo_list_path = relative_todo_list_path(task);
+
+    format!(
+        "Create a detailed step-by-step todo list for implementing the following feature: {}\n\n\
+        Requirements:\n\
+        - Save the todo list as a markdown file at {}\n\
+        - Use checkbox format: - [ ] Task description\n\
+        - Break down complex tasks into smaller, implementable steps\n\
+        - Include testing requirements for each major feature\n\
+        - Focus on practical implementation steps\n\
+        - Each task should be specific and actionable\n\n\
+        Generate a comprehensive plan that a developer can follow step by step.",
+        task.task_description, todo_list_path
+    )
+}
+
+/// Prompt asking the Commander to work through the todo list
+pub fn implementation_prompt(task: &TaskContext) -> String {
+    let todo_list_path = relative_todo_list_path(task);
+
+    format!(
+        "Implement the following feature: {}\n\n\
+        Instructions:\n\
+        - Follow the todo list at {} step by step, in order\n\
+        - When an item is done, tick it in that file by changing - [ ] to - [x]\n\
+        - Do not reword, reorder or remove existing items\n\
+        - Run the relevant tests before ticking an item\n\n\
+        Keep going until every item in the todo list is ticked.",
+        task.task_description, todo_list_path
+    )
+}
+
+/// Prompt asking the Overseer to review the finished implementation
+pub fn review_prompt(task: &TaskContext) -> String {
+    format!(
+        "Review the implementation of the following feature: {}\n\n\
+        Requirements:\n\
+        - Check the changes against every item of the todo list at {}\n\
+        - Save the review as a markdown file at {}\n\
+        - List each finding as a bullet: - **severity** `path/to/file:line` description\n\
+        - Use severity critical, major, minor or info; critical and major findings must be fixed\n\
+        - Explain why each finding matters and how to fix it\n\
+        - Append (rejected) to earlier findings that no longer apply\n\
+        - If nothing needs to change, say so explicitly\n\n\
+        Do not modify any source files; only write the review.",
+        task.task_description,
+        relative_todo_list_path(task),
+        relative_review_path(task)
+    )
+}
+
+/// Prompt asking the Commander to fix blocking review findings
+pub fn fix_prompt(task: &TaskContext) -> String {
+    format!(
+        "Fix the blocking review findings for the following feature: {}\n\n\
+        Instructions:\n\
+        - The findings have been added as unchecked items under the last {} section of the todo list at {}\n\
+        - Fix each finding, then tick its item by changing - [ ] to - [x]\n\
+        - Do not reword, reorder or remove existing items\n\
+        - Run the relevant tests before ticking an item\n\n\
+        Keep going until every item in the todo list is ticked.",
+        task.task_description,
+        crate::review::FIX_ROUND_HEADING,
+        relative_todo_list_path(task)
+    )
+}
+
+/// Prompt asking the Overseer to rewrite a plan the operator rejected
+pub fn replan_prompt(task: &TaskContext) -> String {
+    format!(
+        "Your todo list for the following feature was rejected: {}\n\n\
+        Instructions:\n\
+        - Read the feedback at {}\n\
+        - The rejected list is kept next to it as todo-list-rejected-<n>.md\n\
+        - Write a new todo list that addresses the feedback at {}\n\
+        - Use checkbox format: - [ ] Task description\n\
+        - Each task should be specific and actionable\n\n\
+        Only write the todo list; do not start implementing it.",
+        task.task_description,
+        relative_plan_feedback_path(task),
+        relative_todo_list_path(task)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assistant::{BackendKind, RoleAssistant};
+    use crate::shell::ShellDialect;
+    use proptest::prelude::*;
+
+    fn test_config() -> LiteLLMConfig {
+        LiteLLMConfig {
+            api_key: "test-key".to_string(),
+            api_key_file: None,
+            url: "https://litellm.test".to_string(),
+        }
+    }
+
+    fn task<'a>(
+        task_id: &'a TaskId,
+        task_description: &'a str,
+        config: &'a LiteLLMConfig,
+        assistants: &'a AssistantConfig,
+        file_system: &'a FileSystem,
+    ) -> TaskContext<'a> {
+        TaskContext {
+            task_id,
+            task_description,
+            litellm_config: config,
+            assistants,
+            file_system,
+        }
+    }
+
+    #[test]
+    fn test_paths_follow_configured_base_directory() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let configuration =
+            std::iter::once(("base_directory".to_string(), "work/.zzz".to_string())).collect();
+        let file_system =
+            FileSystem::from_configuration(&configuration, std::path::Path::new("/host"));
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        assert!(planning_prompt(&task).contains("work/.zzz/task-42/todo-list.md"));
+        assert_eq!(
+            relative_prompt_path(&task, PaneRole::Commander, 1),
+            "work/.zzz/task-42/prompt-commander-1.md"
+        );
+    }
+
+    #[test]
+    fn test_paths_use_issue_tracker_keys() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::parse("PROJ-1234").unwrap();
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        assert!(planning_prompt(&task).contains(".zzz/task-PROJ-1234/todo-list.md"));
+        assert_eq!(
+            relative_prompt_path(&task, PaneRole::Overseer, 2),
+            ".zzz/task-PROJ-1234/prompt-overseer-2.md"
+        );
+    }
+
+    #[test]
+    fn test_planning_command_includes_environment() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = AssistantPrompt::Text(build_prompt(&task, PromptKind::Planning));
+        let command = build_launch_command(&task, PaneRole::Overseer, &prompt, "Planning prompt");
+        assert!(command.starts_with(
+            "(chmod 600 .zzz/task-42/overseer.env && . .zzz/task-42/overseer.env && \
+             OPENAI_BASE_URL=https://litellm.test codex --quiet"
+        ));
+        assert!(!command.contains("test-key"));
+        assert!(command.contains("Add login"));
+        assert!(command.contains(".zzz/task-42/todo-list.md"));
+    }
+
+    #[test]
+    fn test_planning_command_quotes_shell_metacharacters() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(1);
+        let task = task(
+            &task_id,
+            "Say \"hello\" to $USER, it's `id`",
+            &config,
+            &assistants,
+            &file_system,
+        );
+
+        let prompt = AssistantPrompt::Text(build_prompt(&task, PromptKind::Planning));
+        let command = build_launch_command(&task, PaneRole::Overseer, &prompt, "Planning prompt");
+        assert!(command.contains("Say \"hello\" to $USER, it'\\''s `id`"));
+    }
+
+    #[test]
+    fn test_launch_command_uses_role_shell() {
+        let config = test_config();
+        let mut assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        assistants.set_role(
+            PaneRole::Commander,
+            RoleAssistant {
+                shell: ShellDialect::Fish,
+                ..RoleAssistant::default()
+            },
+        );
+        let task_id = TaskId::from(1);
+        let task = task(&task_id, "it's", &config, &assistants, &file_system);
+
+        let prompt = AssistantPrompt::Text("it's".to_string());
+        let command = build_launch_command(&task, PaneRole::Commander, &prompt, "Fixes prompt");
+        assert!(command.starts_with("sh -c 'chmod 600 .zzz/task-1/commander.env"));
+    }
+
+    #[test]
+    fn test_needs_prompt_file() {
+        assert!(!needs_prompt_file("short\nprompt"));
+        assert!(needs_prompt_file("has\ta tab"));
+        assert!(needs_prompt_file("bell\u{7}"));
+        assert!(needs_prompt_file(&"x".repeat(MAX_INLINE_PROMPT_BYTES + 1)));
+    }
+
+    #[test]
+    fn test_relative_prompt_path() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        assert_eq!(
+            relative_prompt_path(&task, PaneRole::Overseer, 3),
+            ".zzz/task-42/prompt-overseer-3.md"
+        );
+    }
+
+    #[test]
+    fn test_build_secret_env_uses_backend_variables() {
+        let config = test_config();
+        let mut assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        assistants.set_role(
+            PaneRole::Commander,
+            RoleAssistant {
+                backend: BackendKind::Claude,
+                ..RoleAssistant::default()
+            },
+        );
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        assert_eq!(
+            build_secret_env(&task, PaneRole::Overseer).unwrap(),
+            "export OPENAI_API_KEY=test-key\n"
+        );
+        assert_eq!(
+            build_secret_env(&task, PaneRole::Commander).unwrap(),
+            "export ANTHROPIC_AUTH_TOKEN=test-key\n"
+        );
+    }
+
+    #[test]
+    fn test_build_secret_env_from_key_file() {
+        let config = LiteLLMConfig {
+            api_key_file: Some("/keys/litellm".to_string()),
+            ..test_config()
+        };
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        assert_eq!(
+            build_secret_env(&task, PaneRole::Overseer).unwrap(),
+            "export OPENAI_API_KEY=\"$(cat /keys/litellm)\"\n"
+        );
+    }
+
+    #[test]
+    fn test_build_secret_env_without_key() {
+        let config = LiteLLMConfig::default();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        assert_eq!(build_secret_env(&task, PaneRole::Overseer), None);
+    }
+
+    #[test]
+    fn test_command_uses_role_backend() {
+        let config = test_config();
+        let mut assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        assistants.set_role(
+            PaneRole::Commander,
+            RoleAssistant {
+                backend: BackendKind::Claude,
+                model: Some("sonnet".to_string()),
+                ..RoleAssistant::default()
+            },
+        );
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = AssistantPrompt::Text(implementation_prompt(&task));
+        let command = build_assistant_command(&task, PaneRole::Commander, &prompt);
+        assert_eq!(command.program, "claude");
+        assert_eq!(command.args[..2], ["--model", "sonnet"]);
+        assert_eq!(command.args[2], implementation_prompt(&task));
+
+        let overseer = build_assistant_command(&task, PaneRole::Overseer, &prompt);
+        assert_eq!(overseer.program, "codex");
+    }
+
+    #[test]
+    fn test_implementation_prompt_points_at_todo_list() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = build_prompt(&task, PromptKind::Implementation);
+        assert!(prompt.contains("Implement the following feature: Add login"));
+        assert!(prompt.contains("Follow the todo list at .zzz/task-42/todo-list.md"));
+        assert!(prompt.contains("- [x]"));
+    }
+
+    #[test]
+    fn test_review_prompt_writes_review() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = build_prompt(&task, PromptKind::Review);
+        assert!(prompt.contains("Review the implementation of the following feature: Add login"));
+        assert!(prompt.contains("todo list at .zzz/task-42/todo-list.md"));
+        assert!(prompt.contains("markdown file at .zzz/task-42/review.md"));
+        assert!(prompt.contains("- **severity** `path/to/file:line` description"));
+    }
+
+    #[test]
+    fn test_fix_prompt_points_at_fix_round() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = build_prompt(&task, PromptKind::Fixes);
+        assert!(prompt
+            .contains("Fix the blocking review findings for the following feature: Add login"));
+        assert!(prompt
+            .contains("last Review round section of the todo list at .zzz/task-42/todo-list.md"));
+    }
+
+    #[test]
+    fn test_replan_prompt_points_at_feedback() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(42);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = build_prompt(&task, PromptKind::Replan);
+        assert!(prompt.contains("rejected: Add login"));
+        assert!(prompt.contains("feedback at .zzz/task-42/plan-feedback.md"));
+        assert!(prompt.contains("addresses the feedback at .zzz/task-42/todo-list.md"));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_task_description_reaches_assistant_unchanged(description in "[^\\x00]*") {
+            let config = test_config();
+            let mut assistants = AssistantConfig::default();
+            let file_system = FileSystem::default();
+            assistants.set_role(
+                PaneRole::Overseer,
+                RoleAssistant {
+                    backend: BackendKind::Template,
+                    template: Some("printf %s {prompt}".to_string()),
+                    ..RoleAssistant::default()
+                },
+            );
+            let task_id = TaskId::from(7);
+            let task = task(&task_id, &description, &config, &assistants, &file_system);
+            let prompt = build_prompt(&task, PromptKind::Planning);
+            let command = build_launch_command(
+                &task,
+                PaneRole::Overseer,
+                &AssistantPrompt::Text(prompt.clone()),
+                "Planning prompt",
+            );
+
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .output()
+                .unwrap();
+            prop_assert_eq!(String::from_utf8(output.stdout).unwrap(), prompt);
+        }
+    }
+
+    /// Task whose Commander runs `sh -c {prompt}` with its output captured
+    fn capturing_assistants() -> AssistantConfig {
+        let mut assistants = AssistantConfig::default();
+        assistants.set_role(
+            PaneRole::Commander,
+            RoleAssistant {
+                backend: BackendKind::Template,
+                template: Some("ZZZ_TEST_KEY={api_key} sh -c {prompt}".to_string()),
+                capture_output: true,
+                ..RoleAssistant::default()
+            },
+        );
+        assistants
+    }
+
+    #[test]
+    fn test_capture_output_appends_timestamped_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_system = FileSystem::new(temp_dir.path());
+        let task_id = TaskId::from(3);
+        file_system.setup_task_directories(&task_id).unwrap();
+        let config = test_config();
+        let assistants = capturing_assistants();
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+        FileSystem::write_private_file_atomic(
+            file_system.get_secret_env_path(&task_id, "commander"),
+            &build_secret_env(&task, PaneRole::Commander).unwrap(),
+        )
+        .unwrap();
+
+        let prompt = AssistantPrompt::Text(
+            "printf 'one\\ntwo\\n'; echo \"key $ZZZ_TEST_KEY\"; echo oops >&2; printf done; exit 3"
+                .to_string(),
+        );
+        let command =
+            build_launch_command(&task, PaneRole::Commander, &prompt, "Implementation prompt");
+        let output = std::process::Command::new("sh")
+            .current_dir(temp_dir.path())
+            .arg("-c")
+            .arg(&command)
+            .output()
+            .unwrap();
+
+        // The pane still shows the output, and the assistant's status survives the pipe
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "one\ntwo\nkey test-key\noops\ndone"
+        );
+        assert_eq!(output.status.code(), Some(3));
+
+        let log = std::fs::read_to_string(file_system.get_commander_log_path(&task_id)).unwrap();
+        let text: Vec<&str> = log
+            .lines()
+            .map(|line| {
+                let (stamp, text) = line.split_at(line.find("] ").unwrap() + 2);
+                assert!(stamp[1..stamp.len() - 2].parse::<u64>().unwrap() > 0);
+                text
+            })
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "--- Implementation prompt started ---",
+                "one",
+                "two",
+                "key [REDACTED]",
+                "oops",
+                "done",
+                "--- Implementation prompt finished with status 3 ---"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_output_in_fish_runs_under_sh() {
+        let config = test_config();
+        let mut assistants = capturing_assistants();
+        let file_system = FileSystem::default();
+        assistants.set_role(
+            PaneRole::Overseer,
+            RoleAssistant {
+                shell: ShellDialect::Fish,
+                capture_output: true,
+                ..RoleAssistant::default()
+            },
+        );
+        let task_id = TaskId::parse("PROJ-9").unwrap();
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = AssistantPrompt::Text("go".to_string());
+        let command = build_launch_command(&task, PaneRole::Overseer, &prompt, "Review prompt");
+        assert!(command.starts_with(
+            "sh -c 'chmod 600 .zzz/task-PROJ-9/overseer.env && . .zzz/task-PROJ-9/overseer.env \
+             && { OPENAI_BASE_URL=https://litellm.test codex --quiet go 2>&1; \
+             echo \"__zzz_exit_status__=$?\"; } | awk -v \\'run=Review prompt\\' \
+             -v out=.zzz/task-PROJ-9/logs/overseer.log -v secrets=OPENAI_API_KEY "
+        ));
+        assert_eq!(
+            relative_role_log_path(&task, PaneRole::Commander),
+            ".zzz/task-PROJ-9/logs/commander.log"
+        );
+    }
+
+    #[test]
+    fn test_capture_output_is_off_by_default() {
+        let config = test_config();
+        let assistants = AssistantConfig::default();
+        let file_system = FileSystem::default();
+        let task_id = TaskId::from(1);
+        let task = task(&task_id, "Add login", &config, &assistants, &file_system);
+
+        let prompt = AssistantPrompt::Text("go".to_string());
+        let command = build_launch_command(&task, PaneRole::Commander, &prompt, "Fixes prompt");
+        assert!(!command.contains("awk"));
+        assert_eq!(
+            command,
+            "(chmod 600 .zzz/task-1/commander.env && . .zzz/task-1/commander.env && \
+             OPENAI_BASE_URL=https://litellm.test codex --quiet go)"
+        );
+    }
+}
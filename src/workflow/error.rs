@@ -0,0 +1,138 @@
+use super::event::WorkflowEvent;
+use super::transition::Guard;
+use crate::communication::CommunicationError;
+use crate::file_system::FileSystemError;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Errors raised when a workflow event cannot be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionError {
+    /// The transition table has no edge for this event from the current phase
+    InvalidTransition {
+        from: WorkflowPhase,
+        event: WorkflowEvent,
+    },
+    /// A guard predicate rejected the transition
+    GuardFailed {
+        from: WorkflowPhase,
+        event: WorkflowEvent,
+        guard: Guard,
+    },
+    /// A forced move targeted the phase the workflow is already in
+    AlreadyInPhase(WorkflowPhase),
+}
+
+impl std::fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionError::InvalidTransition { from, event } => {
+                write!(
+                    f,
+                    "Invalid state transition: {:?} is not allowed from {:?}",
+                    event, from
+                )
+            }
+            TransitionError::GuardFailed { from, event, guard } => {
+                write!(f, "Cannot apply {:?} from {:?}: {}", event, from, guard)
+            }
+            TransitionError::AlreadyInPhase(phase) => {
+                write!(f, "The workflow is already in {:?}", phase)
+            }
+        }
+    }
+}
+
+/// Errors raised while performing a workflow action
+#[derive(Debug)]
+pub enum ActionError {
+    /// Sending to a pane or the coordination pipe failed
+    Communication(CommunicationError),
+    /// Reading or writing a task file failed
+    FileSystem(FileSystemError),
+}
+
+impl std::fmt::Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionError::Communication(e) => write!(f, "{}", e),
+            ActionError::FileSystem(e) => write!(f, "Task file operation failed: {:?}", e),
+        }
+    }
+}
+
+impl From<CommunicationError> for ActionError {
+    fn from(error: CommunicationError) -> Self {
+        ActionError::Communication(error)
+    }
+}
+
+impl From<FileSystemError> for ActionError {
+    fn from(error: FileSystemError) -> Self {
+        ActionError::FileSystem(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pane_role::PaneRole;
+
+    #[test]
+    fn test_invalid_transition_display() {
+        let error = TransitionError::InvalidTransition {
+            from: WorkflowPhase::Initializing,
+            event: WorkflowEvent::Finish,
+        };
+
+        assert_eq!(
+            format!("{}", error),
+            "Invalid state transition: Finish is not allowed from Initializing"
+        );
+    }
+
+    #[test]
+    fn test_guard_failed_display() {
+        let error = TransitionError::GuardFailed {
+            from: WorkflowPhase::PlanReady,
+            event: WorkflowEvent::StartImplementation,
+            guard: Guard::RoleRegistered(PaneRole::Commander),
+        };
+
+        assert_eq!(
+            format!("{}", error),
+            "Cannot apply StartImplementation from PlanReady: no pane registered for role Commander"
+        );
+    }
+
+    #[test]
+    fn test_debug_output_contains_variant_names() {
+        let invalid = TransitionError::InvalidTransition {
+            from: WorkflowPhase::Finished,
+            event: WorkflowEvent::StartPlanning,
+        };
+        let guard = TransitionError::GuardFailed {
+            from: WorkflowPhase::Initializing,
+            event: WorkflowEvent::StartPlanning,
+            guard: Guard::PermissionsGranted,
+        };
+
+        assert!(format!("{:?}", invalid).contains("InvalidTransition"));
+        assert!(format!("{:?}", guard).contains("GuardFailed"));
+    }
+
+    #[test]
+    fn test_action_error_display() {
+        let communication: ActionError =
+            CommunicationError::PaneNotFound(PaneRole::Commander).into();
+        assert_eq!(
+            format!("{}", communication),
+            "Pane not found for role: Commander"
+        );
+
+        let file_system: ActionError = FileSystemError::Timeout.into();
+        assert_eq!(
+            format!("{}", file_system),
+            "Task file operation failed: Timeout"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::coordination_message::CoordinationMessage;
+use crate::file_system::FileSystem;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Events that drive the workflow state machine from one phase to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WorkflowEvent {
+    /// Kick off planning in the Overseer pane
+    StartPlanning,
+    /// The todo list has been written by the planner
+    PlanReady,
+    /// Hand the plan over to the Commander for implementation
+    StartImplementation,
+    /// Every item in the todo list has been completed
+    AllTasksComplete,
+    /// Ask the Overseer to review the implementation
+    StartReview,
+    /// The review has been written
+    ReviewComplete,
+    /// Send blocking review findings back to the Commander for another round
+    RequestFixes,
+    /// Close out the workflow
+    Finish,
+    /// Move to a phase chosen by the operator, bypassing the transition table
+    Skip,
+    /// Abandon the workflow on the operator's request
+    Abort,
+    /// The operator accepts the todo list without waiting for it to validate
+    ApprovePlan,
+    /// The operator sends the plan back to the Overseer with feedback
+    RejectPlan,
+    /// The operator starts a review before every todo item is ticked
+    ForceReview,
+    /// The operator archives the task files and starts the task over
+    Reset,
+}
+
+impl WorkflowEvent {
+    /// Check whether the event is a manual override that only an operator fires
+    pub fn is_override(&self) -> bool {
+        matches!(
+            self,
+            WorkflowEvent::Skip
+                | WorkflowEvent::Abort
+                | WorkflowEvent::ApprovePlan
+                | WorkflowEvent::RejectPlan
+                | WorkflowEvent::ForceReview
+                | WorkflowEvent::Reset
+        )
+    }
+
+    /// Map an incoming coordination message to the workflow event it represents
+    ///
+    /// Messages that only carry information (progress, file changes, phase
+    /// announcements) do not drive the state machine and map to `None`.
+    pub fn from_message(message: &CoordinationMessage) -> Option<Self> {
+        match message {
+            CoordinationMessage::StartPlanning { .. } => Some(WorkflowEvent::StartPlanning),
+            CoordinationMessage::PlanReady { .. } => Some(WorkflowEvent::PlanReady),
+            CoordinationMessage::StartImplementation => Some(WorkflowEvent::StartImplementation),
+            CoordinationMessage::AllTasksComplete => Some(WorkflowEvent::AllTasksComplete),
+            CoordinationMessage::StartReview => Some(WorkflowEvent::StartReview),
+            CoordinationMessage::ReviewComplete { .. } => Some(WorkflowEvent::ReviewComplete),
+            CoordinationMessage::TaskCompleted { .. }
+            | CoordinationMessage::PhaseTransition { .. }
+            | CoordinationMessage::AssistantFailed { .. }
+            | CoordinationMessage::Ack { .. }
+            | CoordinationMessage::FileChanged { .. } => None,
+        }
+    }
+
+    /// Map a settled `FileChanged` message for a task file to the event it signals
+    ///
+    /// What a change means depends on the phase: a written todo list is the
+    /// plan while planning and progress while implementing, and a written review
+    /// completes the review. Removals and unrelated files map to `None`.
+    pub fn from_file_change(message: &CoordinationMessage, phase: WorkflowPhase) -> Option<Self> {
+        let (file_path, event_type) = match message {
+            CoordinationMessage::FileChanged {
+                file_path,
+                event_type,
+            } => (file_path, event_type),
+            _ => return None,
+        };
+
+        if event_type == "removed" {
+            return None;
+        }
+
+        match (Path::new(file_path).file_name()?.to_str()?, phase) {
+            (FileSystem::TODO_LIST_FILE, WorkflowPhase::PlanningInProgress) => {
+                Some(WorkflowEvent::PlanReady)
+            }
+            (FileSystem::TODO_LIST_FILE, WorkflowPhase::ImplementationInProgress) => {
+                Some(WorkflowEvent::AllTasksComplete)
+            }
+            (FileSystem::REVIEW_FILE, WorkflowPhase::ReviewInProgress) => {
+                Some(WorkflowEvent::ReviewComplete)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_id::TaskId;
+
+    #[test]
+    fn test_from_message_maps_driving_messages() {
+        let cases = vec![
+            (
+                CoordinationMessage::StartPlanning {
+                    task_id: TaskId::from(1),
+                    task_description: "Plan".to_string(),
+                },
+                WorkflowEvent::StartPlanning,
+            ),
+            (
+                CoordinationMessage::PlanReady {
+                    todo_file_path: "todo-list.md".to_string(),
+                },
+                WorkflowEvent::PlanReady,
+            ),
+            (
+                CoordinationMessage::StartImplementation,
+                WorkflowEvent::StartImplementation,
+            ),
+            (
+                CoordinationMessage::AllTasksComplete,
+                WorkflowEvent::AllTasksComplete,
+            ),
+            (CoordinationMessage::StartReview, WorkflowEvent::StartReview),
+            (
+                CoordinationMessage::ReviewComplete {
+                    review_file_path: "review.md".to_string(),
+                },
+                WorkflowEvent::ReviewComplete,
+            ),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(WorkflowEvent::from_message(&message), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_from_message_ignores_informational_messages() {
+        let messages = vec![
+            CoordinationMessage::TaskCompleted {
+                task_id: "1.1".to_string(),
+            },
+            CoordinationMessage::PhaseTransition {
+                from: WorkflowPhase::Initializing,
+                to: WorkflowPhase::PlanningInProgress,
+            },
+            CoordinationMessage::FileChanged {
+                file_path: "todo-list.md".to_string(),
+                event_type: "modified".to_string(),
+            },
+        ];
+
+        for message in messages {
+            assert_eq!(WorkflowEvent::from_message(&message), None);
+        }
+    }
+
+    #[test]
+    fn test_is_override() {
+        assert!(WorkflowEvent::RejectPlan.is_override());
+        assert!(WorkflowEvent::Reset.is_override());
+        assert!(WorkflowEvent::Skip.is_override());
+        assert!(!WorkflowEvent::PlanReady.is_override());
+        assert!(!WorkflowEvent::Finish.is_override());
+    }
+
+    fn file_changed(file_path: &str, event_type: &str) -> CoordinationMessage {
+        CoordinationMessage::FileChanged {
+            file_path: file_path.to_string(),
+            event_type: event_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_from_file_change_depends_on_phase() {
+        let todo = file_changed("/host/.zzz/task-1/todo-list.md", "modified");
+        let review = file_changed("/host/.zzz/task-1/review.md", "created");
+
+        assert_eq!(
+            WorkflowEvent::from_file_change(&todo, WorkflowPhase::PlanningInProgress),
+            Some(WorkflowEvent::PlanReady)
+        );
+        assert_eq!(
+            WorkflowEvent::from_file_change(&todo, WorkflowPhase::ImplementationInProgress),
+            Some(WorkflowEvent::AllTasksComplete)
+        );
+        assert_eq!(
+            WorkflowEvent::from_file_change(&review, WorkflowPhase::ReviewInProgress),
+            Some(WorkflowEvent::ReviewComplete)
+        );
+    }
+
+    #[test]
+    fn test_from_file_change_ignores_phases_not_waiting_on_file() {
+        let todo = file_changed("/host/.zzz/task-1/todo-list.md", "modified");
+        let review = file_changed("/host/.zzz/task-1/review.md", "created");
+
+        assert_eq!(
+            WorkflowEvent::from_file_change(&todo, WorkflowPhase::Initializing),
+            None
+        );
+        assert_eq!(
+            WorkflowEvent::from_file_change(&todo, WorkflowPhase::ReviewInProgress),
+            None
+        );
+        assert_eq!(
+            WorkflowEvent::from_file_change(&review, WorkflowPhase::ImplementationInProgress),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_file_change_ignores_removals_and_other_files() {
+        assert_eq!(
+            WorkflowEvent::from_file_change(
+                &file_changed("/host/.zzz/task-1/todo-list.md", "removed"),
+                WorkflowPhase::PlanningInProgress
+            ),
+            None
+        );
+        assert_eq!(
+            WorkflowEvent::from_file_change(
+                &file_changed("/host/.zzz/task-1/plan.md", "created"),
+                WorkflowPhase::PlanningInProgress
+            ),
+            None
+        );
+        assert_eq!(
+            WorkflowEvent::from_file_change(
+                &CoordinationMessage::StartReview,
+                WorkflowPhase::ReviewInProgress
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_event_serialization_roundtrip() {
+        let serialized = serde_json::to_string(&WorkflowEvent::AllTasksComplete).unwrap();
+        let deserialized: WorkflowEvent = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, WorkflowEvent::AllTasksComplete);
+    }
+}
@@ -0,0 +1,1082 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use super::commands::{self, TaskContext};
+use super::error::{ActionError, TransitionError};
+use super::event::WorkflowEvent;
+use super::machine::{PhaseChange, TransitionOutcome, WorkflowMachine};
+use super::transition::{GuardContext, PromptKind, WorkflowAction};
+use crate::assistant::AssistantPrompt;
+use crate::communication::envelope::COORDINATOR_SENDER;
+use crate::communication::{Communication, CommunicationError, MessageEnvelope, MessageRouter};
+use crate::coordination_message::CoordinationMessage;
+use crate::file_system::FileSystem;
+use crate::pane_role::PaneRole;
+use crate::review::{self, Review};
+use crate::workflow_phase::WorkflowPhase;
+use crate::zellij_service::ZellijService;
+
+/// Everything that happened while dispatching a workflow event
+#[derive(Debug, Default)]
+pub struct DispatchReport {
+    /// Phase changes applied, including chained `Advance` transitions
+    pub changes: Vec<PhaseChange>,
+    /// Actions that were attempted and failed
+    pub action_failures: Vec<(WorkflowAction, ActionError)>,
+    /// Chained `Advance` events that the state machine rejected
+    pub advance_errors: Vec<TransitionError>,
+}
+
+/// Performs workflow actions against Zellij through the router and pipe
+pub struct ActionExecutor<'a, T: ZellijService> {
+    router: &'a MessageRouter<T>,
+    communication: &'a Communication<T>,
+    task: TaskContext<'a>,
+}
+
+impl<'a, T: ZellijService> ActionExecutor<'a, T> {
+    /// Create an executor for the given task
+    pub fn new(
+        router: &'a MessageRouter<T>,
+        communication: &'a Communication<T>,
+        task: TaskContext<'a>,
+    ) -> Self {
+        Self {
+            router,
+            communication,
+            task,
+        }
+    }
+
+    /// Fire an event on the machine and run every action the transition implies
+    ///
+    /// `Advance` actions are queued and fired after the current transition's
+    /// actions have run, so a phase's entry actions always complete before the
+    /// workflow moves on.
+    pub fn dispatch(
+        &self,
+        machine: &mut WorkflowMachine,
+        event: WorkflowEvent,
+        guards: &GuardContext,
+    ) -> Result<DispatchReport, TransitionError> {
+        let outcome = machine.fire(event, guards)?;
+        Ok(self.follow(machine, outcome, guards))
+    }
+
+    /// Force the machine into `phase` and run the new phase's entry actions
+    ///
+    /// Chained `Advance` actions are still subject to their guards.
+    pub fn force(
+        &self,
+        machine: &mut WorkflowMachine,
+        phase: WorkflowPhase,
+        event: WorkflowEvent,
+        guards: &GuardContext,
+    ) -> Result<DispatchReport, TransitionError> {
+        let outcome = machine.force(phase, event)?;
+        Ok(self.follow(machine, outcome, guards))
+    }
+
+    /// Run a transition's actions and any `Advance` transitions they queue
+    fn follow(
+        &self,
+        machine: &mut WorkflowMachine,
+        outcome: TransitionOutcome,
+        guards: &GuardContext,
+    ) -> DispatchReport {
+        let mut report = DispatchReport::default();
+        let mut pending = VecDeque::new();
+
+        self.run_actions(outcome.actions, &mut pending, &mut report);
+        report.changes.push(outcome.change);
+
+        while let Some(candidates) = pending.pop_front() {
+            // Take the first candidate whose guards pass; if none do, fire the
+            // first one so its rejection is reported
+            let next = candidates
+                .iter()
+                .copied()
+                .find(|candidate| machine.check(*candidate, guards).is_ok())
+                .unwrap_or(candidates[0]);
+
+            match machine.fire(next, guards) {
+                Ok(outcome) => {
+                    self.run_actions(outcome.actions, &mut pending, &mut report);
+                    report.changes.push(outcome.change);
+                }
+                Err(e) => report.advance_errors.push(e),
+            }
+        }
+
+        report
+    }
+
+    /// Execute a single non-chaining action
+    pub fn execute(&self, action: &WorkflowAction) -> Result<(), ActionError> {
+        match action {
+            WorkflowAction::LaunchAssistant { role, prompt } => {
+                let command = self.build_command(*role, *prompt)?;
+                self.router
+                    .execute_command_in_role(&format!("{}\n", command), *role)?;
+            }
+            WorkflowAction::Broadcast(message) => self.broadcast(message.clone())?,
+            WorkflowAction::AnnouncePlanReady => {
+                let todo_file_path = self.task.file_system.get_todo_list_path(self.task.task_id);
+                self.broadcast(CoordinationMessage::PlanReady {
+                    todo_file_path: todo_file_path.to_string_lossy().into_owned(),
+                })?;
+            }
+            WorkflowAction::AnnounceReviewComplete => {
+                let review_file_path = self.task.file_system.get_review_path(self.task.task_id);
+                self.broadcast(CoordinationMessage::ReviewComplete {
+                    review_file_path: review_file_path.to_string_lossy().into_owned(),
+                })?;
+            }
+            WorkflowAction::QueueReviewFixes => self.queue_review_fixes()?,
+            WorkflowAction::ArchivePlan => self.archive_plan()?,
+            WorkflowAction::ArchiveTaskFiles => self.archive_task_files()?,
+            WorkflowAction::Advance(_) | WorkflowAction::AdvanceFirst(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Build the command line that runs a prompt with the role's assistant
+    ///
+    /// Long prompts, or ones a shell could not take as typed input, are written
+    /// to `prompt-<role>-<n>.md` and the assistant is given the path instead.
+    /// The assistant's output is captured into the role's log if configured.
+    pub fn build_command(&self, role: PaneRole, prompt: PromptKind) -> Result<String, ActionError> {
+        let text = commands::build_prompt(&self.task, prompt);
+        let assistant_prompt = if commands::needs_prompt_file(&text) {
+            self.write_prompt_file(role, &text)?
+        } else {
+            AssistantPrompt::Text(text)
+        };
+
+        Ok(commands::build_launch_command(
+            &self.task,
+            role,
+            &assistant_prompt,
+            &format!("{:?} prompt", prompt),
+        ))
+    }
+
+    /// Write a prompt to the role's next unused prompt file
+    fn write_prompt_file(
+        &self,
+        role: PaneRole,
+        text: &str,
+    ) -> Result<AssistantPrompt, ActionError> {
+        let file_system = self.task.file_system;
+        let task_id = self.task.task_id;
+        let mut n = 1;
+        while FileSystem::file_exists(file_system.get_prompt_path(task_id, role.config_key(), n)) {
+            n += 1;
+        }
+
+        FileSystem::write_file_atomic(
+            file_system.get_prompt_path(task_id, role.config_key(), n),
+            text,
+        )?;
+        Ok(AssistantPrompt::File(PathBuf::from(
+            commands::relative_prompt_path(&self.task, role, n),
+        )))
+    }
+
+    fn run_actions(
+        &self,
+        actions: Vec<WorkflowAction>,
+        pending: &mut VecDeque<Vec<WorkflowEvent>>,
+        report: &mut DispatchReport,
+    ) {
+        for action in actions {
+            match action {
+                WorkflowAction::Advance(event) => {
+                    pending.push_back(vec![event]);
+                    continue;
+                }
+                WorkflowAction::AdvanceFirst(events) if !events.is_empty() => {
+                    pending.push_back(events);
+                    continue;
+                }
+                _ => {}
+            }
+
+            if let Err(e) = self.execute(&action) {
+                report.action_failures.push((action, e));
+            }
+        }
+    }
+
+    /// Append the review's blocking findings to the todo list as a new fix round
+    ///
+    /// The review is archived as `review-round-{n}.md` so that the next review
+    /// starts from a clean slate.
+    fn queue_review_fixes(&self) -> Result<(), ActionError> {
+        let file_system = self.task.file_system;
+        let review_path = file_system.get_review_path(self.task.task_id);
+        let todo_path = file_system.get_todo_list_path(self.task.task_id);
+
+        let review = Review::parse(&FileSystem::read_file_safe(&review_path)?);
+        let todo_content = FileSystem::read_file_safe(&todo_path)?;
+        let (updated, round) = review::append_fix_round(&todo_content, &review);
+
+        FileSystem::write_file_atomic(&todo_path, &updated)?;
+        std::fs::rename(
+            &review_path,
+            file_system.get_review_archive_path(self.task.task_id, round),
+        )
+        .map_err(crate::file_system::FileSystemError::from)?;
+
+        Ok(())
+    }
+
+    /// Move a rejected todo list to the next unused `todo-list-rejected-<n>.md`
+    fn archive_plan(&self) -> Result<(), ActionError> {
+        let file_system = self.task.file_system;
+        let task_id = self.task.task_id;
+        let todo_path = file_system.get_todo_list_path(task_id);
+        if !FileSystem::file_exists(&todo_path) {
+            return Ok(());
+        }
+
+        let mut n = 1;
+        while FileSystem::file_exists(file_system.get_rejected_plan_path(task_id, n)) {
+            n += 1;
+        }
+
+        std::fs::rename(&todo_path, file_system.get_rejected_plan_path(task_id, n))
+            .map_err(crate::file_system::FileSystemError::from)?;
+        Ok(())
+    }
+
+    /// Move the workflow files of the task into the next unused `reset-<n>` directory
+    ///
+    /// State, secrets, prompts and logs stay in place.
+    fn archive_task_files(&self) -> Result<(), ActionError> {
+        let file_system = self.task.file_system;
+        let task_id = self.task.task_id;
+        let mut n = 1;
+        while file_system.get_reset_archive_dir(task_id, n).exists() {
+            n += 1;
+        }
+        let archive_dir = file_system.get_reset_archive_dir(task_id, n);
+
+        let task_dir = file_system.get_task_directory_path(task_id);
+        let entries =
+            std::fs::read_dir(&task_dir).map_err(crate::file_system::FileSystemError::from)?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !is_workflow_file(&name) {
+                continue;
+            }
+
+            std::fs::create_dir_all(&archive_dir)
+                .and_then(|_| std::fs::rename(entry.path(), archive_dir.join(&name)))
+                .map_err(crate::file_system::FileSystemError::from)?;
+        }
+
+        Ok(())
+    }
+
+    fn broadcast(&self, message: CoordinationMessage) -> Result<(), CommunicationError> {
+        let envelope = MessageEnvelope::new_broadcast(message, COORDINATOR_SENDER);
+        self.communication.send_pipe_message(&envelope)
+    }
+}
+
+/// Check whether a task directory entry is produced by the workflow rather than the coordinator
+fn is_workflow_file(name: &str) -> bool {
+    name == FileSystem::TODO_LIST_FILE
+        || name == FileSystem::REVIEW_FILE
+        || name == FileSystem::PLAN_FEEDBACK_FILE
+        || name.starts_with("review-round-")
+        || name.starts_with("todo-list-rejected-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assistant::AssistantConfig;
+    use crate::litellm_config::LiteLLMConfig;
+    use crate::task_id::TaskId;
+    use crate::workflow::transition::ready_context;
+    use crate::zellij_service::MockZellijService;
+    use std::fs;
+    use tempfile::{tempdir, TempDir};
+    use zellij_tile::prelude::PaneId;
+
+    fn test_config() -> LiteLLMConfig {
+        LiteLLMConfig {
+            api_key: "test-key".to_string(),
+            api_key_file: None,
+            url: "https://litellm.test".to_string(),
+        }
+    }
+
+    /// Task fields an executor borrows, with the task directory under a temporary root
+    struct Fixture {
+        _root: TempDir,
+        task_id: TaskId,
+        task_description: String,
+        config: LiteLLMConfig,
+        assistants: AssistantConfig,
+        file_system: FileSystem,
+    }
+
+    impl Fixture {
+        fn new(task_id: u32, task_description: &str) -> Self {
+            let root = tempdir().unwrap();
+            let file_system = FileSystem::new(root.path());
+            let task_id = TaskId::from(task_id);
+            file_system.setup_task_directories(&task_id).unwrap();
+
+            Self {
+                _root: root,
+                task_id,
+                task_description: task_description.to_string(),
+                config: test_config(),
+                assistants: AssistantConfig::default(),
+                file_system,
+            }
+        }
+
+        fn task(&self) -> TaskContext<'_> {
+            TaskContext {
+                task_id: &self.task_id,
+                task_description: &self.task_description,
+                litellm_config: &self.config,
+                assistants: &self.assistants,
+                file_system: &self.file_system,
+            }
+        }
+
+        fn task_dir(&self) -> PathBuf {
+            self.file_system.get_task_directory_path(&self.task_id)
+        }
+
+        /// Path of a task file as the assistant panes see it
+        fn pane_path(&self, file_name: &str) -> String {
+            self.file_system
+                .pane_task_directory_path(&self.task_id)
+                .join(file_name)
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    fn create_router() -> MessageRouter<MockZellijService> {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router.register_pane(PaneRole::Overseer, PaneId::Terminal(1));
+        router.register_pane(PaneRole::Commander, PaneId::Terminal(2));
+        router
+    }
+
+    fn piped_messages(
+        communication: &Communication<MockZellijService>,
+    ) -> Vec<CoordinationMessage> {
+        communication
+            .get_zellij_service()
+            .get_piped_messages()
+            .iter()
+            .map(|(payload, _)| {
+                serde_json::from_str::<MessageEnvelope>(payload)
+                    .unwrap()
+                    .coordination_message
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dispatch_start_planning_launches_codex_in_overseer() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(7, "Build a thing");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::new();
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::StartPlanning, &ready_context())
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert_eq!(report.changes.len(), 1);
+        assert!(report.action_failures.is_empty());
+
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(1));
+        assert!(sent[0].0.contains("codex --quiet"));
+        assert!(!sent[0].0.contains("test-key"));
+        assert!(sent[0].0.contains("Build a thing"));
+        // Output capture is opt-in, so the assistant keeps the terminal
+        assert!(!sent[0].0.contains("| awk"));
+        assert!(sent[0].0.ends_with('\n'));
+        // A short prompt is typed inline rather than written to a file
+        assert!(!fixture
+            .file_system
+            .get_prompt_path(&TaskId::from(7), "overseer", 1)
+            .exists());
+    }
+
+    #[test]
+    fn test_prompt_with_control_characters_is_never_typed_inline() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(7, "Column\tseparated");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        fs::remove_dir_all(fixture.task_dir()).unwrap();
+
+        // Without a task directory the prompt file cannot be written, and the
+        // prompt may not be sent to the pane instead
+        let result = executor.execute(&WorkflowAction::LaunchAssistant {
+            role: PaneRole::Overseer,
+            prompt: PromptKind::Planning,
+        });
+
+        assert!(matches!(result, Err(ActionError::FileSystem(_))));
+        assert!(router.get_zellij_service().get_sent_messages().is_empty());
+    }
+
+    #[test]
+    fn test_dispatch_rejected_event_runs_no_actions() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(7, "Build a thing");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::new();
+
+        let result = executor.dispatch(
+            &mut machine,
+            WorkflowEvent::StartPlanning,
+            &GuardContext::default(),
+        );
+
+        assert!(result.is_err());
+        assert!(router.get_zellij_service().get_sent_messages().is_empty());
+        assert!(communication
+            .get_zellij_service()
+            .get_piped_messages()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dispatch_records_action_failures() {
+        let router = MessageRouter::new(MockZellijService::new());
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(7, "Build a thing");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::new();
+
+        // Guards claim the Overseer exists, but the router has no such pane
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::StartPlanning, &ready_context())
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert_eq!(report.action_failures.len(), 1);
+        assert!(matches!(
+            report.action_failures[0].1,
+            ActionError::Communication(CommunicationError::PaneNotFound(PaneRole::Overseer))
+        ));
+    }
+
+    #[test]
+    fn test_plan_ready_hands_off_to_commander() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(5, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::PlanReady, &ready_context())
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ImplementationInProgress);
+        assert_eq!(report.changes.len(), 2);
+        assert!(report.action_failures.is_empty());
+        assert!(report.advance_errors.is_empty());
+
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(2));
+        assert!(sent[0].0.contains(&format!(
+            "Follow the todo list at {}",
+            fixture.pane_path("todo-list.md")
+        )));
+
+        assert_eq!(
+            piped_messages(&communication),
+            vec![
+                CoordinationMessage::PlanReady {
+                    todo_file_path: fixture
+                        .file_system
+                        .get_todo_list_path(&TaskId::from(5))
+                        .to_string_lossy()
+                        .into_owned(),
+                },
+                CoordinationMessage::StartImplementation,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_ready_waits_for_commander() {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router.register_pane(PaneRole::Overseer, PaneId::Terminal(1));
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(5, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+        let context = GuardContext {
+            registered_roles: vec![PaneRole::Overseer],
+            ..ready_context()
+        };
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::PlanReady, &context)
+            .unwrap();
+
+        // The plan is accepted, but implementation waits for a Commander pane
+        assert_eq!(machine.phase(), WorkflowPhase::PlanReady);
+        assert_eq!(report.advance_errors.len(), 1);
+        assert!(router.get_zellij_service().get_sent_messages().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_plan_is_rejected() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(5, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+        let context = GuardContext {
+            todo_list_valid: false,
+            ..ready_context()
+        };
+
+        let result = executor.dispatch(&mut machine, WorkflowEvent::PlanReady, &context);
+
+        assert_eq!(
+            result.unwrap_err(),
+            TransitionError::GuardFailed {
+                from: WorkflowPhase::PlanningInProgress,
+                event: WorkflowEvent::PlanReady,
+                guard: crate::workflow::Guard::TodoListValid,
+            }
+        );
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert!(piped_messages(&communication).is_empty());
+    }
+
+    #[test]
+    fn test_all_tasks_complete_starts_review_in_overseer() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(8, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine =
+            WorkflowMachine::restore(WorkflowPhase::ImplementationInProgress, Vec::new());
+
+        let report = executor
+            .dispatch(
+                &mut machine,
+                WorkflowEvent::AllTasksComplete,
+                &ready_context(),
+            )
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ReviewInProgress);
+        let phases: Vec<WorkflowPhase> = report.changes.iter().map(|c| c.to).collect();
+        assert_eq!(
+            phases,
+            vec![
+                WorkflowPhase::ImplementationComplete,
+                WorkflowPhase::ReviewInProgress
+            ]
+        );
+
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(1));
+        assert!(sent[0].0.contains(&format!(
+            "markdown file at {}",
+            fixture.pane_path("review.md")
+        )));
+
+        assert_eq!(
+            piped_messages(&communication),
+            vec![
+                CoordinationMessage::AllTasksComplete,
+                CoordinationMessage::StartReview
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchecked_items_block_completion() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(8, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine =
+            WorkflowMachine::restore(WorkflowPhase::ImplementationInProgress, Vec::new());
+        let context = GuardContext {
+            todo_list_complete: false,
+            ..ready_context()
+        };
+
+        assert!(executor
+            .dispatch(&mut machine, WorkflowEvent::AllTasksComplete, &context)
+            .is_err());
+        assert_eq!(machine.phase(), WorkflowPhase::ImplementationInProgress);
+        assert!(piped_messages(&communication).is_empty());
+    }
+
+    #[test]
+    fn test_blocking_review_starts_fix_round() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(9, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let todo_path = fixture.file_system.get_todo_list_path(&TaskId::from(9));
+        let review_path = fixture.file_system.get_review_path(&TaskId::from(9));
+        fs::write(&todo_path, "- [x] Add login\n").unwrap();
+        fs::write(
+            &review_path,
+            "- **major** `src/auth.rs:3` Password stored in plain text\n",
+        )
+        .unwrap();
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewInProgress, Vec::new());
+        let context = GuardContext {
+            review_has_blocking_findings: true,
+            ..ready_context()
+        };
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::ReviewComplete, &context)
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ImplementationInProgress);
+        assert_eq!(machine.fix_rounds(), 1);
+        assert!(report.action_failures.is_empty());
+        assert!(report.advance_errors.is_empty());
+        assert!(fs::read_to_string(&todo_path)
+            .unwrap()
+            .contains("Password stored in plain text"));
+        assert!(!review_path.exists());
+
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(2));
+        assert!(sent[0].0.contains("Fix the blocking review findings"));
+
+        assert_eq!(
+            piped_messages(&communication),
+            vec![
+                CoordinationMessage::ReviewComplete {
+                    review_file_path: fixture
+                        .file_system
+                        .get_review_path(&TaskId::from(9))
+                        .to_string_lossy()
+                        .into_owned(),
+                },
+                CoordinationMessage::StartImplementation,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_review_round_limit_finishes_workflow() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(9, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewInProgress, Vec::new());
+        let context = GuardContext {
+            review_has_blocking_findings: true,
+            fix_rounds: 2,
+            ..ready_context()
+        };
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::ReviewComplete, &context)
+            .unwrap();
+
+        assert!(machine.is_finished());
+        assert!(report.advance_errors.is_empty());
+        assert!(router.get_zellij_service().get_sent_messages().is_empty());
+    }
+
+    #[test]
+    fn test_fix_round_waits_for_commander() {
+        let mut router = MessageRouter::new(MockZellijService::new());
+        router.register_pane(PaneRole::Overseer, PaneId::Terminal(1));
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(9, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewInProgress, Vec::new());
+        let context = GuardContext {
+            registered_roles: vec![PaneRole::Overseer],
+            review_has_blocking_findings: true,
+            ..ready_context()
+        };
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::ReviewComplete, &context)
+            .unwrap();
+
+        // Neither fixing nor finishing is possible, and the fix round's reason is reported
+        assert_eq!(machine.phase(), WorkflowPhase::ReviewComplete);
+        assert_eq!(
+            report.advance_errors,
+            vec![TransitionError::GuardFailed {
+                from: WorkflowPhase::ReviewComplete,
+                event: WorkflowEvent::RequestFixes,
+                guard: crate::workflow::Guard::RoleRegistered(PaneRole::Commander),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_full_lifecycle_end_to_end() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(3, "End to end");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::new();
+        let context = ready_context();
+
+        // PlanReady chains into ImplementationInProgress and
+        // AllTasksComplete chains into ReviewInProgress
+        for event in [
+            WorkflowEvent::StartPlanning,
+            WorkflowEvent::PlanReady,
+            WorkflowEvent::AllTasksComplete,
+        ]
+        .iter()
+        {
+            executor.dispatch(&mut machine, *event, &context).unwrap();
+        }
+        assert_eq!(machine.phase(), WorkflowPhase::ReviewInProgress);
+
+        // ReviewComplete chains straight into Finish
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::ReviewComplete, &context)
+            .unwrap();
+        assert_eq!(report.changes.len(), 2);
+        assert_eq!(report.changes[1].to, WorkflowPhase::Finished);
+        assert!(report.advance_errors.is_empty());
+        assert!(machine.is_finished());
+        assert_eq!(machine.history().len(), 7);
+
+        let broadcasts = piped_messages(&communication);
+        assert_eq!(
+            broadcasts,
+            vec![
+                CoordinationMessage::PlanReady {
+                    todo_file_path: fixture
+                        .file_system
+                        .get_todo_list_path(&TaskId::from(3))
+                        .to_string_lossy()
+                        .into_owned(),
+                },
+                CoordinationMessage::StartImplementation,
+                CoordinationMessage::AllTasksComplete,
+                CoordinationMessage::StartReview,
+                CoordinationMessage::ReviewComplete {
+                    review_file_path: fixture
+                        .file_system
+                        .get_review_path(&TaskId::from(3))
+                        .to_string_lossy()
+                        .into_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_force_runs_entry_actions_of_new_phase() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(4, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        let report = executor
+            .force(
+                &mut machine,
+                WorkflowPhase::ReviewInProgress,
+                WorkflowEvent::Skip,
+                &ready_context(),
+            )
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ReviewInProgress);
+        assert_eq!(report.changes.len(), 1);
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(1));
+        // The abandoned planning phase does not announce a plan
+        assert_eq!(
+            piped_messages(&communication),
+            vec![CoordinationMessage::StartReview]
+        );
+    }
+
+    #[test]
+    fn test_force_follows_chained_advances() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(4, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        let report = executor
+            .force(
+                &mut machine,
+                WorkflowPhase::PlanReady,
+                WorkflowEvent::Skip,
+                &ready_context(),
+            )
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ImplementationInProgress);
+        assert_eq!(report.changes.len(), 2);
+        assert_eq!(
+            router.get_zellij_service().get_sent_messages()[0].1,
+            PaneId::Terminal(2)
+        );
+    }
+
+    #[test]
+    fn test_force_into_current_phase_runs_nothing() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(4, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine = WorkflowMachine::new();
+
+        let result = executor.force(
+            &mut machine,
+            WorkflowPhase::Initializing,
+            WorkflowEvent::Skip,
+            &ready_context(),
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            TransitionError::AlreadyInPhase(WorkflowPhase::Initializing)
+        );
+        assert!(router.get_zellij_service().get_sent_messages().is_empty());
+    }
+
+    #[test]
+    fn test_reject_plan_relaunches_overseer_with_feedback_prompt() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(6, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let todo_path = fixture.file_system.get_todo_list_path(&TaskId::from(6));
+        fs::write(&todo_path, "- [ ] Plan 1\n").unwrap();
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanReady, Vec::new());
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::RejectPlan, &ready_context())
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert!(report.action_failures.is_empty());
+        assert!(!todo_path.exists());
+        assert_eq!(
+            fs::read_to_string(
+                fixture
+                    .file_system
+                    .get_rejected_plan_path(&TaskId::from(6), 1)
+            )
+            .unwrap(),
+            "- [ ] Plan 1\n"
+        );
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, PaneId::Terminal(1));
+        assert!(sent[0].0.contains(&fixture.pane_path("plan-feedback.md")));
+        assert!(piped_messages(&communication).is_empty());
+    }
+
+    #[test]
+    fn test_force_review_skips_completion_announcement() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(6, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        let mut machine =
+            WorkflowMachine::restore(WorkflowPhase::ImplementationInProgress, Vec::new());
+        let context = GuardContext {
+            todo_list_complete: false,
+            ..ready_context()
+        };
+
+        executor
+            .dispatch(&mut machine, WorkflowEvent::ForceReview, &context)
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ReviewInProgress);
+        assert_eq!(
+            piped_messages(&communication),
+            vec![CoordinationMessage::StartReview]
+        );
+    }
+
+    #[test]
+    fn test_reset_reports_unarchivable_task_files_and_restarts_planning() {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let fixture = Fixture::new(6, "Add login");
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        fs::remove_dir_all(fixture.task_dir()).unwrap();
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewInProgress, Vec::new());
+
+        let report = executor
+            .dispatch(&mut machine, WorkflowEvent::Reset, &ready_context())
+            .unwrap();
+
+        assert!(matches!(
+            report.action_failures[0],
+            (WorkflowAction::ArchiveTaskFiles, ActionError::FileSystem(_))
+        ));
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert!(piped_messages(&communication).is_empty());
+    }
+
+    #[test]
+    fn test_is_workflow_file() {
+        assert!(is_workflow_file("todo-list.md"));
+        assert!(is_workflow_file("review-round-2.md"));
+        assert!(is_workflow_file("todo-list-rejected-1.md"));
+        assert!(is_workflow_file("plan-feedback.md"));
+        assert!(!is_workflow_file("state.json"));
+        assert!(!is_workflow_file("overseer.env"));
+        assert!(!is_workflow_file("prompt-overseer-1.md"));
+        assert!(!is_workflow_file("logs"));
+    }
+
+    /// Run a single action against the fixture's task
+    fn execute(fixture: &Fixture, action: WorkflowAction) -> Result<(), ActionError> {
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        ActionExecutor::new(&router, &communication, fixture.task()).execute(&action)
+    }
+
+    #[test]
+    fn test_prompt_file_is_written_under_root() {
+        let fixture = Fixture::new(4, "Column\tseparated");
+        let router = create_router();
+        let communication = Communication::new(MockZellijService::new());
+        let executor = ActionExecutor::new(&router, &communication, fixture.task());
+        for _ in 0..2 {
+            executor
+                .execute(&WorkflowAction::LaunchAssistant {
+                    role: PaneRole::Overseer,
+                    prompt: PromptKind::Planning,
+                })
+                .unwrap();
+        }
+
+        let prompt_path = fixture
+            .file_system
+            .get_prompt_path(&TaskId::from(4), "overseer", 2);
+        assert!(fs::read_to_string(&prompt_path)
+            .unwrap()
+            .contains("Column\tseparated"));
+
+        // The pane is given the prompt file's path, as it sees it
+        let sent = router.get_zellij_service().get_sent_messages();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[1].0.contains(&*prompt_path.to_string_lossy()));
+    }
+
+    #[test]
+    fn test_archive_plan_moves_todo_list_under_root() {
+        let fixture = Fixture::new(4, "Add login");
+        let todo_path = fixture.file_system.get_todo_list_path(&TaskId::from(4));
+
+        // Nothing to archive yet
+        execute(&fixture, WorkflowAction::ArchivePlan).unwrap();
+
+        for n in 1..=2 {
+            fs::write(&todo_path, format!("- [ ] Plan {}\n", n)).unwrap();
+            execute(&fixture, WorkflowAction::ArchivePlan).unwrap();
+        }
+
+        assert!(!todo_path.exists());
+        assert_eq!(
+            fs::read_to_string(
+                fixture
+                    .file_system
+                    .get_rejected_plan_path(&TaskId::from(4), 2)
+            )
+            .unwrap(),
+            "- [ ] Plan 2\n"
+        );
+    }
+
+    #[test]
+    fn test_archive_task_files_keeps_coordinator_files() {
+        let fixture = Fixture::new(4, "Add login");
+        let task_dir = fixture.task_dir();
+        for name in [
+            "todo-list.md",
+            "review.md",
+            "review-round-1.md",
+            "state.json",
+        ]
+        .iter()
+        {
+            fs::write(task_dir.join(name), "content").unwrap();
+        }
+
+        execute(&fixture, WorkflowAction::ArchiveTaskFiles).unwrap();
+
+        let archive_dir = fixture
+            .file_system
+            .get_reset_archive_dir(&TaskId::from(4), 1);
+        for name in ["todo-list.md", "review.md", "review-round-1.md"].iter() {
+            assert!(archive_dir.join(name).exists(), "{} archived", name);
+            assert!(!task_dir.join(name).exists());
+        }
+        assert!(task_dir.join("state.json").exists());
+        assert!(fixture
+            .file_system
+            .get_logs_dir_path(&TaskId::from(4))
+            .is_dir());
+    }
+
+    #[test]
+    fn test_queue_review_fixes_under_root() {
+        let fixture = Fixture::new(4, "Add login");
+        let file_system = &fixture.file_system;
+        fs::write(
+            file_system.get_todo_list_path(&TaskId::from(4)),
+            "- [x] Add login\n",
+        )
+        .unwrap();
+        fs::write(
+            file_system.get_review_path(&TaskId::from(4)),
+            "- **major** `src/auth.rs:3` Password stored in plain text\n",
+        )
+        .unwrap();
+
+        execute(&fixture, WorkflowAction::QueueReviewFixes).unwrap();
+
+        let todo = fs::read_to_string(file_system.get_todo_list_path(&TaskId::from(4))).unwrap();
+        assert!(todo.starts_with("- [x] Add login\n"));
+        assert!(todo.contains("Password stored in plain text"));
+        assert!(!file_system.get_review_path(&TaskId::from(4)).exists());
+        assert!(file_system
+            .get_review_archive_path(&TaskId::from(4), 1)
+            .exists());
+    }
+}
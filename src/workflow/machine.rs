@@ -0,0 +1,565 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::error::TransitionError;
+use super::event::WorkflowEvent;
+use super::transition::{self, GuardContext, PromptKind, Transition, WorkflowAction};
+use crate::pane_role::PaneRole;
+use crate::workflow_phase::WorkflowPhase;
+
+/// Record of a single phase change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseChange {
+    pub from: WorkflowPhase,
+    pub to: WorkflowPhase,
+    pub event: WorkflowEvent,
+    /// Unix timestamp when the transition happened
+    pub timestamp: u64,
+}
+
+/// Assistant work abandoned because its pane exited
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedWork {
+    pub role: PaneRole,
+    pub phase: WorkflowPhase,
+    pub prompt: PromptKind,
+    pub exit_status: Option<i32>,
+    /// Unix timestamp when the exit was noticed
+    pub timestamp: u64,
+}
+
+/// Result of a successful transition: the change itself plus the actions to run
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionOutcome {
+    pub change: PhaseChange,
+    /// Exit actions of the old phase followed by entry actions of the new one
+    pub actions: Vec<WorkflowAction>,
+}
+
+/// Table-driven workflow state machine
+///
+/// The machine only decides whether a transition is allowed and which actions
+/// it implies; performing those actions is left to the caller.
+#[derive(Debug, Clone)]
+pub struct WorkflowMachine {
+    phase: WorkflowPhase,
+    history: Vec<PhaseChange>,
+}
+
+impl Default for WorkflowMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorkflowMachine {
+    /// Create a machine in the `Initializing` phase
+    pub fn new() -> Self {
+        Self {
+            phase: WorkflowPhase::Initializing,
+            history: Vec::new(),
+        }
+    }
+
+    /// Rebuild a machine from a previously persisted phase and history
+    pub fn restore(phase: WorkflowPhase, history: Vec<PhaseChange>) -> Self {
+        Self { phase, history }
+    }
+
+    /// Get the current workflow phase
+    pub fn phase(&self) -> WorkflowPhase {
+        self.phase
+    }
+
+    /// Get every phase change applied so far, oldest first
+    pub fn history(&self) -> &[PhaseChange] {
+        &self.history
+    }
+
+    /// Number of fix rounds sent back to the Commander after a review
+    ///
+    /// Only rounds since the task was last reset count.
+    pub fn fix_rounds(&self) -> u32 {
+        self.history
+            .iter()
+            .rev()
+            .take_while(|change| change.event != WorkflowEvent::Reset)
+            .filter(|change| change.event == WorkflowEvent::RequestFixes)
+            .count() as u32
+    }
+
+    /// Assistant work the current phase is waiting on: the role doing it and its prompt
+    pub fn in_flight(&self) -> Option<(PaneRole, PromptKind)> {
+        match self.phase {
+            WorkflowPhase::PlanningInProgress if self.replanning() => {
+                Some((PaneRole::Overseer, PromptKind::Replan))
+            }
+            WorkflowPhase::PlanningInProgress => Some((PaneRole::Overseer, PromptKind::Planning)),
+            WorkflowPhase::ImplementationInProgress if self.fix_rounds() > 0 => {
+                Some((PaneRole::Commander, PromptKind::Fixes))
+            }
+            WorkflowPhase::ImplementationInProgress => {
+                Some((PaneRole::Commander, PromptKind::Implementation))
+            }
+            WorkflowPhase::ReviewInProgress => Some((PaneRole::Overseer, PromptKind::Review)),
+            _ => None,
+        }
+    }
+
+    /// Check whether planning was re-entered because the operator rejected the plan
+    fn replanning(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|change| change.event == WorkflowEvent::RejectPlan)
+    }
+
+    /// Record of the in-flight work lost when `role`'s pane exited, if it had any
+    pub fn fail_in_flight(&self, role: PaneRole, exit_status: Option<i32>) -> Option<FailedWork> {
+        let (working_role, prompt) = self.in_flight()?;
+        if working_role != role {
+            return None;
+        }
+
+        Some(FailedWork {
+            role,
+            phase: self.phase,
+            prompt,
+            exit_status,
+            timestamp: Self::current_timestamp(),
+        })
+    }
+
+    /// Check whether the workflow has reached its terminal phase
+    pub fn is_finished(&self) -> bool {
+        self.phase == WorkflowPhase::Finished
+    }
+
+    /// Events that have an edge out of the current phase (guards not evaluated)
+    ///
+    /// Manual overrides are left out; they are only fired on request.
+    pub fn available_events(&self) -> Vec<WorkflowEvent> {
+        transition::TRANSITIONS
+            .iter()
+            .filter(|t| t.from == self.phase && !t.event.is_override())
+            .map(|t| t.event)
+            .collect()
+    }
+
+    /// Validate an event against the transition table and its guards without applying it
+    pub fn check(
+        &self,
+        event: WorkflowEvent,
+        context: &GuardContext,
+    ) -> Result<&'static Transition, TransitionError> {
+        let transition = transition::find_transition(self.phase, event).ok_or(
+            TransitionError::InvalidTransition {
+                from: self.phase,
+                event,
+            },
+        )?;
+
+        if let Some(guard) = transition.guards.iter().find(|g| !g.check(context)) {
+            return Err(TransitionError::GuardFailed {
+                from: self.phase,
+                event,
+                guard: *guard,
+            });
+        }
+
+        Ok(transition)
+    }
+
+    /// Apply an event, moving to the next phase if the table and guards allow it
+    pub fn fire(
+        &mut self,
+        event: WorkflowEvent,
+        context: &GuardContext,
+    ) -> Result<TransitionOutcome, TransitionError> {
+        let transition = self.check(event, context)?;
+
+        let change = PhaseChange {
+            from: transition.from,
+            to: transition.to,
+            event,
+            timestamp: Self::current_timestamp(),
+        };
+
+        let mut actions = transition::exit_actions(transition.from, event);
+        actions.extend(transition::entry_actions(transition.to, event));
+
+        self.phase = transition.to;
+        self.history.push(change.clone());
+
+        Ok(TransitionOutcome { change, actions })
+    }
+
+    /// Move straight to `phase`, bypassing the transition table and its guards
+    ///
+    /// Used for operator overrides such as skipping or aborting; the change is
+    /// recorded under `event`. Only the new phase's entry actions are returned,
+    /// since the work of the phase being left was not completed.
+    pub fn force(
+        &mut self,
+        phase: WorkflowPhase,
+        event: WorkflowEvent,
+    ) -> Result<TransitionOutcome, TransitionError> {
+        if phase == self.phase {
+            return Err(TransitionError::AlreadyInPhase(phase));
+        }
+
+        let change = PhaseChange {
+            from: self.phase,
+            to: phase,
+            event,
+            timestamp: Self::current_timestamp(),
+        };
+
+        self.phase = phase;
+        self.history.push(change.clone());
+
+        Ok(TransitionOutcome {
+            change,
+            actions: transition::entry_actions(phase, event),
+        })
+    }
+
+    /// Get current Unix timestamp
+    fn current_timestamp() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::transition::ready_context;
+    use crate::workflow::transition::Guard;
+
+    #[test]
+    fn test_new_machine_starts_initializing() {
+        let machine = WorkflowMachine::new();
+        assert_eq!(machine.phase(), WorkflowPhase::Initializing);
+        assert!(machine.history().is_empty());
+        assert!(!machine.is_finished());
+    }
+
+    #[test]
+    fn test_fire_valid_transition() {
+        let mut machine = WorkflowMachine::new();
+        let outcome = machine
+            .fire(WorkflowEvent::StartPlanning, &ready_context())
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert_eq!(outcome.change.from, WorkflowPhase::Initializing);
+        assert_eq!(outcome.change.to, WorkflowPhase::PlanningInProgress);
+        assert_eq!(outcome.change.event, WorkflowEvent::StartPlanning);
+        assert!(outcome.change.timestamp > 0);
+        assert_eq!(
+            outcome.actions,
+            vec![WorkflowAction::LaunchAssistant {
+                role: PaneRole::Overseer,
+                prompt: PromptKind::Planning,
+            }]
+        );
+        assert_eq!(machine.history().len(), 1);
+    }
+
+    #[test]
+    fn test_fire_invalid_transition_leaves_phase_unchanged() {
+        let mut machine = WorkflowMachine::new();
+        let result = machine.fire(WorkflowEvent::StartReview, &ready_context());
+
+        assert_eq!(
+            result.unwrap_err(),
+            TransitionError::InvalidTransition {
+                from: WorkflowPhase::Initializing,
+                event: WorkflowEvent::StartReview,
+            }
+        );
+        assert_eq!(machine.phase(), WorkflowPhase::Initializing);
+        assert!(machine.history().is_empty());
+    }
+
+    #[test]
+    fn test_fire_reports_first_failing_guard() {
+        let mut machine = WorkflowMachine::new();
+        let context = GuardContext {
+            permissions_granted: true,
+            ..GuardContext::default()
+        };
+
+        let result = machine.fire(WorkflowEvent::StartPlanning, &context);
+        assert_eq!(
+            result.unwrap_err(),
+            TransitionError::GuardFailed {
+                from: WorkflowPhase::Initializing,
+                event: WorkflowEvent::StartPlanning,
+                guard: Guard::PanesDiscovered,
+            }
+        );
+        assert_eq!(machine.phase(), WorkflowPhase::Initializing);
+    }
+
+    #[test]
+    fn test_exit_actions_precede_entry_actions() {
+        let mut machine = WorkflowMachine::new();
+        let context = ready_context();
+        machine
+            .fire(WorkflowEvent::StartPlanning, &context)
+            .unwrap();
+        machine.fire(WorkflowEvent::PlanReady, &context).unwrap();
+        machine
+            .fire(WorkflowEvent::StartImplementation, &context)
+            .unwrap();
+
+        let outcome = machine
+            .fire(WorkflowEvent::AllTasksComplete, &context)
+            .unwrap();
+        assert_eq!(
+            outcome.actions,
+            vec![
+                WorkflowAction::Broadcast(
+                    crate::coordination_message::CoordinationMessage::AllTasksComplete
+                ),
+                WorkflowAction::Advance(WorkflowEvent::StartReview),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_full_lifecycle_records_history() {
+        let mut machine = WorkflowMachine::new();
+        let context = ready_context();
+        let events = [
+            WorkflowEvent::StartPlanning,
+            WorkflowEvent::PlanReady,
+            WorkflowEvent::StartImplementation,
+            WorkflowEvent::AllTasksComplete,
+            WorkflowEvent::StartReview,
+            WorkflowEvent::ReviewComplete,
+            WorkflowEvent::Finish,
+        ];
+
+        for event in events.iter() {
+            machine.fire(*event, &context).unwrap();
+        }
+
+        assert!(machine.is_finished());
+        let recorded: Vec<WorkflowEvent> = machine.history().iter().map(|c| c.event).collect();
+        assert_eq!(recorded, events.to_vec());
+        assert!(machine.available_events().is_empty());
+    }
+
+    #[test]
+    fn test_restore_continues_from_persisted_phase() {
+        let history = vec![PhaseChange {
+            from: WorkflowPhase::Initializing,
+            to: WorkflowPhase::PlanningInProgress,
+            event: WorkflowEvent::StartPlanning,
+            timestamp: 1,
+        }];
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, history);
+
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        assert_eq!(machine.history().len(), 1);
+
+        // Planning must not be restarted after a restore
+        assert!(machine
+            .fire(WorkflowEvent::StartPlanning, &ready_context())
+            .is_err());
+        machine
+            .fire(WorkflowEvent::PlanReady, &ready_context())
+            .unwrap();
+        assert_eq!(machine.history().len(), 2);
+    }
+
+    #[test]
+    fn test_review_with_blocking_findings_loops_back() {
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewComplete, Vec::new());
+        let context = GuardContext {
+            review_has_blocking_findings: true,
+            ..ready_context()
+        };
+
+        assert!(machine.fire(WorkflowEvent::Finish, &context).is_err());
+        let outcome = machine.fire(WorkflowEvent::RequestFixes, &context).unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ImplementationInProgress);
+        assert_eq!(machine.fix_rounds(), 1);
+        assert_eq!(
+            outcome.actions,
+            vec![
+                WorkflowAction::QueueReviewFixes,
+                WorkflowAction::Broadcast(
+                    crate::coordination_message::CoordinationMessage::StartImplementation
+                ),
+                WorkflowAction::LaunchAssistant {
+                    role: PaneRole::Commander,
+                    prompt: PromptKind::Fixes,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_in_flight_follows_phase() {
+        assert_eq!(WorkflowMachine::new().in_flight(), None);
+        assert_eq!(
+            WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new()).in_flight(),
+            Some((PaneRole::Overseer, PromptKind::Planning))
+        );
+        assert_eq!(
+            WorkflowMachine::restore(WorkflowPhase::ImplementationInProgress, Vec::new())
+                .in_flight(),
+            Some((PaneRole::Commander, PromptKind::Implementation))
+        );
+        assert_eq!(
+            WorkflowMachine::restore(WorkflowPhase::ReviewInProgress, Vec::new()).in_flight(),
+            Some((PaneRole::Overseer, PromptKind::Review))
+        );
+        assert_eq!(
+            WorkflowMachine::restore(WorkflowPhase::PlanReady, Vec::new()).in_flight(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_in_flight_after_fix_round_is_fixes() {
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewComplete, Vec::new());
+        let context = GuardContext {
+            review_has_blocking_findings: true,
+            ..ready_context()
+        };
+        machine.fire(WorkflowEvent::RequestFixes, &context).unwrap();
+
+        assert_eq!(
+            machine.in_flight(),
+            Some((PaneRole::Commander, PromptKind::Fixes))
+        );
+    }
+
+    #[test]
+    fn test_fail_in_flight_only_for_working_role() {
+        let machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        assert_eq!(machine.fail_in_flight(PaneRole::Commander, Some(1)), None);
+
+        let failure = machine.fail_in_flight(PaneRole::Overseer, Some(1)).unwrap();
+        assert_eq!(failure.role, PaneRole::Overseer);
+        assert_eq!(failure.phase, WorkflowPhase::PlanningInProgress);
+        assert_eq!(failure.prompt, PromptKind::Planning);
+        assert_eq!(failure.exit_status, Some(1));
+        assert!(failure.timestamp > 0);
+        // Failing work does not move the workflow
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+    }
+
+    #[test]
+    fn test_available_events() {
+        let machine = WorkflowMachine::new();
+        assert_eq!(
+            machine.available_events(),
+            vec![WorkflowEvent::StartPlanning]
+        );
+    }
+
+    #[test]
+    fn test_check_does_not_mutate() {
+        let machine = WorkflowMachine::new();
+        let transition = machine
+            .check(WorkflowEvent::StartPlanning, &ready_context())
+            .unwrap();
+
+        assert_eq!(transition.to, WorkflowPhase::PlanningInProgress);
+        assert_eq!(machine.phase(), WorkflowPhase::Initializing);
+    }
+
+    #[test]
+    fn test_force_bypasses_table_and_guards() {
+        let mut machine = WorkflowMachine::new();
+
+        let outcome = machine
+            .force(WorkflowPhase::ReviewInProgress, WorkflowEvent::Skip)
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::ReviewInProgress);
+        assert_eq!(outcome.change.from, WorkflowPhase::Initializing);
+        assert_eq!(outcome.change.event, WorkflowEvent::Skip);
+        assert_eq!(machine.history(), std::slice::from_ref(&outcome.change));
+        // Only the new phase's entry actions run
+        assert_eq!(
+            outcome.actions,
+            transition::entry_actions(WorkflowPhase::ReviewInProgress, WorkflowEvent::Skip)
+        );
+    }
+
+    #[test]
+    fn test_force_skips_exit_actions_of_abandoned_phase() {
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        let outcome = machine
+            .force(WorkflowPhase::Finished, WorkflowEvent::Abort)
+            .unwrap();
+
+        assert!(machine.is_finished());
+        assert!(outcome.actions.is_empty());
+    }
+
+    #[test]
+    fn test_force_into_current_phase_is_rejected() {
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanReady, Vec::new());
+
+        assert_eq!(
+            machine.force(WorkflowPhase::PlanReady, WorkflowEvent::Skip),
+            Err(TransitionError::AlreadyInPhase(WorkflowPhase::PlanReady))
+        );
+        assert!(machine.history().is_empty());
+    }
+
+    #[test]
+    fn test_overrides_are_not_available_events() {
+        let machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+        assert_eq!(machine.available_events(), vec![WorkflowEvent::PlanReady]);
+
+        let machine = WorkflowMachine::restore(WorkflowPhase::Finished, Vec::new());
+        assert!(machine.available_events().is_empty());
+    }
+
+    #[test]
+    fn test_reject_plan_replans_with_feedback_prompt() {
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::PlanningInProgress, Vec::new());
+
+        let outcome = machine
+            .fire(WorkflowEvent::RejectPlan, &ready_context())
+            .unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::PlanningInProgress);
+        // The rejected plan is not announced
+        assert!(!outcome.actions.contains(&WorkflowAction::AnnouncePlanReady));
+        assert_eq!(
+            machine.in_flight(),
+            Some((PaneRole::Overseer, PromptKind::Replan))
+        );
+    }
+
+    #[test]
+    fn test_reset_clears_fix_rounds() {
+        let mut machine = WorkflowMachine::restore(WorkflowPhase::ReviewComplete, Vec::new());
+        let context = GuardContext {
+            review_has_blocking_findings: true,
+            ..ready_context()
+        };
+        machine.fire(WorkflowEvent::RequestFixes, &context).unwrap();
+        assert_eq!(machine.fix_rounds(), 1);
+
+        machine.fire(WorkflowEvent::Reset, &context).unwrap();
+
+        assert_eq!(machine.phase(), WorkflowPhase::Initializing);
+        assert_eq!(machine.fix_rounds(), 0);
+        assert_eq!(machine.history().len(), 2);
+    }
+}
@@ -0,0 +1,13 @@
+pub mod commands;
+pub mod error;
+pub mod event;
+pub mod executor;
+pub mod machine;
+pub mod transition;
+
+pub use commands::TaskContext;
+pub use error::{ActionError, TransitionError};
+pub use event::WorkflowEvent;
+pub use executor::{ActionExecutor, DispatchReport};
+pub use machine::{FailedWork, PhaseChange, TransitionOutcome, WorkflowMachine};
+pub use transition::{Guard, GuardContext, PromptKind, Transition, WorkflowAction};
@@ -0,0 +1,700 @@
+use serde::{Deserialize, Serialize};
+
+use crate::coordination_message::CoordinationMessage;
+use crate::pane_role::PaneRole;
+use crate::workflow_phase::WorkflowPhase;
+
+use super::event::WorkflowEvent;
+
+/// Predicates that must hold before a transition is allowed to fire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guard {
+    /// Zellij granted the permissions requested in `load`
+    PermissionsGranted,
+    /// At least one pane has been mapped to a role
+    PanesDiscovered,
+    /// LiteLLM API key and URL are both configured, and the key could be
+    /// handed to the assistants
+    LiteLLMConfigured,
+    /// A pane is registered for the given role
+    RoleRegistered(PaneRole),
+    /// The todo-list.md file exists for the current task
+    TodoListExists,
+    /// The todo list parses into a usable plan
+    TodoListValid,
+    /// Every item in the todo list is ticked
+    TodoListComplete,
+    /// The review.md file exists for the current task
+    ReviewExists,
+    /// The review contains findings that must be fixed
+    ReviewHasBlockingFindings,
+    /// Another fix round fits within the configured review round limit
+    ReviewRoundsRemaining,
+    /// The review can be closed: nothing blocking is left, or no rounds remain
+    ReviewResolved,
+}
+
+impl Guard {
+    /// Evaluate the guard against the current coordinator facts
+    pub fn check(&self, context: &GuardContext) -> bool {
+        match self {
+            Guard::PermissionsGranted => context.permissions_granted,
+            Guard::PanesDiscovered => !context.registered_roles.is_empty(),
+            Guard::LiteLLMConfigured => context.litellm_configured,
+            Guard::RoleRegistered(role) => context.registered_roles.contains(role),
+            Guard::TodoListExists => context.todo_list_exists,
+            Guard::TodoListValid => context.todo_list_valid,
+            Guard::TodoListComplete => context.todo_list_complete,
+            Guard::ReviewExists => context.review_exists,
+            Guard::ReviewHasBlockingFindings => context.review_has_blocking_findings,
+            Guard::ReviewRoundsRemaining => context.review_rounds_remaining(),
+            Guard::ReviewResolved => {
+                !context.review_has_blocking_findings || !context.review_rounds_remaining()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Guard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Guard::PermissionsGranted => write!(f, "permissions not granted"),
+            Guard::PanesDiscovered => write!(f, "no panes discovered"),
+            Guard::LiteLLMConfigured => write!(f, "LiteLLM configuration incomplete"),
+            Guard::RoleRegistered(role) => write!(f, "no pane registered for role {:?}", role),
+            Guard::TodoListExists => write!(f, "todo list has not been written"),
+            Guard::TodoListValid => write!(f, "todo list is not a valid plan"),
+            Guard::TodoListComplete => write!(f, "todo list still has unchecked items"),
+            Guard::ReviewExists => write!(f, "review has not been written"),
+            Guard::ReviewHasBlockingFindings => write!(f, "review has no blocking findings"),
+            Guard::ReviewRoundsRemaining => write!(f, "review round limit reached"),
+            Guard::ReviewResolved => write!(f, "review still has blocking findings"),
+        }
+    }
+}
+
+/// Snapshot of coordinator state that guards are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct GuardContext {
+    pub permissions_granted: bool,
+    pub registered_roles: Vec<PaneRole>,
+    pub litellm_configured: bool,
+    pub todo_list_exists: bool,
+    pub todo_list_valid: bool,
+    pub todo_list_complete: bool,
+    pub review_exists: bool,
+    pub review_has_blocking_findings: bool,
+    /// Fix rounds already sent to the Commander
+    pub fix_rounds: u32,
+    /// Maximum number of reviews, including the first one
+    pub max_review_rounds: u32,
+}
+
+impl GuardContext {
+    /// Check whether another review would still fit after one more fix round
+    pub fn review_rounds_remaining(&self) -> bool {
+        self.fix_rounds + 1 < self.max_review_rounds
+    }
+}
+
+/// Guard context in which every guard passes and the review blocks nothing
+#[cfg(test)]
+pub(crate) fn ready_context() -> GuardContext {
+    GuardContext {
+        permissions_granted: true,
+        registered_roles: vec![PaneRole::Overseer, PaneRole::Commander],
+        litellm_configured: true,
+        todo_list_exists: true,
+        todo_list_valid: true,
+        todo_list_complete: true,
+        review_exists: true,
+        review_has_blocking_findings: false,
+        fix_rounds: 0,
+        max_review_rounds: 3,
+    }
+}
+
+/// A single edge in the workflow transition table
+#[derive(Debug)]
+pub struct Transition {
+    pub from: WorkflowPhase,
+    pub event: WorkflowEvent,
+    pub to: WorkflowPhase,
+    pub guards: &'static [Guard],
+}
+
+/// The complete workflow transition table
+pub const TRANSITIONS: &[Transition] = &[
+    Transition {
+        from: WorkflowPhase::Initializing,
+        event: WorkflowEvent::StartPlanning,
+        to: WorkflowPhase::PlanningInProgress,
+        guards: &[
+            Guard::PermissionsGranted,
+            Guard::PanesDiscovered,
+            Guard::LiteLLMConfigured,
+            Guard::RoleRegistered(PaneRole::Overseer),
+        ],
+    },
+    Transition {
+        from: WorkflowPhase::PlanningInProgress,
+        event: WorkflowEvent::PlanReady,
+        to: WorkflowPhase::PlanReady,
+        guards: &[Guard::TodoListExists, Guard::TodoListValid],
+    },
+    Transition {
+        from: WorkflowPhase::PlanReady,
+        event: WorkflowEvent::StartImplementation,
+        to: WorkflowPhase::ImplementationInProgress,
+        guards: &[Guard::RoleRegistered(PaneRole::Commander)],
+    },
+    Transition {
+        from: WorkflowPhase::ImplementationInProgress,
+        event: WorkflowEvent::AllTasksComplete,
+        to: WorkflowPhase::ImplementationComplete,
+        guards: &[Guard::TodoListComplete],
+    },
+    Transition {
+        from: WorkflowPhase::ImplementationComplete,
+        event: WorkflowEvent::StartReview,
+        to: WorkflowPhase::ReviewInProgress,
+        guards: &[Guard::RoleRegistered(PaneRole::Overseer)],
+    },
+    Transition {
+        from: WorkflowPhase::ReviewInProgress,
+        event: WorkflowEvent::ReviewComplete,
+        to: WorkflowPhase::ReviewComplete,
+        guards: &[Guard::ReviewExists],
+    },
+    Transition {
+        from: WorkflowPhase::ReviewComplete,
+        event: WorkflowEvent::Finish,
+        to: WorkflowPhase::Finished,
+        guards: &[Guard::ReviewResolved],
+    },
+    Transition {
+        from: WorkflowPhase::ReviewComplete,
+        event: WorkflowEvent::RequestFixes,
+        to: WorkflowPhase::ImplementationInProgress,
+        guards: &[
+            Guard::ReviewHasBlockingFindings,
+            Guard::ReviewRoundsRemaining,
+            Guard::RoleRegistered(PaneRole::Commander),
+        ],
+    },
+    // Manual overrides fired from keybinds or the control pipe
+    Transition {
+        from: WorkflowPhase::PlanningInProgress,
+        event: WorkflowEvent::ApprovePlan,
+        to: WorkflowPhase::PlanReady,
+        guards: &[Guard::TodoListExists],
+    },
+    Transition {
+        from: WorkflowPhase::PlanningInProgress,
+        event: WorkflowEvent::RejectPlan,
+        to: WorkflowPhase::PlanningInProgress,
+        guards: &[Guard::RoleRegistered(PaneRole::Overseer)],
+    },
+    Transition {
+        from: WorkflowPhase::PlanReady,
+        event: WorkflowEvent::RejectPlan,
+        to: WorkflowPhase::PlanningInProgress,
+        guards: &[Guard::RoleRegistered(PaneRole::Overseer)],
+    },
+    Transition {
+        from: WorkflowPhase::ImplementationInProgress,
+        event: WorkflowEvent::RejectPlan,
+        to: WorkflowPhase::PlanningInProgress,
+        guards: &[Guard::RoleRegistered(PaneRole::Overseer)],
+    },
+    Transition {
+        from: WorkflowPhase::ImplementationInProgress,
+        event: WorkflowEvent::ForceReview,
+        to: WorkflowPhase::ImplementationComplete,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::PlanningInProgress,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::PlanReady,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::ImplementationInProgress,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::ImplementationComplete,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::ReviewInProgress,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::ReviewComplete,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+    Transition {
+        from: WorkflowPhase::Finished,
+        event: WorkflowEvent::Reset,
+        to: WorkflowPhase::Initializing,
+        guards: &[],
+    },
+];
+
+/// Look up the transition for an event fired from the given phase
+pub fn find_transition(from: WorkflowPhase, event: WorkflowEvent) -> Option<&'static Transition> {
+    TRANSITIONS
+        .iter()
+        .find(|transition| transition.from == from && transition.event == event)
+}
+
+/// The kind of prompt an assistant is launched with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromptKind {
+    /// Generate the todo list for the task
+    Planning,
+    /// Work through the todo list, ticking items as they are done
+    Implementation,
+    /// Review the implementation and write review.md
+    Review,
+    /// Fix the review findings appended to the todo list
+    Fixes,
+    /// Rewrite a rejected todo list following the operator's feedback
+    Replan,
+}
+
+/// Side effects performed when a phase is entered or exited
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkflowAction {
+    /// Type an assistant command into the pane registered for `role`
+    LaunchAssistant { role: PaneRole, prompt: PromptKind },
+    /// Broadcast a coordination message on the coordination pipe
+    Broadcast(CoordinationMessage),
+    /// Broadcast `PlanReady` with the todo list path of the current task
+    AnnouncePlanReady,
+    /// Broadcast `ReviewComplete` with the review path of the current task
+    AnnounceReviewComplete,
+    /// Append blocking review findings to the todo list and archive the review
+    QueueReviewFixes,
+    /// Archive the rejected todo list so the next one is written from scratch
+    ArchivePlan,
+    /// Move the task's workflow files into a `reset-<n>` directory
+    ArchiveTaskFiles,
+    /// Immediately fire another workflow event after this transition
+    Advance(WorkflowEvent),
+    /// Fire the first of these events whose guards pass after this transition
+    AdvanceFirst(Vec<WorkflowEvent>),
+}
+
+/// Actions performed when the workflow enters `phase` because of `event`
+pub fn entry_actions(phase: WorkflowPhase, event: WorkflowEvent) -> Vec<WorkflowAction> {
+    match phase {
+        WorkflowPhase::Initializing if event == WorkflowEvent::Reset => vec![
+            WorkflowAction::ArchiveTaskFiles,
+            WorkflowAction::Advance(WorkflowEvent::StartPlanning),
+        ],
+        WorkflowPhase::PlanningInProgress if event == WorkflowEvent::RejectPlan => vec![
+            WorkflowAction::ArchivePlan,
+            WorkflowAction::LaunchAssistant {
+                role: PaneRole::Overseer,
+                prompt: PromptKind::Replan,
+            },
+        ],
+        WorkflowPhase::PlanningInProgress => vec![WorkflowAction::LaunchAssistant {
+            role: PaneRole::Overseer,
+            prompt: PromptKind::Planning,
+        }],
+        WorkflowPhase::PlanReady => {
+            vec![WorkflowAction::Advance(WorkflowEvent::StartImplementation)]
+        }
+        WorkflowPhase::ImplementationInProgress if event == WorkflowEvent::RequestFixes => vec![
+            WorkflowAction::QueueReviewFixes,
+            WorkflowAction::Broadcast(CoordinationMessage::StartImplementation),
+            WorkflowAction::LaunchAssistant {
+                role: PaneRole::Commander,
+                prompt: PromptKind::Fixes,
+            },
+        ],
+        WorkflowPhase::ImplementationInProgress => vec![
+            WorkflowAction::Broadcast(CoordinationMessage::StartImplementation),
+            WorkflowAction::LaunchAssistant {
+                role: PaneRole::Commander,
+                prompt: PromptKind::Implementation,
+            },
+        ],
+        WorkflowPhase::ImplementationComplete => {
+            vec![WorkflowAction::Advance(WorkflowEvent::StartReview)]
+        }
+        WorkflowPhase::ReviewInProgress => vec![
+            WorkflowAction::Broadcast(CoordinationMessage::StartReview),
+            WorkflowAction::LaunchAssistant {
+                role: PaneRole::Overseer,
+                prompt: PromptKind::Review,
+            },
+        ],
+        WorkflowPhase::ReviewComplete => vec![WorkflowAction::AdvanceFirst(vec![
+            WorkflowEvent::RequestFixes,
+            WorkflowEvent::Finish,
+        ])],
+        _ => Vec::new(),
+    }
+}
+
+/// Actions performed when the workflow leaves `phase` because of `event`
+///
+/// A phase cut short by an override does not announce its result.
+pub fn exit_actions(phase: WorkflowPhase, event: WorkflowEvent) -> Vec<WorkflowAction> {
+    if matches!(
+        event,
+        WorkflowEvent::RejectPlan | WorkflowEvent::ForceReview | WorkflowEvent::Reset
+    ) {
+        return Vec::new();
+    }
+
+    match phase {
+        WorkflowPhase::PlanningInProgress => vec![WorkflowAction::AnnouncePlanReady],
+        WorkflowPhase::ImplementationInProgress => vec![WorkflowAction::Broadcast(
+            CoordinationMessage::AllTasksComplete,
+        )],
+        WorkflowPhase::ReviewInProgress => vec![WorkflowAction::AnnounceReviewComplete],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_transition_table_has_no_duplicate_edges() {
+        let mut seen = HashSet::new();
+        for transition in TRANSITIONS {
+            assert!(
+                seen.insert((transition.from, transition.event)),
+                "Duplicate transition from {:?} on {:?}",
+                transition.from,
+                transition.event
+            );
+        }
+    }
+
+    #[test]
+    fn test_transition_table_reaches_finished() {
+        // The happy path with one round of fixes, and the phase each event leads to
+        let run = [
+            (
+                WorkflowEvent::StartPlanning,
+                WorkflowPhase::PlanningInProgress,
+            ),
+            (WorkflowEvent::PlanReady, WorkflowPhase::PlanReady),
+            (
+                WorkflowEvent::StartImplementation,
+                WorkflowPhase::ImplementationInProgress,
+            ),
+            (
+                WorkflowEvent::AllTasksComplete,
+                WorkflowPhase::ImplementationComplete,
+            ),
+            (WorkflowEvent::StartReview, WorkflowPhase::ReviewInProgress),
+            (WorkflowEvent::ReviewComplete, WorkflowPhase::ReviewComplete),
+            (
+                WorkflowEvent::RequestFixes,
+                WorkflowPhase::ImplementationInProgress,
+            ),
+            (
+                WorkflowEvent::AllTasksComplete,
+                WorkflowPhase::ImplementationComplete,
+            ),
+            (WorkflowEvent::StartReview, WorkflowPhase::ReviewInProgress),
+            (WorkflowEvent::ReviewComplete, WorkflowPhase::ReviewComplete),
+            (WorkflowEvent::Finish, WorkflowPhase::Finished),
+        ];
+
+        let mut phase = WorkflowPhase::Initializing;
+        for (event, expected) in run.iter() {
+            let transition = find_transition(phase, *event)
+                .unwrap_or_else(|| panic!("No {:?} transition from {:?}", event, phase));
+            assert_eq!(
+                transition.to, *expected,
+                "{:?} from {:?} went to {:?}",
+                event, phase, transition.to
+            );
+            phase = transition.to;
+        }
+    }
+
+    #[test]
+    fn test_find_transition() {
+        let transition =
+            find_transition(WorkflowPhase::PlanReady, WorkflowEvent::StartImplementation).unwrap();
+        assert_eq!(transition.to, WorkflowPhase::ImplementationInProgress);
+
+        assert!(find_transition(WorkflowPhase::Initializing, WorkflowEvent::Finish).is_none());
+    }
+
+    #[test]
+    fn test_guards_pass_with_ready_context() {
+        let context = ready_context();
+        // RequestFixes is the only edge that needs an unresolved review
+        for transition in TRANSITIONS
+            .iter()
+            .filter(|t| t.event != WorkflowEvent::RequestFixes)
+        {
+            for guard in transition.guards {
+                assert!(guard.check(&context), "{:?} should pass", guard);
+            }
+        }
+    }
+
+    #[test]
+    fn test_guards_fail_with_default_context() {
+        let context = GuardContext::default();
+        assert!(!Guard::PermissionsGranted.check(&context));
+        assert!(!Guard::PanesDiscovered.check(&context));
+        assert!(!Guard::LiteLLMConfigured.check(&context));
+        assert!(!Guard::RoleRegistered(PaneRole::Overseer).check(&context));
+        assert!(!Guard::TodoListExists.check(&context));
+        assert!(!Guard::TodoListValid.check(&context));
+        assert!(!Guard::TodoListComplete.check(&context));
+        assert!(!Guard::ReviewExists.check(&context));
+    }
+
+    #[test]
+    fn test_role_registered_guard_checks_specific_role() {
+        let context = GuardContext {
+            registered_roles: vec![PaneRole::Overseer],
+            ..GuardContext::default()
+        };
+        assert!(Guard::RoleRegistered(PaneRole::Overseer).check(&context));
+        assert!(!Guard::RoleRegistered(PaneRole::Commander).check(&context));
+    }
+
+    #[test]
+    fn test_review_round_guards() {
+        let mut context = GuardContext {
+            review_has_blocking_findings: true,
+            fix_rounds: 0,
+            max_review_rounds: 3,
+            ..GuardContext::default()
+        };
+        assert!(Guard::ReviewHasBlockingFindings.check(&context));
+        assert!(Guard::ReviewRoundsRemaining.check(&context));
+        assert!(!Guard::ReviewResolved.check(&context));
+
+        // The third review is the last one, so its findings close the workflow
+        context.fix_rounds = 2;
+        assert!(!Guard::ReviewRoundsRemaining.check(&context));
+        assert!(Guard::ReviewResolved.check(&context));
+
+        context.fix_rounds = 0;
+        context.review_has_blocking_findings = false;
+        assert!(!Guard::ReviewHasBlockingFindings.check(&context));
+        assert!(Guard::ReviewResolved.check(&context));
+    }
+
+    #[test]
+    fn test_guard_display() {
+        assert_eq!(
+            format!("{}", Guard::PermissionsGranted),
+            "permissions not granted"
+        );
+        assert_eq!(
+            format!("{}", Guard::RoleRegistered(PaneRole::Commander)),
+            "no pane registered for role Commander"
+        );
+    }
+
+    #[test]
+    fn test_entry_and_exit_actions() {
+        assert_eq!(
+            entry_actions(
+                WorkflowPhase::PlanningInProgress,
+                WorkflowEvent::StartPlanning
+            ),
+            vec![WorkflowAction::LaunchAssistant {
+                role: PaneRole::Overseer,
+                prompt: PromptKind::Planning,
+            }]
+        );
+        assert_eq!(
+            exit_actions(WorkflowPhase::PlanningInProgress, WorkflowEvent::PlanReady),
+            vec![WorkflowAction::AnnouncePlanReady]
+        );
+        assert_eq!(
+            entry_actions(WorkflowPhase::ReviewComplete, WorkflowEvent::ReviewComplete),
+            vec![WorkflowAction::AdvanceFirst(vec![
+                WorkflowEvent::RequestFixes,
+                WorkflowEvent::Finish
+            ])]
+        );
+        assert_eq!(
+            entry_actions(
+                WorkflowPhase::ImplementationInProgress,
+                WorkflowEvent::RequestFixes
+            ),
+            vec![
+                WorkflowAction::QueueReviewFixes,
+                WorkflowAction::Broadcast(CoordinationMessage::StartImplementation),
+                WorkflowAction::LaunchAssistant {
+                    role: PaneRole::Commander,
+                    prompt: PromptKind::Fixes,
+                },
+            ]
+        );
+        assert_eq!(
+            exit_actions(
+                WorkflowPhase::ReviewInProgress,
+                WorkflowEvent::ReviewComplete
+            ),
+            vec![WorkflowAction::AnnounceReviewComplete]
+        );
+        assert_eq!(
+            entry_actions(WorkflowPhase::PlanReady, WorkflowEvent::PlanReady),
+            vec![WorkflowAction::Advance(WorkflowEvent::StartImplementation)]
+        );
+        assert_eq!(
+            entry_actions(
+                WorkflowPhase::ImplementationInProgress,
+                WorkflowEvent::StartImplementation
+            ),
+            vec![
+                WorkflowAction::Broadcast(CoordinationMessage::StartImplementation),
+                WorkflowAction::LaunchAssistant {
+                    role: PaneRole::Commander,
+                    prompt: PromptKind::Implementation,
+                },
+            ]
+        );
+        assert_eq!(
+            entry_actions(
+                WorkflowPhase::ImplementationComplete,
+                WorkflowEvent::AllTasksComplete
+            ),
+            vec![WorkflowAction::Advance(WorkflowEvent::StartReview)]
+        );
+        assert_eq!(
+            entry_actions(WorkflowPhase::ReviewInProgress, WorkflowEvent::StartReview),
+            vec![
+                WorkflowAction::Broadcast(CoordinationMessage::StartReview),
+                WorkflowAction::LaunchAssistant {
+                    role: PaneRole::Overseer,
+                    prompt: PromptKind::Review,
+                },
+            ]
+        );
+        assert!(entry_actions(WorkflowPhase::Finished, WorkflowEvent::Finish).is_empty());
+        assert!(exit_actions(WorkflowPhase::Initializing, WorkflowEvent::StartPlanning).is_empty());
+    }
+
+    #[test]
+    fn test_every_working_phase_can_be_reset() {
+        for phase in WorkflowPhase::ALL
+            .iter()
+            .filter(|phase| **phase != WorkflowPhase::Initializing)
+        {
+            let transition = find_transition(*phase, WorkflowEvent::Reset)
+                .unwrap_or_else(|| panic!("{:?} cannot be reset", phase));
+            assert_eq!(transition.to, WorkflowPhase::Initializing);
+            assert!(transition.guards.is_empty());
+        }
+        assert!(find_transition(WorkflowPhase::Initializing, WorkflowEvent::Reset).is_none());
+    }
+
+    #[test]
+    fn test_plan_overrides() {
+        let approve = find_transition(
+            WorkflowPhase::PlanningInProgress,
+            WorkflowEvent::ApprovePlan,
+        )
+        .unwrap();
+        assert_eq!(approve.to, WorkflowPhase::PlanReady);
+        // Approving skips validation, but there must be a plan to approve
+        assert_eq!(approve.guards, &[Guard::TodoListExists]);
+
+        for phase in [
+            WorkflowPhase::PlanningInProgress,
+            WorkflowPhase::PlanReady,
+            WorkflowPhase::ImplementationInProgress,
+        ]
+        .iter()
+        {
+            let reject = find_transition(*phase, WorkflowEvent::RejectPlan).unwrap();
+            assert_eq!(reject.to, WorkflowPhase::PlanningInProgress);
+        }
+        assert!(
+            find_transition(WorkflowPhase::ReviewInProgress, WorkflowEvent::RejectPlan).is_none()
+        );
+
+        let force = find_transition(
+            WorkflowPhase::ImplementationInProgress,
+            WorkflowEvent::ForceReview,
+        )
+        .unwrap();
+        assert_eq!(force.to, WorkflowPhase::ImplementationComplete);
+        assert!(force.guards.is_empty());
+    }
+
+    #[test]
+    fn test_override_actions() {
+        assert_eq!(
+            entry_actions(WorkflowPhase::PlanningInProgress, WorkflowEvent::RejectPlan),
+            vec![
+                WorkflowAction::ArchivePlan,
+                WorkflowAction::LaunchAssistant {
+                    role: PaneRole::Overseer,
+                    prompt: PromptKind::Replan,
+                },
+            ]
+        );
+        assert_eq!(
+            entry_actions(WorkflowPhase::Initializing, WorkflowEvent::Reset),
+            vec![
+                WorkflowAction::ArchiveTaskFiles,
+                WorkflowAction::Advance(WorkflowEvent::StartPlanning),
+            ]
+        );
+        // A forced review still chains into the review itself
+        assert_eq!(
+            entry_actions(
+                WorkflowPhase::ImplementationComplete,
+                WorkflowEvent::ForceReview
+            ),
+            vec![WorkflowAction::Advance(WorkflowEvent::StartReview)]
+        );
+
+        // Cut-short phases do not announce their result
+        assert!(
+            exit_actions(WorkflowPhase::PlanningInProgress, WorkflowEvent::RejectPlan).is_empty()
+        );
+        assert!(exit_actions(
+            WorkflowPhase::ImplementationInProgress,
+            WorkflowEvent::ForceReview
+        )
+        .is_empty());
+        assert!(exit_actions(WorkflowPhase::ReviewInProgress, WorkflowEvent::Reset).is_empty());
+        assert_eq!(
+            exit_actions(
+                WorkflowPhase::PlanningInProgress,
+                WorkflowEvent::ApprovePlan
+            ),
+            vec![WorkflowAction::AnnouncePlanReady]
+        );
+    }
+}
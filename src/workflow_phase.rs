@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WorkflowPhase {
     Initializing,
     PlanningInProgress,
@@ -11,3 +11,68 @@ pub enum WorkflowPhase {
     ReviewComplete,
     Finished,
 }
+
+impl WorkflowPhase {
+    /// Every phase in workflow order
+    pub const ALL: [WorkflowPhase; 8] = [
+        WorkflowPhase::Initializing,
+        WorkflowPhase::PlanningInProgress,
+        WorkflowPhase::PlanReady,
+        WorkflowPhase::ImplementationInProgress,
+        WorkflowPhase::ImplementationComplete,
+        WorkflowPhase::ReviewInProgress,
+        WorkflowPhase::ReviewComplete,
+        WorkflowPhase::Finished,
+    ];
+
+    /// Parse a phase name, ignoring case, dashes and underscores
+    /// (`ReviewInProgress`, `review-in-progress`, `review_in_progress`)
+    pub fn from_name(name: &str) -> Option<WorkflowPhase> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_lowercase();
+
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|phase| format!("{:?}", phase).to_lowercase() == normalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name_accepts_common_spellings() {
+        for name in [
+            "ReviewInProgress",
+            "review-in-progress",
+            "review_in_progress",
+            "REVIEWINPROGRESS",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                WorkflowPhase::from_name(name),
+                Some(WorkflowPhase::ReviewInProgress),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_name_roundtrips_every_phase() {
+        for phase in WorkflowPhase::ALL.iter() {
+            assert_eq!(
+                WorkflowPhase::from_name(&format!("{:?}", phase)),
+                Some(*phase)
+            );
+        }
+        assert_eq!(WorkflowPhase::from_name("reviewing"), None);
+        assert_eq!(WorkflowPhase::from_name(""), None);
+    }
+}
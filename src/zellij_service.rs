@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use zellij_tile::prelude::*;
 
 /// Trait for abstracting Zellij API calls to enable testing
@@ -7,6 +8,18 @@ pub trait ZellijService {
 
     /// Send a pipe message to a plugin by name
     fn pipe_message_to_plugin(&self, message: &str, target: &str);
+
+    /// Send a pipe message with the given name to one plugin instance
+    fn pipe_message_to_plugin_id(&self, message: &str, name: &str, plugin_id: u32);
+
+    /// Open a command pane; `context` comes back with its `CommandPaneOpened` event
+    fn open_command_pane(&self, command: CommandToRun, context: BTreeMap<String, String>);
+
+    /// Change the title of a terminal pane
+    fn rename_terminal_pane(&self, terminal_pane_id: u32, name: &str);
+
+    /// Re-run the command of a held terminal pane
+    fn rerun_command_pane(&self, terminal_pane_id: u32);
 }
 
 /// Production implementation that calls real Zellij APIs  
@@ -22,8 +35,31 @@ impl ZellijService for ZellijServiceImpl {
             zellij_tile::prelude::MessageToPlugin::new(target).with_payload(message);
         zellij_tile::prelude::pipe_message_to_plugin(message_to_plugin);
     }
+
+    fn pipe_message_to_plugin_id(&self, message: &str, name: &str, plugin_id: u32) {
+        let message_to_plugin = zellij_tile::prelude::MessageToPlugin::new(name)
+            .with_destination_plugin_id(plugin_id)
+            .with_payload(message);
+        zellij_tile::prelude::pipe_message_to_plugin(message_to_plugin);
+    }
+
+    fn open_command_pane(&self, command: CommandToRun, context: BTreeMap<String, String>) {
+        zellij_tile::prelude::open_command_pane(command, context);
+    }
+
+    fn rename_terminal_pane(&self, terminal_pane_id: u32, name: &str) {
+        zellij_tile::prelude::rename_terminal_pane(terminal_pane_id, name);
+    }
+
+    fn rerun_command_pane(&self, terminal_pane_id: u32) {
+        zellij_tile::prelude::rerun_command_pane(terminal_pane_id);
+    }
 }
 
+// Re-export MockZellijService for use in other test modules
+#[cfg(test)]
+pub use tests::MockZellijService;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,6 +69,16 @@ mod tests {
     pub struct MockZellijService {
         pub sent_messages: RefCell<Vec<(String, PaneId)>>,
         pub piped_messages: RefCell<Vec<(String, String)>>,
+        pub plugin_piped_messages: RefCell<Vec<(String, String, u32)>>,
+        pub opened_panes: RefCell<Vec<(CommandToRun, BTreeMap<String, String>)>>,
+        pub renamed_panes: RefCell<Vec<(u32, String)>>,
+        pub rerun_panes: RefCell<Vec<u32>>,
+    }
+
+    impl Default for MockZellijService {
+        fn default() -> Self {
+            Self::new()
+        }
     }
 
     impl MockZellijService {
@@ -40,6 +86,10 @@ mod tests {
             Self {
                 sent_messages: RefCell::new(Vec::new()),
                 piped_messages: RefCell::new(Vec::new()),
+                plugin_piped_messages: RefCell::new(Vec::new()),
+                opened_panes: RefCell::new(Vec::new()),
+                renamed_panes: RefCell::new(Vec::new()),
+                rerun_panes: RefCell::new(Vec::new()),
             }
         }
 
@@ -53,10 +103,34 @@ mod tests {
             self.piped_messages.borrow().clone()
         }
 
+        /// Get all messages piped to a single plugin, with the pipe name and plugin ID
+        pub fn get_plugin_piped_messages(&self) -> Vec<(String, String, u32)> {
+            self.plugin_piped_messages.borrow().clone()
+        }
+
+        /// Get all command panes opened, with their context
+        pub fn get_opened_panes(&self) -> Vec<(CommandToRun, BTreeMap<String, String>)> {
+            self.opened_panes.borrow().clone()
+        }
+
+        /// Get all terminal panes renamed
+        pub fn get_renamed_panes(&self) -> Vec<(u32, String)> {
+            self.renamed_panes.borrow().clone()
+        }
+
+        /// Get all terminal panes re-run
+        pub fn get_rerun_panes(&self) -> Vec<u32> {
+            self.rerun_panes.borrow().clone()
+        }
+
         /// Clear all recorded messages
         pub fn clear(&self) {
             self.sent_messages.borrow_mut().clear();
             self.piped_messages.borrow_mut().clear();
+            self.plugin_piped_messages.borrow_mut().clear();
+            self.opened_panes.borrow_mut().clear();
+            self.renamed_panes.borrow_mut().clear();
+            self.rerun_panes.borrow_mut().clear();
         }
     }
 
@@ -72,9 +146,27 @@ mod tests {
                 .borrow_mut()
                 .push((message.to_string(), target.to_string()));
         }
+
+        fn pipe_message_to_plugin_id(&self, message: &str, name: &str, plugin_id: u32) {
+            self.plugin_piped_messages.borrow_mut().push((
+                message.to_string(),
+                name.to_string(),
+                plugin_id,
+            ));
+        }
+
+        fn open_command_pane(&self, command: CommandToRun, context: BTreeMap<String, String>) {
+            self.opened_panes.borrow_mut().push((command, context));
+        }
+
+        fn rename_terminal_pane(&self, terminal_pane_id: u32, name: &str) {
+            self.renamed_panes
+                .borrow_mut()
+                .push((terminal_pane_id, name.to_string()));
+        }
+
+        fn rerun_command_pane(&self, terminal_pane_id: u32) {
+            self.rerun_panes.borrow_mut().push(terminal_pane_id);
+        }
     }
 }
-
-// Re-export MockZellijService for use in other test modules
-#[cfg(test)]
-pub use tests::MockZellijService;